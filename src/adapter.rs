@@ -0,0 +1,15 @@
+//! Simple implementations of storages adapting other storages, to provide capabilities they lack.
+
+mod borrowed;
+mod erased;
+mod multi_element;
+mod pool;
+mod single_element;
+mod typed;
+
+pub use borrowed::Borrowed;
+pub use erased::{ErasedElementHandle, ErasedElementStorage, ErasedHandle, ErasedRangeHandle, ErasedRangeStorage};
+pub use multi_element::{MultiElement, MultiElementHandle};
+pub use pool::{Pool, PoolHandle};
+pub use single_element::SingleElement;
+pub use typed::{Typed, TypedHandle, TypedRangeHandle};
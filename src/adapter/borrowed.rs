@@ -0,0 +1,115 @@
+//! Adapter implementation of the element storages, atop a mutably borrowed storage.
+
+use core::{fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
+
+use crate::{
+    error::StorageError,
+    traits::{ElementStorage, MultiElementStorage, SingleElementStorage},
+};
+
+/// Adapter ElementStorage, atop a mutably borrowed storage.
+///
+/// The elements live within the borrowed storage, so that a collection may use an inline storage or arena it does not
+/// own, such as `RawBox<T, adapter::SingleElement<Borrowed<'_, inline::Obstack<S>>>>`.
+pub struct Borrowed<'a, S: ?Sized> {
+    storage: &'a mut S,
+}
+
+impl<'a, S: ?Sized> Borrowed<'a, S> {
+    /// Creates an instance around `storage`.
+    pub fn new(storage: &'a mut S) -> Self { Self { storage } }
+
+    /// Returns the underlying storage.
+    pub fn into_inner(self) -> &'a mut S { self.storage }
+}
+
+impl<'a, S: ?Sized + ElementStorage> ElementStorage for Borrowed<'a, S> {
+    type Handle<T: ?Sized + Pointee> = S::Handle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        self.storage.deallocate(handle)
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.storage.resolve(handle)
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.storage.resolve_mut(handle)
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        self.storage.coerce(handle)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        self.storage.cast(handle)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        self.storage.update_metadata(handle, meta)
+    }
+}
+
+impl<'a, S: ?Sized + SingleElementStorage> SingleElementStorage for Borrowed<'a, S> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        SingleElementStorage::allocate::<T>(self.storage, meta)
+    }
+}
+
+impl<'a, S: ?Sized + MultiElementStorage> MultiElementStorage for Borrowed<'a, S> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        MultiElementStorage::allocate::<T>(self.storage, meta)
+    }
+}
+
+impl<'a, S: ?Sized + Debug> Debug for Borrowed<'a, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Borrowed{{ {:?} }}", self.storage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use crate::collections::RawBox;
+use crate::inline::{Obstack, SingleElement};
+
+use super::*;
+
+#[test]
+fn box_inline() {
+    let mut storage = SingleElement::<[u32; 2]>::new();
+
+    {
+        let boxed = RawBox::new(7u32, Borrowed::new(&mut storage)).unwrap();
+
+        assert_eq!(7, *boxed);
+    }
+
+    //  The storage is usable again, once released.
+    let boxed = RawBox::new(8u32, Borrowed::new(&mut storage)).unwrap();
+
+    assert_eq!(8, *boxed);
+}
+
+#[test]
+fn multi_element() {
+    let mut arena = Obstack::<[u32; 4]>::new();
+
+    {
+        let mut borrowed = Borrowed::new(&mut arena);
+
+        let handle = MultiElementStorage::create(&mut borrowed, 3u32).unwrap();
+
+        assert_eq!(3, unsafe { *borrowed.resolve(handle).as_ref() });
+
+        unsafe { borrowed.deallocate(handle) };
+    }
+
+    assert_eq!(0, arena.used());
+}
+
+} // mod tests
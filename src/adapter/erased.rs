@@ -0,0 +1,443 @@
+//! Adapter implementation of the element and range storages, atop an object-safe `ErasedElementStorage`, or
+//! `ErasedRangeStorage`.
+
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug},
+    marker::{PhantomData, Unsize},
+    mem::{self, MaybeUninit},
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::{
+    error::StorageError,
+    traits::{ElementStorage, MultiElementStorage, RangeStorage, SingleElementStorage, SingleRangeStorage, Storage},
+    utils,
+};
+
+/// An object-safe storage, with untyped handles and a `Layout`-based API.
+///
+/// The element storages are generic over the type of their elements, and thus cannot be used as trait objects. Instead,
+/// the element storages are implemented for `&mut E`, where `E: ErasedElementStorage` may be unsized, so that a
+/// collection may be instantiated once for all storages, such as `RawBox<T, &mut dyn ErasedElementStorage>`.
+///
+/// It is implemented for any untyped `Storage`, as long as its handles fit within an `ErasedHandle`.
+pub trait ErasedElementStorage {
+    /// Allocates a block suitable for `layout`.
+    ///
+    /// This may fail if memory cannot be allocated for it, or if the handle of the underlying storage does not fit.
+    fn allocate(&mut self, layout: Layout) -> Result<ErasedHandle, StorageError>;
+
+    /// Deallocates the block associated to `handle`.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `layout` is the layout it was
+    ///     allocated with.
+    /// -   This invalidates `handle`, and all of its copies.
+    unsafe fn deallocate(&mut self, handle: ErasedHandle, layout: Layout);
+
+    /// Gets a pointer to the block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   The pointer is only valid as long as the storage is not moved and the `handle` remains valid.
+    unsafe fn resolve(&self, handle: ErasedHandle) -> NonNull<u8>;
+
+    /// Gets a pointer to the block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   The pointer is only valid as long as the storage is not moved and the `handle` remains valid.
+    unsafe fn resolve_mut(&mut self, handle: ErasedHandle) -> NonNull<u8>;
+
+    /// Attempts to shrink the block associated to `handle` to `new_layout`, preserving its leading bytes.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `old_layout` is the layout it was
+    ///     allocated with.
+    unsafe fn try_shrink(&mut self, handle: ErasedHandle, old_layout: Layout, new_layout: Layout)
+        -> Result<ErasedHandle, StorageError>;
+}
+
+impl<S: Storage> ErasedElementStorage for S {
+    fn allocate(&mut self, layout: Layout) -> Result<ErasedHandle, StorageError> {
+        if !fits::<S::Handle>() {
+            return Err(StorageError::unsupported());
+        }
+
+        let handle = Storage::allocate(self, layout)?;
+
+        //  Safety:
+        //  -   The handle fits, as checked above.
+        Ok(unsafe { ErasedHandle::erase(handle) })
+    }
+
+    unsafe fn deallocate(&mut self, handle: ErasedHandle, layout: Layout) {
+        Storage::deallocate(self, handle.restore(), layout)
+    }
+
+    unsafe fn resolve(&self, handle: ErasedHandle) -> NonNull<u8> { Storage::resolve(self, handle.restore()) }
+
+    unsafe fn resolve_mut(&mut self, handle: ErasedHandle) -> NonNull<u8> {
+        Storage::resolve_mut(self, handle.restore())
+    }
+
+    unsafe fn try_shrink(&mut self, handle: ErasedHandle, old_layout: Layout, new_layout: Layout)
+        -> Result<ErasedHandle, StorageError>
+    {
+        let handle = Storage::try_shrink(self, handle.restore(), old_layout, new_layout)?;
+
+        //  Safety:
+        //  -   The handle fits, as it was erased on allocation.
+        Ok(ErasedHandle::erase(handle))
+    }
+}
+
+impl<E: ?Sized + ErasedElementStorage> ElementStorage for &mut E {
+    type Handle<T: ?Sized + Pointee> = ErasedElementHandle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        ErasedElementStorage::deallocate(&mut **self, handle.0, utils::layout_of::<T>(handle.1));
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        NonNull::from_raw_parts(ErasedElementStorage::resolve(&**self, handle.0).cast::<()>(), handle.1)
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        NonNull::from_raw_parts(ErasedElementStorage::resolve_mut(&mut **self, handle.0).cast::<()>(), handle.1)
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid.
+        let element = ElementStorage::resolve(self, handle);
+
+        let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
+
+        ErasedElementHandle(handle.0, meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        ErasedElementHandle(handle.0, handle.1)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let (old_layout, new_layout) = (utils::layout_of::<T>(handle.1), utils::layout_of::<T>(meta));
+        let new_handle = ErasedElementStorage::try_shrink(&mut **self, handle.0, old_layout, new_layout)?;
+
+        Ok(ErasedElementHandle(new_handle, meta))
+    }
+}
+
+impl<E: ?Sized + ErasedElementStorage> SingleElementStorage for &mut E {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let handle = ErasedElementStorage::allocate(&mut **self, utils::layout_of::<T>(meta))?;
+
+        Ok(ErasedElementHandle(handle, meta))
+    }
+}
+
+impl<E: ?Sized + ErasedElementStorage> MultiElementStorage for &mut E {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let handle = ErasedElementStorage::allocate(&mut **self, utils::layout_of::<T>(meta))?;
+
+        Ok(ErasedElementHandle(handle, meta))
+    }
+}
+
+/// An object-safe storage, with untyped handles and a `Layout`-based API, for ranges.
+///
+/// The range storages are generic over the type of their elements, and thus cannot be used as trait objects. Instead,
+/// the range storages are implemented for `&mut E`, where `E: ErasedRangeStorage` may be unsized, so that a collection
+/// may be instantiated once for all storages, such as `RawVec<T, &mut dyn ErasedRangeStorage>`.
+///
+/// It is implemented for any untyped `Storage`, as long as its handles fit within an `ErasedHandle`.
+pub trait ErasedRangeStorage {
+    /// Allocates a block suitable for `layout`.
+    ///
+    /// This may fail if memory cannot be allocated for it, or if the handle of the underlying storage does not fit.
+    fn allocate(&mut self, layout: Layout) -> Result<ErasedHandle, StorageError>;
+
+    /// Deallocates the block associated to `handle`.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `layout` is the layout it was
+    ///     allocated with.
+    /// -   This invalidates `handle`, and all of its copies.
+    unsafe fn deallocate(&mut self, handle: ErasedHandle, layout: Layout);
+
+    /// Gets a pointer to the block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   The pointer is only valid as long as the storage is not moved and the `handle` remains valid.
+    unsafe fn resolve(&self, handle: ErasedHandle) -> NonNull<u8>;
+
+    /// Gets a pointer to the block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   The pointer is only valid as long as the storage is not moved and the `handle` remains valid.
+    unsafe fn resolve_mut(&mut self, handle: ErasedHandle) -> NonNull<u8>;
+
+    /// Attempts to grow the block associated to `handle` to `new_layout`, preserving its bytes.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `old_layout` is the layout it was
+    ///     allocated with.
+    unsafe fn try_grow(&mut self, handle: ErasedHandle, old_layout: Layout, new_layout: Layout)
+        -> Result<ErasedHandle, StorageError>;
+
+    /// Attempts to shrink the block associated to `handle` to `new_layout`, preserving its leading bytes.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `old_layout` is the layout it was
+    ///     allocated with.
+    unsafe fn try_shrink(&mut self, handle: ErasedHandle, old_layout: Layout, new_layout: Layout)
+        -> Result<ErasedHandle, StorageError>;
+}
+
+impl<S: Storage> ErasedRangeStorage for S {
+    fn allocate(&mut self, layout: Layout) -> Result<ErasedHandle, StorageError> {
+        ErasedElementStorage::allocate(self, layout)
+    }
+
+    unsafe fn deallocate(&mut self, handle: ErasedHandle, layout: Layout) {
+        Storage::deallocate(self, handle.restore(), layout)
+    }
+
+    unsafe fn resolve(&self, handle: ErasedHandle) -> NonNull<u8> { Storage::resolve(self, handle.restore()) }
+
+    unsafe fn resolve_mut(&mut self, handle: ErasedHandle) -> NonNull<u8> {
+        Storage::resolve_mut(self, handle.restore())
+    }
+
+    unsafe fn try_grow(&mut self, handle: ErasedHandle, old_layout: Layout, new_layout: Layout)
+        -> Result<ErasedHandle, StorageError>
+    {
+        let handle = Storage::try_grow(self, handle.restore(), old_layout, new_layout)?;
+
+        //  Safety:
+        //  -   The handle fits, as it was erased on allocation.
+        Ok(ErasedHandle::erase(handle))
+    }
+
+    unsafe fn try_shrink(&mut self, handle: ErasedHandle, old_layout: Layout, new_layout: Layout)
+        -> Result<ErasedHandle, StorageError>
+    {
+        ErasedElementStorage::try_shrink(self, handle, old_layout, new_layout)
+    }
+}
+
+impl<E: ?Sized + ErasedRangeStorage> RangeStorage for &mut E {
+    type Handle<T> = ErasedRangeHandle<T>;
+
+    type Capacity = usize;
+
+    fn maximum_capacity<T>(&self) -> Self::Capacity { usize::MAX }
+
+    unsafe fn deallocate<T>(&mut self, handle: Self::Handle<T>) {
+        ErasedRangeStorage::deallocate(&mut **self, handle.0, layout_for::<T>(handle.1));
+    }
+
+    unsafe fn resolve<T>(&self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
+        NonNull::slice_from_raw_parts(ErasedRangeStorage::resolve(&**self, handle.0).cast(), handle.1)
+    }
+
+    unsafe fn resolve_mut<T>(&mut self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
+        NonNull::slice_from_raw_parts(ErasedRangeStorage::resolve_mut(&mut **self, handle.0).cast(), handle.1)
+    }
+
+    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let (old_layout, new_layout) = (layout_for::<T>(handle.1), utils::array_layout::<T>(new_capacity)?);
+        let new_handle = ErasedRangeStorage::try_grow(&mut **self, handle.0, old_layout, new_layout)?;
+
+        Ok(ErasedRangeHandle(new_handle, new_capacity, PhantomData))
+    }
+
+    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let (old_layout, new_layout) = (layout_for::<T>(handle.1), layout_for::<T>(new_capacity));
+        let new_handle = ErasedRangeStorage::try_shrink(&mut **self, handle.0, old_layout, new_layout)?;
+
+        Ok(ErasedRangeHandle(new_handle, new_capacity, PhantomData))
+    }
+}
+
+impl<E: ?Sized + ErasedRangeStorage> SingleRangeStorage for &mut E {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let handle = ErasedRangeStorage::allocate(&mut **self, utils::array_layout::<T>(capacity)?)?;
+
+        Ok(ErasedRangeHandle(handle, capacity, PhantomData))
+    }
+}
+
+/// An untyped handle, issued by an `ErasedElementStorage`.
+///
+/// The handle of the underlying storage is stored within, and thus must fit within 2 `usize`.
+#[derive(Clone, Copy)]
+pub struct ErasedHandle(MaybeUninit<[usize; 2]>);
+
+impl Debug for ErasedHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "ErasedHandle")
+    }
+}
+
+/// The element Handle for `&mut E`, where `E: ErasedElementStorage`.
+pub struct ErasedElementHandle<T: ?Sized + Pointee>(ErasedHandle, T::Metadata);
+
+impl<T: ?Sized + Pointee> Clone for ErasedElementHandle<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized + Pointee> Copy for ErasedElementHandle<T> {}
+
+impl<T: ?Sized + Pointee> Debug for ErasedElementHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "ErasedElementHandle")
+    }
+}
+
+/// The range Handle for `&mut E`, where `E: ErasedRangeStorage`.
+pub struct ErasedRangeHandle<T>(ErasedHandle, usize, PhantomData<fn(T) -> T>);
+
+impl<T> Clone for ErasedRangeHandle<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for ErasedRangeHandle<T> {}
+
+impl<T> Debug for ErasedRangeHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "ErasedRangeHandle({})", self.1)
+    }
+}
+
+//
+//  Implementation
+//
+
+impl ErasedHandle {
+    //  #   Safety
+    //
+    //  -   Assumes `fits::<H>()`.
+    unsafe fn erase<H: Copy>(handle: H) -> Self {
+        let mut erased = MaybeUninit::uninit();
+        ptr::write(erased.as_mut_ptr() as *mut H, handle);
+        Self(erased)
+    }
+
+    //  #   Safety
+    //
+    //  -   Assumes `self` was obtained by `erase::<H>`.
+    unsafe fn restore<H: Copy>(self) -> H { ptr::read(self.0.as_ptr() as *const H) }
+}
+
+//  Returns the layout of a range of `capacity` elements, which is assumed to have been validated on allocation.
+fn layout_for<T>(capacity: usize) -> Layout { Layout::array::<T>(capacity).expect("Valid capacity") }
+
+//  Returns whether a handle of type `H` can be erased.
+const fn fits<H>() -> bool {
+    mem::size_of::<H>() <= mem::size_of::<ErasedHandle>() && mem::align_of::<H>() <= mem::align_of::<ErasedHandle>()
+}
+
+#[cfg(test)]
+mod tests {
+
+use crate::allocator::MultiElement;
+use crate::collections::{RawBox, RawLinkedList, RawVec};
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn raw_box() {
+    let allocator = SpyAllocator::default();
+    let mut storage = MultiElement::new(allocator.clone());
+
+    {
+        let erased: &mut dyn ErasedElementStorage = &mut storage;
+
+        let boxed: RawBox<dyn Debug, _> = RawBox::new([1u8, 2, 3], erased).ok().unwrap().coerce();
+
+        assert_eq!("RawBox{ [1, 2, 3] }", format!("{:?}", boxed));
+        assert_eq!(1, allocator.allocated());
+    }
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn raw_box_truncate() {
+    let allocator = SpyAllocator::default();
+    let mut storage = MultiElement::new(allocator.clone());
+
+    let boxed: RawBox<[String], &mut dyn ErasedElementStorage> =
+        RawBox::new(["1".to_string(), "2".to_string()], &mut storage as _).ok().unwrap().coerce();
+
+    let boxed = RawBox::truncate(boxed, 1).ok().unwrap();
+
+    assert_eq!(["1".to_string()], &*boxed);
+
+    drop(boxed);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn linked_list() {
+    let allocator = SpyAllocator::default();
+    let mut storage = MultiElement::new(allocator.clone());
+
+    let mut list = RawLinkedList::new(&mut storage as &mut dyn ErasedElementStorage);
+
+    for i in 0..4 {
+        list.push(i.to_string()).unwrap();
+    }
+
+    assert_eq!(Some("3".to_string()), list.pop());
+    assert_eq!(4, allocator.allocated());
+
+    drop(list);
+
+    assert_eq!(4, allocator.deallocated());
+}
+
+#[test]
+fn raw_vec() {
+    let allocator = SpyAllocator::default();
+    let mut storage = MultiElement::new(allocator.clone());
+
+    {
+        let mut vec = RawVec::new(&mut storage as &mut dyn ErasedRangeStorage);
+
+        for i in 0..5 {
+            vec.push(i.to_string());
+        }
+
+        assert_eq!(Some("4".to_string()), vec.pop());
+        assert_eq!(&["0".to_string(), "1".to_string(), "2".to_string(), "3".to_string()], &*vec);
+    }
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+} // mod tests
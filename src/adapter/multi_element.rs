@@ -0,0 +1,521 @@
+//! Adapter implementation of `MultiElementStorage`, atop a `SingleRangeStorage`.
+
+use core::{
+    fmt::{self, Debug},
+    marker::Unsize,
+    mem,
+    num::NonZeroUsize,
+    ops::CoerceUnsized,
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::{
+    error::StorageError,
+    traits::{Capacity, ClearableStorage, ElementStorage, MultiElementStorage, SingleRangeStorage},
+    utils::{self, OffsetMeta},
+};
+
+/// Adapter MultiElementStorage, sub-allocating from a single range.
+///
+/// A single range is allocated from the underlying `SingleRangeStorage`, within which elements are allocated using an
+/// address-ordered first-fit free-list, coalescing adjacent free blocks on deallocation.
+///
+/// Memory is handed out in blocks of `BLOCK_SIZE` bytes, which is also the maximum alignment supported.
+pub struct MultiElement<S: SingleRangeStorage> {
+    free: usize,
+    blocks: usize,
+    range: S::Handle<Block>,
+    storage: S,
+}
+
+impl<S: SingleRangeStorage> MultiElement<S> {
+    /// The size, and alignment, of a block.
+    pub const BLOCK_SIZE: usize = mem::size_of::<Block>();
+
+    /// Attempts to create an instance, allocating a range of at least `bytes` bytes from `storage`.
+    ///
+    /// Returns `storage` on failure.
+    pub fn new(mut storage: S, bytes: usize) -> Result<Self, S> {
+        let blocks = round_up_blocks(bytes);
+
+        let capacity = match S::Capacity::from_usize(blocks) {
+            Some(capacity) => capacity,
+            None => return Err(storage),
+        };
+
+        let range = match storage.allocate::<Block>(capacity) {
+            Ok(range) => range,
+            Err(_) => return Err(storage),
+        };
+
+        let mut result = Self { free: INVALID_OFFSET, blocks, range, storage };
+        result.reset();
+
+        Ok(result)
+    }
+}
+
+impl<S: SingleRangeStorage> ElementStorage for MultiElement<S> {
+    type Handle<T: ?Sized + Pointee> = MultiElementHandle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        let size = blocks_of(utils::layout_of::<T>(handle.meta()).size());
+
+        self.release(handle.offset(), size);
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        let range = self.storage.resolve(self.range);
+
+        //  Safety:
+        //  -   `handle.offset()` is within range, as `handle` is assumed to be valid.
+        let pointer: NonNull<()> = NonNull::new_unchecked(range.as_mut_ptr().add(handle.offset())).cast();
+
+        NonNull::from_raw_parts(pointer, handle.meta())
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        let range = self.storage.resolve_mut(self.range);
+
+        //  Safety:
+        //  -   `handle.offset()` is within range, as `handle` is assumed to be valid.
+        let pointer: NonNull<()> = NonNull::new_unchecked(range.as_mut_ptr().add(handle.offset())).cast();
+
+        NonNull::from_raw_parts(pointer, handle.meta())
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid.
+        let element = self.resolve(handle);
+
+        let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
+
+        MultiElementHandle::new(handle.offset(), meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        MultiElementHandle::new(handle.offset(), handle.meta())
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let old_size = blocks_of(utils::layout_of::<T>(handle.meta()).size());
+        let new_size = blocks_of(utils::layout_of::<T>(meta).size());
+
+        if new_size < old_size {
+            self.release(handle.offset() + new_size, old_size - new_size);
+        }
+
+        Ok(MultiElementHandle::new(handle.offset(), meta))
+    }
+}
+
+impl<S: SingleRangeStorage> MultiElementStorage for MultiElement<S> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+
+        if layout.align() > mem::align_of::<Block>() {
+            return Err(StorageError::unsupported_alignment(layout));
+        }
+
+        let size = blocks_of(layout.size());
+
+        let mut previous = INVALID_OFFSET;
+        let mut current = self.free;
+
+        while current != INVALID_OFFSET {
+            //  Safety:
+            //  -   `current` is a free block, within range.
+            let block = unsafe { self.read_block(current) };
+
+            if block.size >= size {
+                let next = if block.size == size {
+                    block.next
+                } else {
+                    let remainder = current + size;
+
+                    //  Safety:
+                    //  -   `remainder` is within the free block.
+                    unsafe { self.write_block(remainder, Block { size: block.size - size, next: block.next }) };
+
+                    remainder
+                };
+
+                if previous == INVALID_OFFSET {
+                    self.free = next;
+                } else {
+                    //  Safety:
+                    //  -   `previous` is a free block, within range.
+                    unsafe {
+                        let mut previous_block = self.read_block(previous);
+                        previous_block.next = next;
+                        self.write_block(previous, previous_block);
+                    }
+                }
+
+                return Ok(MultiElementHandle::new(current, meta));
+            }
+
+            previous = current;
+            current = block.next;
+        }
+
+        Err(StorageError::exhausted(layout))
+    }
+}
+
+impl<S: SingleRangeStorage> ClearableStorage for MultiElement<S> {
+    unsafe fn for_each_handle<T, F: FnMut(&mut Self, Self::Handle<T>)>(&mut self, mut fun: F) {
+        let size = blocks_of(mem::size_of::<T>());
+
+        let mut offset = 0;
+        let mut free = self.free;
+
+        //  The free list being sorted by offset, the elements are the gaps between its blocks.
+        while offset < self.blocks {
+            if offset == free {
+                //  Safety:
+                //  -   `free` is a free block, within range.
+                let block = self.read_block(free);
+
+                offset += block.size;
+                free = block.next;
+            } else {
+                fun(self, MultiElementHandle::new(offset, ()));
+
+                offset += size;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.free = INVALID_OFFSET;
+
+        if self.blocks > 0 {
+            //  Safety:
+            //  -   `0 < self.blocks`.
+            unsafe { self.write_block(0, Block { size: self.blocks, next: INVALID_OFFSET }) };
+            self.free = 0;
+        }
+    }
+}
+
+impl<S: SingleRangeStorage> Debug for MultiElement<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "MultiElement{{ blocks: {}, free: [", self.blocks)?;
+
+        let mut next = self.free;
+        let mut first = true;
+
+        while next != INVALID_OFFSET {
+            //  Safety:
+            //  -   `next` is a free block, within range.
+            let block = unsafe { self.read_block(next) };
+
+            if !first {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}..{}", next, next + block.size)?;
+
+            first = false;
+            next = block.next;
+        }
+
+        write!(f, "] }}")
+    }
+}
+
+impl<S: SingleRangeStorage> Drop for MultiElement<S> {
+    fn drop(&mut self) {
+        //  Safety:
+        //  -   `self.range` is valid.
+        unsafe { self.storage.deallocate(self.range) };
+    }
+}
+
+/// The Handle for MultiElement.
+///
+/// The offset is stored off-by-one, so that an `Option` of the handle is no larger than the handle itself.
+///
+/// The handle coerces implicitly, as per `CoerceUnsized`, and so do the boxes using it.
+pub struct MultiElementHandle<T: ?Sized + Pointee>(OffsetMeta<T>);
+
+impl<T: ?Sized + Pointee> Clone for MultiElementHandle<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized + Pointee> Copy for MultiElementHandle<T> {}
+
+impl<T: ?Sized + Pointee> Debug for MultiElementHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "MultiElementHandle({})", self.offset())
+    }
+}
+
+impl<T, U> CoerceUnsized<MultiElementHandle<U>> for MultiElementHandle<T>
+    where
+        T: ?Sized + Pointee + Unsize<U>,
+        U: ?Sized + Pointee,
+{
+}
+
+//
+//  Implementation
+//
+
+const INVALID_OFFSET: usize = usize::MAX;
+
+impl<T: ?Sized + Pointee> MultiElementHandle<T> {
+    //  `offset` is within the range, hence cannot saturate.
+    fn new(offset: usize, meta: T::Metadata) -> Self {
+        Self(OffsetMeta::new(NonZeroUsize::MIN.saturating_add(offset), meta))
+    }
+
+    fn offset(&self) -> usize { self.0.offset().get() - 1 }
+
+    fn meta(&self) -> T::Metadata { self.0.meta() }
+}
+
+//  A block of memory, which doubles as the header of a free block.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Block {
+    //  Size, in blocks, of the free block.
+    size: usize,
+    //  Offset, in blocks, of the next free block.
+    next: usize,
+}
+
+//  Returns the number of blocks necessary to hold `bytes`.
+fn round_up_blocks(bytes: usize) -> usize {
+    let size = mem::size_of::<Block>();
+
+    match bytes % size {
+        0 => bytes / size,
+        _ => bytes / size + 1,
+    }
+}
+
+//  Returns the number of blocks necessary to hold an element of `bytes`.
+fn blocks_of(bytes: usize) -> usize {
+    //  Even zero-sized elements are given a block, so as to have distinct offsets.
+    round_up_blocks(bytes).max(1)
+}
+
+impl<S: SingleRangeStorage> MultiElement<S> {
+    //  Returns the `size` blocks at `offset` to the free list, coalescing them with adjacent free blocks.
+    //
+    //  #   Safety
+    //
+    //  -   Assumes `offset..offset + size` is within range, and allocated.
+    unsafe fn release(&mut self, offset: usize, size: usize) {
+        //  Find insertion point, keeping the free list sorted by offset.
+        let mut previous = INVALID_OFFSET;
+        let mut next = self.free;
+
+        while next != INVALID_OFFSET && next < offset {
+            previous = next;
+            next = self.read_block(next).next;
+        }
+
+        let mut block = Block { size, next };
+
+        //  Coalesce with next.
+        if next != INVALID_OFFSET && offset + size == next {
+            let next = self.read_block(next);
+            block.size += next.size;
+            block.next = next.next;
+        }
+
+        if previous == INVALID_OFFSET {
+            self.write_block(offset, block);
+            self.free = offset;
+            return;
+        }
+
+        let mut previous_block = self.read_block(previous);
+
+        //  Coalesce with previous.
+        if previous + previous_block.size == offset {
+            previous_block.size += block.size;
+            previous_block.next = block.next;
+        } else {
+            self.write_block(offset, block);
+            previous_block.next = offset;
+        }
+
+        self.write_block(previous, previous_block);
+    }
+
+    //  #   Safety
+    //
+    //  -   Assumes `offset` is within range, and contains a free block.
+    unsafe fn read_block(&self, offset: usize) -> Block {
+        debug_assert!(offset < self.blocks);
+
+        let range = self.storage.resolve(self.range);
+
+        ptr::read(range.as_mut_ptr().add(offset) as *const Block)
+    }
+
+    //  #   Safety
+    //
+    //  -   Assumes `offset` is within range, and is not allocated.
+    unsafe fn write_block(&mut self, offset: usize, block: Block) {
+        debug_assert!(offset < self.blocks);
+
+        let range = self.storage.resolve_mut(self.range);
+
+        ptr::write(range.as_mut_ptr().add(offset) as *mut Block, block)
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::collections::RawLinkedList;
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Storage = MultiElement<SingleRange<usize, [usize; 2], 8>>;
+
+#[test]
+fn new_success() {
+    let storage = Storage::new(SingleRange::new(), 100).unwrap();
+
+    assert_eq!("MultiElement{ blocks: 7, free: [0..7] }", format!("{:?}", storage));
+}
+
+#[test]
+fn new_failure() {
+    Storage::new(SingleRange::new(), 129).unwrap_err();
+}
+
+#[test]
+fn allocate_deallocate_coalesce() {
+    let mut storage = Storage::new(SingleRange::new(), 64).unwrap();
+
+    let h1 = storage.create(1u8).unwrap();
+    let h2 = storage.create([2u64; 3]).unwrap();
+    let h3 = storage.create(3u32).unwrap();
+
+    assert_eq!("MultiElement{ blocks: 4, free: [] }", format!("{:?}", storage));
+    storage.create(4u8).unwrap_err();
+
+    assert_eq!(1, unsafe { *storage.resolve(h1).as_ref() });
+    assert_eq!([2; 3], unsafe { *storage.resolve(h2).as_ref() });
+    assert_eq!(3, unsafe { *storage.resolve(h3).as_ref() });
+
+    unsafe { storage.destroy(h1) };
+    unsafe { storage.destroy(h3) };
+
+    assert_eq!("MultiElement{ blocks: 4, free: [0..1, 3..4] }", format!("{:?}", storage));
+    storage.create([4u64; 3]).unwrap_err();
+
+    unsafe { storage.destroy(h2) };
+
+    assert_eq!("MultiElement{ blocks: 4, free: [0..4] }", format!("{:?}", storage));
+    storage.create([4u64; 7]).unwrap();
+}
+
+#[test]
+fn update_metadata_releases_tail() {
+    let mut storage = Storage::new(SingleRange::new(), 64).unwrap();
+
+    let handle = storage.create([1u64, 2, 3, 4, 5, 6]).unwrap();
+    let handle = unsafe { storage.coerce::<[u64], _>(handle) };
+
+    assert_eq!("MultiElement{ blocks: 4, free: [3..4] }", format!("{:?}", storage));
+
+    let handle = unsafe { storage.update_metadata(handle, 2) }.unwrap();
+
+    assert_eq!("MultiElement{ blocks: 4, free: [1..4] }", format!("{:?}", storage));
+    assert_eq!(&[1, 2], unsafe { storage.resolve(handle).as_ref() });
+
+    unsafe { storage.destroy(handle) };
+
+    assert_eq!("MultiElement{ blocks: 4, free: [0..4] }", format!("{:?}", storage));
+}
+
+#[test]
+fn clear_with() {
+    let mut storage = Storage::new(SingleRange::new(), 64).unwrap();
+
+    let handles: Vec<_> = (0..4).map(|i| storage.create(i as u64).unwrap()).collect();
+
+    unsafe { storage.destroy(handles[2]) };
+
+    let mut values = Vec::new();
+    unsafe { storage.clear_with(|pointer: NonNull<u64>| values.push(*pointer.as_ref())) };
+
+    assert_eq!(vec![0, 1, 3], values);
+    assert_eq!("MultiElement{ blocks: 4, free: [0..4] }", format!("{:?}", storage));
+}
+
+#[test]
+fn coerce_unsize() {
+    let mut storage = Storage::new(SingleRange::new(), 64).unwrap();
+
+    let handle = storage.create([1u8, 2]).unwrap();
+
+    let handle = unsafe { storage.coerce::<[u8], _>(handle) };
+    let element = unsafe { storage.resolve(handle) };
+
+    assert_eq!(&[1, 2], unsafe { element.as_ref() });
+}
+
+#[test]
+fn linked_list() {
+    let storage = Storage::new(SingleRange::new(), 128).unwrap();
+    let mut list = RawLinkedList::new(storage);
+
+    list.push(1u32).unwrap();
+    list.push(2u32).unwrap();
+    list.push(3u32).unwrap();
+
+    assert_eq!("[3, 2, 1]", format!("{:?}", list));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn new_failure() {
+    MultiElement::new(SingleRange::new(NonAllocator), 64).unwrap_err();
+}
+
+#[test]
+fn create_success() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = MultiElement::new(SingleRange::new(allocator.clone()), 1024).unwrap();
+
+    let handles: Vec<_> = (0..10).map(|i| storage.create(i.to_string()).unwrap()).collect();
+
+    for (i, handle) in handles.iter().enumerate() {
+        assert_eq!(&i.to_string(), unsafe { storage.resolve(*handle).as_ref() });
+    }
+
+    for handle in handles {
+        unsafe { storage.destroy(handle) };
+    }
+
+    assert_eq!(1, allocator.allocated());
+
+    drop(storage);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+} // mod test_allocator
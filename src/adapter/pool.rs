@@ -0,0 +1,189 @@
+//! Adapter implementation of `MultiElementStorage`, atop a fixed number of `SingleElementStorage`.
+
+use core::{fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
+
+use crate::{
+    error::StorageError,
+    traits::{CloneStorage, ElementStorage, MultiElementStorage, SingleElementStorage},
+    utils,
+};
+
+/// Adapter MultiElementStorage, atop `N` SingleElementStorages.
+///
+/// Each underlying storage holds at most one element, hence at most `N` elements may be live at any one time.
+///
+/// This is the reverse of `adapter::SingleElement`: since a SingleElementStorage only ever holds a single element, a
+/// MultiElementStorage can only be obtained from a bounded pool of them.
+pub struct Pool<S, const N: usize> {
+    occupied: [bool; N],
+    storages: [S; N],
+}
+
+impl<S, const N: usize> Pool<S, N> {
+    /// Creates an instance, atop `storages`.
+    pub fn new(storages: [S; N]) -> Self { Self { occupied: [false; N], storages } }
+
+    /// Returns the number of live elements.
+    pub fn len(&self) -> usize { self.occupied.iter().filter(|o| **o).count() }
+
+    /// Returns whether there is no live element, or not.
+    pub fn is_empty(&self) -> bool { self.occupied.iter().all(|o| !*o) }
+}
+
+impl<S: SingleElementStorage, const N: usize> ElementStorage for Pool<S, N> {
+    type Handle<T: ?Sized + Pointee> = PoolHandle<T, S>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        debug_assert!(self.occupied[handle.0]);
+
+        self.occupied[handle.0] = false;
+        self.storages[handle.0].deallocate(handle.1);
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.storages[handle.0].resolve(handle.1)
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.storages[handle.0].resolve_mut(handle.1)
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        PoolHandle(handle.0, self.storages[handle.0].coerce(handle.1))
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        PoolHandle(handle.0, self.storages[handle.0].cast(handle.1))
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        Ok(PoolHandle(handle.0, self.storages[handle.0].update_metadata(handle.1, meta)?))
+    }
+}
+
+impl<S: SingleElementStorage, const N: usize> MultiElementStorage for Pool<S, N> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let index = self.occupied.iter().position(|o| !*o)
+            .ok_or_else(|| StorageError::exhausted(utils::layout_of::<T>(meta)))?;
+
+        let handle = self.storages[index].allocate::<T>(meta)?;
+        self.occupied[index] = true;
+
+        Ok(PoolHandle(index, handle))
+    }
+}
+
+impl<S: CloneStorage, const N: usize> CloneStorage for Pool<S, N> {
+    fn clone_storage(&self) -> Self { Self::new(self.storages.each_ref().map(S::clone_storage)) }
+}
+
+impl<S, const N: usize> Debug for Pool<S, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Pool{{ occupied: {:?} }}", self.occupied)
+    }
+}
+
+impl<S: Default, const N: usize> Default for Pool<S, N> {
+    fn default() -> Self { Self::new([(); N].map(|_| S::default())) }
+}
+
+/// The Handle for Pool.
+pub struct PoolHandle<T: ?Sized + Pointee, S: ElementStorage>(usize, S::Handle<T>);
+
+impl<T: ?Sized + Pointee, S: ElementStorage> Clone for PoolHandle<T, S> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized + Pointee, S: ElementStorage> Copy for PoolHandle<T, S> {}
+
+impl<T: ?Sized + Pointee, S: ElementStorage> Debug for PoolHandle<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "PoolHandle({})", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use core::mem::MaybeUninit;
+
+use crate::collections::RawLinkedList;
+use crate::inline::SingleElement;
+
+use super::*;
+
+type Storage = Pool<SingleElement<[usize; 3]>, 3>;
+
+#[test]
+fn create_deallocate() {
+    let mut storage = Storage::default();
+
+    let h1 = storage.create(1u8).unwrap();
+    let h2 = storage.create(2u32).unwrap();
+    let h3 = storage.create([3u64; 3]).unwrap();
+
+    storage.create(4u8).unwrap_err();
+
+    assert_eq!(3, storage.len());
+    assert_eq!(1, unsafe { *storage.resolve(h1).as_ref() });
+    assert_eq!(2, unsafe { *storage.resolve(h2).as_ref() });
+    assert_eq!([3; 3], unsafe { *storage.resolve(h3).as_ref() });
+
+    unsafe { storage.destroy(h2) };
+
+    assert_eq!("Pool{ occupied: [true, false, true] }", format!("{:?}", storage));
+
+    let h4 = storage.create(4u8).unwrap();
+
+    assert_eq!(4, unsafe { *storage.resolve(h4).as_ref() });
+}
+
+#[test]
+fn create_insufficient_size() {
+    let mut storage = Storage::default();
+
+    storage.create([1u64; 4]).unwrap_err();
+
+    assert!(storage.is_empty());
+}
+
+#[test]
+fn allocate_n_failure() {
+    let mut storage = Storage::default();
+    let mut handles = [MaybeUninit::uninit(); 4];
+
+    storage.allocate_n::<u32>(&mut handles).unwrap_err();
+
+    assert!(storage.is_empty());
+
+    assert_eq!(3, storage.allocate_n::<u32>(&mut handles[..3]).unwrap().len());
+    assert_eq!(3, storage.len());
+}
+
+#[test]
+fn coerce_unsize() {
+    let mut storage = Storage::default();
+
+    let handle = storage.create([1u8, 2]).unwrap();
+
+    let handle = unsafe { storage.coerce::<[u8], _>(handle) };
+    let element = unsafe { storage.resolve(handle) };
+
+    assert_eq!(&[1, 2], unsafe { element.as_ref() });
+}
+
+#[test]
+fn linked_list() {
+    let mut list = RawLinkedList::new(Storage::default());
+
+    list.push(1u32).unwrap();
+    list.push(2u32).unwrap();
+    list.push(3u32).unwrap();
+
+    assert_eq!(Err(4), list.push(4u32));
+    assert_eq!("[3, 2, 1]", format!("{:?}", list));
+}
+
+} // mod tests
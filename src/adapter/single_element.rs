@@ -0,0 +1,231 @@
+//! Adapter implementation of `SingleElementStorage`, atop a `MultiElementStorage`.
+
+use core::{fmt::{self, Debug}, marker::Unsize, mem::{self, MaybeUninit}, ptr::{self, NonNull, Pointee}};
+
+use crate::{
+    error::StorageError,
+    traits::{CloneStorage, ElementStorage, MultiElementStorage, PinningStorage, SingleElementStorage},
+};
+
+/// Adapter SingleElementStorage, atop a MultiElementStorage.
+///
+/// The one live handle is tracked, so that it is deallocated when a new element is allocated -- overwriting the
+/// previous one, as per `SingleElementStorage` -- or when the adapter is dropped.
+///
+/// In order to be tracked, the handles of `M` must fit within `HANDLE_SIZE` bytes, and be no more aligned than `usize`;
+/// allocating elements whose handle would not fit fails.
+pub struct SingleElement<M: MultiElementStorage> {
+    live: Option<LiveHandle<M>>,
+    storage: M,
+}
+
+impl<M: MultiElementStorage> SingleElement<M> {
+    /// The maximum size of a handle of `M`, in bytes.
+    pub const HANDLE_SIZE: usize = mem::size_of::<HandleBuffer>();
+
+    /// Creates an instance, atop `storage`.
+    pub fn new(storage: M) -> Self { Self { live: None, storage } }
+
+    /// Returns the underlying storage.
+    ///
+    /// Any live element is deallocated, without being dropped.
+    pub fn into_inner(mut self) -> M {
+        self.deallocate_live();
+
+        //  Safety:
+        //  -   `self.storage` is valid, and the immediate `forget` avoids double-drops.
+        let storage = unsafe { ptr::read(&self.storage) };
+        mem::forget(self);
+
+        storage
+    }
+}
+
+impl<M: MultiElementStorage> ElementStorage for SingleElement<M> {
+    type Handle<T: ?Sized + Pointee> = M::Handle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        self.live = None;
+
+        self.storage.deallocate(handle);
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.storage.resolve(handle)
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.storage.resolve_mut(handle)
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        self.storage.coerce(handle)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        self.storage.cast(handle)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let handle = self.storage.update_metadata(handle, meta)?;
+
+        if let Some(live) = &mut self.live {
+            //  Safety:
+            //  -   `live.buffer` contains the previous handle, hence is suitably sized and aligned for `handle`.
+            ptr::write(live.buffer.as_mut_ptr() as *mut M::Handle<T>, handle);
+        }
+
+        Ok(handle)
+    }
+}
+
+impl<M: MultiElementStorage> SingleElementStorage for SingleElement<M> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        if mem::size_of::<M::Handle<T>>() > mem::size_of::<HandleBuffer>() ||
+            mem::align_of::<M::Handle<T>>() > mem::align_of::<HandleBuffer>()
+        {
+            return Err(StorageError::unsupported());
+        }
+
+        let handle = self.storage.allocate::<T>(meta)?;
+
+        //  The previous element, if any, is only deallocated once the new one is allocated, so that it remains valid
+        //  on failure.
+        self.deallocate_live();
+
+        let mut buffer = MaybeUninit::<HandleBuffer>::uninit();
+
+        //  Safety:
+        //  -   `buffer` is suitably sized and aligned, as checked above.
+        unsafe { ptr::write(buffer.as_mut_ptr() as *mut M::Handle<T>, handle) };
+
+        self.live = Some(LiveHandle { buffer, deallocate: deallocate_erased::<M, T> });
+
+        Ok(handle)
+    }
+}
+
+//  Safety:
+//  -   The pointers are resolved by the underlying storage, which does not move its elements when moved.
+unsafe impl<M: MultiElementStorage + PinningStorage> PinningStorage for SingleElement<M> {}
+
+impl<M: CloneStorage + MultiElementStorage> CloneStorage for SingleElement<M> {
+    fn clone_storage(&self) -> Self { Self::new(self.storage.clone_storage()) }
+}
+
+impl<M: MultiElementStorage> Debug for SingleElement<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "SingleElement{{ live: {} }}", self.live.is_some())
+    }
+}
+
+impl<M: Default + MultiElementStorage> Default for SingleElement<M> {
+    fn default() -> Self { Self::new(M::default()) }
+}
+
+impl<M: MultiElementStorage> Drop for SingleElement<M> {
+    fn drop(&mut self) { self.deallocate_live(); }
+}
+
+//
+//  Implementation
+//
+
+type HandleBuffer = [usize; 4];
+
+//  A type-erased handle, and the means to deallocate it.
+struct LiveHandle<M> {
+    buffer: MaybeUninit<HandleBuffer>,
+    deallocate: unsafe fn(&mut M, &MaybeUninit<HandleBuffer>),
+}
+
+impl<M: MultiElementStorage> SingleElement<M> {
+    fn deallocate_live(&mut self) {
+        if let Some(live) = self.live.take() {
+            //  Safety:
+            //  -   `live.buffer` contains the handle `live.deallocate` was instantiated for.
+            //  -   The handle is valid, as it is still tracked.
+            unsafe { (live.deallocate)(&mut self.storage, &live.buffer) };
+        }
+    }
+}
+
+//  #   Safety
+//
+//  -   Assumes `buffer` contains a valid `M::Handle<T>`.
+unsafe fn deallocate_erased<M: MultiElementStorage, T: ?Sized + Pointee>(storage: &mut M, buffer: &MaybeUninit<HandleBuffer>) {
+    let handle = ptr::read(buffer.as_ptr() as *const M::Handle<T>);
+
+    storage.deallocate(handle);
+}
+
+#[cfg(test)]
+mod tests {
+
+use crate::allocator::MultiElement;
+use crate::collections::RawBox;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn create_failure() {
+    let mut storage = SingleElement::new(MultiElement::new(NonAllocator));
+    storage.create(1u8).unwrap_err();
+}
+
+#[test]
+fn create_overwrite() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = SingleElement::new(MultiElement::new(allocator.clone()));
+
+    let _ = storage.create(1u8).unwrap();
+    let handle = storage.create(2u32).unwrap();
+
+    assert_eq!(2, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+    assert_eq!(2, unsafe { *storage.resolve(handle).as_ref() });
+
+    unsafe { storage.destroy(handle) };
+
+    assert_eq!(2, allocator.deallocated());
+
+    drop(storage);
+
+    assert_eq!(2, allocator.deallocated());
+}
+
+#[test]
+fn drop_deallocates() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = SingleElement::new(MultiElement::new(allocator.clone()));
+
+    let _ = storage.create(1u8).unwrap();
+
+    drop(storage);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn raw_box() {
+    let allocator = SpyAllocator::default();
+
+    let storage = SingleElement::new(MultiElement::new(allocator.clone()));
+
+    let boxed = RawBox::new([1u8, 2, 3], storage).unwrap();
+    let boxed: RawBox<[u8], _> = boxed.coerce();
+
+    assert_eq!([1, 2, 3], &*boxed);
+
+    drop(boxed);
+
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+}
+
+} // mod tests
@@ -0,0 +1,238 @@
+//! Adapter implementation of the typed storages, atop an untyped `Storage`.
+
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug},
+    marker::{PhantomData, Unsize},
+    mem::MaybeUninit,
+    ptr::{NonNull, Pointee},
+};
+
+use crate::{
+    error::StorageError,
+    traits::{
+        CloneStorage, ElementStorage, MultiElementStorage, MultiRangeStorage, PinningStorage, RangeStorage, Storage,
+    },
+    utils,
+};
+
+/// Adapter MultiElementStorage and MultiRangeStorage, layering types atop an untyped `Storage`.
+///
+/// The handles record the meta-data of the element, or the capacity of the range, so as to compute the layout of the
+/// block when needed.
+pub struct Typed<S> {
+    storage: S,
+}
+
+impl<S> Typed<S> {
+    /// Creates an instance around `storage`.
+    pub fn new(storage: S) -> Self { Self { storage } }
+
+    /// Returns the underlying storage.
+    pub fn into_inner(self) -> S { self.storage }
+}
+
+impl<S: Storage> ElementStorage for Typed<S> {
+    type Handle<T: ?Sized + Pointee> = TypedHandle<S::Handle, T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        self.storage.deallocate(handle.0, utils::layout_of::<T>(handle.1));
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        NonNull::from_raw_parts(self.storage.resolve(handle.0).cast::<()>(), handle.1)
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        NonNull::from_raw_parts(self.storage.resolve_mut(handle.0).cast::<()>(), handle.1)
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid.
+        let element = ElementStorage::resolve(self, handle);
+
+        let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
+
+        TypedHandle(handle.0, meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        TypedHandle(handle.0, handle.1)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let (old_layout, new_layout) = (utils::layout_of::<T>(handle.1), utils::layout_of::<T>(meta));
+        let new_handle = self.storage.try_shrink(handle.0, old_layout, new_layout)?;
+
+        Ok(TypedHandle(new_handle, meta))
+    }
+}
+
+impl<S: Storage> MultiElementStorage for Typed<S> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let handle = self.storage.allocate(utils::layout_of::<T>(meta))?;
+
+        Ok(TypedHandle(handle, meta))
+    }
+}
+
+impl<S: Storage> RangeStorage for Typed<S> {
+    type Handle<T> = TypedRangeHandle<S::Handle, T>;
+
+    type Capacity = usize;
+
+    fn maximum_capacity<T>(&self) -> Self::Capacity { usize::MAX }
+
+    unsafe fn deallocate<T>(&mut self, handle: Self::Handle<T>) {
+        self.storage.deallocate(handle.0, layout_for::<T>(handle.1));
+    }
+
+    unsafe fn resolve<T>(&self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
+        NonNull::slice_from_raw_parts(self.storage.resolve(handle.0).cast(), handle.1)
+    }
+
+    unsafe fn resolve_mut<T>(&mut self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
+        NonNull::slice_from_raw_parts(self.storage.resolve_mut(handle.0).cast(), handle.1)
+    }
+
+    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let new_layout = utils::array_layout::<T>(new_capacity)?;
+        let new_handle = self.storage.try_grow(handle.0, layout_for::<T>(handle.1), new_layout)?;
+
+        Ok(TypedRangeHandle(new_handle, new_capacity, PhantomData))
+    }
+
+    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let (old_layout, new_layout) = (layout_for::<T>(handle.1), layout_for::<T>(new_capacity));
+        let new_handle = self.storage.try_shrink(handle.0, old_layout, new_layout)?;
+
+        Ok(TypedRangeHandle(new_handle, new_capacity, PhantomData))
+    }
+}
+
+impl<S: Storage> MultiRangeStorage for Typed<S> {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::array_layout::<T>(capacity)?;
+        let handle = self.storage.allocate(layout)?;
+
+        Ok(TypedRangeHandle(handle, capacity, PhantomData))
+    }
+}
+
+//  Safety:
+//  -   The pointers are resolved by the underlying storage, which does not move its blocks when moved.
+unsafe impl<S: PinningStorage> PinningStorage for Typed<S> {}
+
+impl<S: CloneStorage> CloneStorage for Typed<S> {
+    fn clone_storage(&self) -> Self { Self::new(self.storage.clone_storage()) }
+}
+
+impl<S: Debug> Debug for Typed<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Typed{{ {:?} }}", self.storage)
+    }
+}
+
+impl<S: Default> Default for Typed<S> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+/// The element Handle for Typed.
+pub struct TypedHandle<H, T: ?Sized + Pointee>(H, T::Metadata);
+
+impl<H: Copy, T: ?Sized + Pointee> Clone for TypedHandle<H, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<H: Copy, T: ?Sized + Pointee> Copy for TypedHandle<H, T> {}
+
+impl<H: Debug, T: ?Sized + Pointee> Debug for TypedHandle<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "TypedHandle({:?})", self.0)
+    }
+}
+
+/// The range Handle for Typed.
+pub struct TypedRangeHandle<H, T>(H, usize, PhantomData<fn(T) -> T>);
+
+impl<H: Copy, T> Clone for TypedRangeHandle<H, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<H: Copy, T> Copy for TypedRangeHandle<H, T> {}
+
+impl<H: Debug, T> Debug for TypedRangeHandle<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "TypedRangeHandle({:?}, {})", self.0, self.1)
+    }
+}
+
+//
+//  Implementation
+//
+
+//  Returns the layout of a range of `capacity` elements, which is assumed to have been validated on allocation.
+fn layout_for<T>(capacity: usize) -> Layout { Layout::array::<T>(capacity).expect("Valid capacity") }
+
+#[cfg(test)]
+mod tests {
+
+use crate::allocator::MultiElement;
+use crate::collections::RawLinkedList;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn linked_list() {
+    let allocator = SpyAllocator::default();
+
+    let mut list = RawLinkedList::new(Typed::new(MultiElement::new(allocator.clone())));
+
+    for i in 0..4 {
+        list.push(i.to_string()).unwrap();
+    }
+
+    assert_eq!(Some("3".to_string()), list.pop());
+    assert_eq!(4, allocator.allocated());
+
+    drop(list);
+
+    assert_eq!(4, allocator.deallocated());
+}
+
+#[test]
+fn ranges() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = Typed::new(MultiElement::new(allocator.clone()));
+
+    let first = MultiRangeStorage::allocate::<u32>(&mut storage, 2).unwrap();
+    let second = MultiRangeStorage::allocate::<u32>(&mut storage, 2).unwrap();
+
+    unsafe {
+        let slice = &mut *RangeStorage::resolve_mut(&mut storage, first).as_ptr();
+        slice[0].write(1);
+        slice[1].write(2);
+
+        let first = storage.try_grow(first, 8).unwrap();
+        let slice = &*RangeStorage::resolve(&storage, first).as_ptr();
+
+        assert_eq!(8, slice.len());
+        assert_eq!([1, 2], [slice[0].assume_init(), slice[1].assume_init()]);
+
+        let first = storage.try_shrink(first, 1).unwrap();
+
+        assert_eq!(1, RangeStorage::resolve(&storage, first).len());
+
+        RangeStorage::deallocate(&mut storage, first);
+        RangeStorage::deallocate(&mut storage, second);
+    }
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+} // mod tests
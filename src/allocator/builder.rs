@@ -1,4 +1,5 @@
 //! A builder for allocator storage adaptors
 
 /// Allocator Builder.
+#[derive(Clone)]
 pub struct AllocatorBuilder<A>(pub A);
@@ -1,8 +1,13 @@
 //! Simple implementation of `MultiElementStorage`.
 
-use core::{alloc::{Allocator, AllocError, Layout}, fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
+use core::{alloc::{Allocator, Layout}, fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
 
-use crate::{alternative::Builder, traits::{ElementStorage, MultiElementStorage}, utils};
+use crate::{
+    alternative::Builder,
+    error::StorageError,
+    traits::{CloneStorage, ElementStorage, MultiElementStorage, PinningStorage, Storage},
+    utils,
+};
 
 use super::AllocatorBuilder;
 
@@ -45,11 +50,43 @@ impl<A: Allocator> ElementStorage for MultiElement<A> {
     unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
         handle
     }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        let (pointer, meta) = handle.to_raw_parts();
+
+        NonNull::from_raw_parts(pointer, meta)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let (old_layout, new_layout) = (Layout::for_value_raw(handle.as_ptr()), utils::layout_of::<T>(meta));
+
+        //  Safety:
+        //  -   `handle` was allocated by call to `self.allocator`, with `old_layout`.
+        //  -   `new_layout` is assumed to be no larger than `old_layout`.
+        let slice = self.allocator.shrink(handle.cast(), old_layout, new_layout)
+            .map_err(|_| StorageError::allocator_failure(new_layout))?;
+
+        let pointer: NonNull<()> = slice.as_non_null_ptr().cast();
+
+        Ok(NonNull::from_raw_parts(pointer, meta))
+    }
 }
 
 impl<A: Allocator> MultiElementStorage for MultiElement<A> {
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError> {
-        let slice = self.allocator.allocate(utils::layout_of::<T>(meta))?;
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+        let slice = self.allocator.allocate(layout).map_err(|_| StorageError::allocator_failure(layout))?;
+
+        let pointer: NonNull<()> = slice.as_non_null_ptr().cast();
+
+        Ok(NonNull::from_raw_parts(pointer, meta))
+    }
+
+    fn allocate_zeroed<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+        let slice = self.allocator.allocate_zeroed(layout).map_err(|_| StorageError::allocator_failure(layout))?;
 
         let pointer: NonNull<()> = slice.as_non_null_ptr().cast();
 
@@ -57,6 +94,46 @@ impl<A: Allocator> MultiElementStorage for MultiElement<A> {
     }
 }
 
+impl<A: Allocator> Storage for MultiElement<A> {
+    type Handle = NonNull<u8>;
+
+    fn allocate(&mut self, layout: Layout) -> Result<Self::Handle, StorageError> {
+        self.allocator.allocate(layout)
+            .map(|slice| slice.as_non_null_ptr())
+            .map_err(|_| StorageError::allocator_failure(layout))
+    }
+
+    unsafe fn deallocate(&mut self, handle: Self::Handle, layout: Layout) {
+        //  Safety:
+        //  -   `handle` is valid, and `layout` matches the one used for the allocation.
+        self.allocator.deallocate(handle, layout);
+    }
+
+    unsafe fn resolve(&self, handle: Self::Handle) -> NonNull<u8> { handle }
+
+    unsafe fn resolve_mut(&mut self, handle: Self::Handle) -> NonNull<u8> { handle }
+
+    unsafe fn try_grow(&mut self, handle: Self::Handle, old_layout: Layout, new_layout: Layout)
+        -> Result<Self::Handle, StorageError>
+    {
+        self.allocator.grow(handle, old_layout, new_layout)
+            .map(|slice| slice.as_non_null_ptr())
+            .map_err(|_| StorageError::allocator_failure(new_layout))
+    }
+
+    unsafe fn try_shrink(&mut self, handle: Self::Handle, old_layout: Layout, new_layout: Layout)
+        -> Result<Self::Handle, StorageError>
+    {
+        self.allocator.shrink(handle, old_layout, new_layout)
+            .map(|slice| slice.as_non_null_ptr())
+            .map_err(|_| StorageError::allocator_failure(new_layout))
+    }
+}
+
+//  Safety:
+//  -   Moving an allocator does not invalidate the memory blocks it returned.
+unsafe impl<A: Allocator> PinningStorage for MultiElement<A> {}
+
 impl<A> Builder<MultiElement<A>> for AllocatorBuilder<A> {
     fn from_storage(storage: MultiElement<A>) -> Self { AllocatorBuilder(storage.allocator) }
 
@@ -67,6 +144,10 @@ impl<A: Default> Default for MultiElement<A> {
     fn default() -> Self { Self::new(A::default()) }
 }
 
+impl<A: Clone> CloneStorage for MultiElement<A> {
+    fn clone_storage(&self) -> Self { Self::new(self.allocator.clone()) }
+}
+
 impl<A> Debug for MultiElement<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "MultiElement")
@@ -1,8 +1,13 @@
 //! Simple implementation of `SingleElementStorage<T>`.
 
-use core::{alloc::{Allocator, AllocError, Layout}, fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
+use core::{alloc::{Allocator, Layout}, fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
 
-use crate::{alternative::Builder, traits::{ElementStorage, SingleElementStorage}, utils};
+use crate::{
+    alternative::Builder,
+    error::StorageError,
+    traits::{CloneStorage, ElementStorage, PinningStorage, SingleElementStorage},
+    utils,
+};
 
 use super::AllocatorBuilder;
 
@@ -39,11 +44,43 @@ impl<A: Allocator> ElementStorage for SingleElement<A> {
     unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
         handle
     }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        let (pointer, meta) = handle.to_raw_parts();
+
+        NonNull::from_raw_parts(pointer, meta)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let (old_layout, new_layout) = (Layout::for_value_raw(handle.as_ptr()), utils::layout_of::<T>(meta));
+
+        //  Safety:
+        //  -   `handle` was allocated by call to `self.allocator`, with `old_layout`.
+        //  -   `new_layout` is assumed to be no larger than `old_layout`.
+        let slice = self.allocator.shrink(handle.cast(), old_layout, new_layout)
+            .map_err(|_| StorageError::allocator_failure(new_layout))?;
+
+        let pointer: NonNull<()> = slice.as_non_null_ptr().cast();
+
+        Ok(NonNull::from_raw_parts(pointer, meta))
+    }
 }
 
 impl<A: Allocator> SingleElementStorage for SingleElement<A> {
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError> {
-        let slice = self.allocator.allocate(utils::layout_of::<T>(meta))?;
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+        let slice = self.allocator.allocate(layout).map_err(|_| StorageError::allocator_failure(layout))?;
+
+        let pointer: NonNull<()> = slice.as_non_null_ptr().cast();
+
+        Ok(NonNull::from_raw_parts(pointer, meta))
+    }
+
+    fn allocate_zeroed<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+        let slice = self.allocator.allocate_zeroed(layout).map_err(|_| StorageError::allocator_failure(layout))?;
 
         let pointer: NonNull<()> = slice.as_non_null_ptr().cast();
 
@@ -51,6 +88,10 @@ impl<A: Allocator> SingleElementStorage for SingleElement<A> {
     }
 }
 
+//  Safety:
+//  -   Moving an allocator does not invalidate the memory blocks it returned.
+unsafe impl<A: Allocator> PinningStorage for SingleElement<A> {}
+
 impl<A> Builder<SingleElement<A>> for AllocatorBuilder<A> {
     fn from_storage(storage: SingleElement<A>) -> Self { AllocatorBuilder(storage.allocator) }
 
@@ -64,6 +105,10 @@ impl<A: Default> Default for SingleElement<A> {
     }
 }
 
+impl<A: Clone> CloneStorage for SingleElement<A> {
+    fn clone_storage(&self) -> Self { Self::new(self.allocator.clone()) }
+}
+
 impl<A> Debug for SingleElement<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleElement")
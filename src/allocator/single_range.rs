@@ -1,8 +1,13 @@
 //! Simple implementation of `SingleRangeStorage`.
 
-use core::{alloc::{Allocator, AllocError, Layout}, fmt::{self, Debug}, mem::MaybeUninit, ptr::NonNull};
+use core::{alloc::{Allocator, Layout}, fmt::{self, Debug}, mem::{self, MaybeUninit}, ptr::{self, NonNull}};
 
-use crate::{alternative::Builder, traits::{RangeStorage, SingleRangeStorage}};
+use crate::{
+    alternative::Builder,
+    error::StorageError,
+    traits::{CloneStorage, PinningStorage, RangeStorage, SingleRangeStorage},
+    utils,
+};
 
 use super::AllocatorBuilder;
 
@@ -26,7 +31,7 @@ impl<A: Allocator> RangeStorage for SingleRange<A> {
     fn maximum_capacity<T>(&self) -> Self::Capacity { usize::MAX }
 
     unsafe fn deallocate<T>(&mut self, handle: Self::Handle<T>) {
-        if handle.len() > 0 {
+        if handle.len() > 0 && !Self::is_zst::<T>() {
             let layout = Self::layout_of(handle);
             let pointer = Self::from_handle(handle);
             self.allocator.deallocate(pointer, layout);
@@ -41,7 +46,7 @@ impl<A: Allocator> RangeStorage for SingleRange<A> {
         handle
     }
 
-    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         debug_assert!(handle.len() < new_capacity);
 
         if handle.len() == 0 {
@@ -52,16 +57,36 @@ impl<A: Allocator> RangeStorage for SingleRange<A> {
         let old_pointer = Self::from_handle(handle);
 
         let new_layout = Self::layout_for::<T>(new_capacity)?;
-        let new_pointer = self.allocator.grow(old_pointer, old_layout, new_layout)?;
+        let new_pointer = self.allocator.grow(old_pointer, old_layout, new_layout)
+            .map_err(|_| StorageError::allocator_failure(new_layout))?;
 
         Ok(Self::into_handle(new_pointer, new_capacity))
     }
 
-    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    unsafe fn try_grow_front<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let old_capacity = handle.len();
+
+        let new_handle = self.try_grow(handle, new_capacity)?;
+
+        //  Safety:
+        //  -   Both source and destination are within the new range, of at least `new_capacity` elements, as the
+        //      allocator may grant more.
+        let base = new_handle.as_mut_ptr();
+        ptr::copy(base, base.add(new_handle.len() - old_capacity), old_capacity);
+
+        Ok(new_handle)
+    }
+
+    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         debug_assert!(handle.len() > new_capacity);
 
+        //  The range of zero-sized elements is unbounded, and never allocated.
+        if Self::is_zst::<T>() {
+            return Ok(handle);
+        }
+
         if handle.len() == 0 {
-            return Err(AllocError);
+            return Err(StorageError::unsupported());
         }
 
         let old_layout = Self::layout_of(handle);
@@ -73,24 +98,39 @@ impl<A: Allocator> RangeStorage for SingleRange<A> {
         }
 
         let new_layout = Self::layout_for::<T>(new_capacity)?;
-        let new_pointer = self.allocator.shrink(old_pointer, old_layout, new_layout)?;
+        let new_pointer = self.allocator.shrink(old_pointer, old_layout, new_layout)
+            .map_err(|_| StorageError::allocator_failure(new_layout))?;
 
         Ok(Self::into_handle(new_pointer, new_capacity))
     }
 }
 
 impl<A: Allocator> SingleRangeStorage for SingleRange<A> {
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
-        if capacity == 0 {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        if capacity == 0 || Self::is_zst::<T>() {
             return Ok(Self::dangling_handle());
         }
 
         let layout = Self::layout_for::<T>(capacity)?;
-        let pointer = self.allocator.allocate(layout)?;
+        let pointer = self.allocator.allocate(layout).map_err(|_| StorageError::allocator_failure(layout))?;
+        Ok(Self::into_handle(pointer, capacity))
+    }
+
+    fn allocate_zeroed<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        if capacity == 0 || Self::is_zst::<T>() {
+            return Ok(Self::dangling_handle());
+        }
+
+        let layout = Self::layout_for::<T>(capacity)?;
+        let pointer = self.allocator.allocate_zeroed(layout).map_err(|_| StorageError::allocator_failure(layout))?;
         Ok(Self::into_handle(pointer, capacity))
     }
 }
 
+//  Safety:
+//  -   Moving an allocator does not invalidate the memory blocks it returned.
+unsafe impl<A: Allocator> PinningStorage for SingleRange<A> {}
+
 impl<A: Allocator> Builder<SingleRange<A>> for A {
     fn from_storage(storage: SingleRange<A>) -> A { storage.allocator }
 
@@ -103,6 +143,10 @@ impl<A> Builder<SingleRange<A>> for AllocatorBuilder<A> {
     fn into_storage(self) -> SingleRange<A> { SingleRange::new(self.0) }
 }
 
+impl<A: Clone> CloneStorage for SingleRange<A> {
+    fn clone_storage(&self) -> Self { Self::new(self.allocator.clone()) }
+}
+
 impl<A> Debug for SingleRange<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleRangeA")
@@ -117,14 +161,19 @@ impl<A: Default> Default for SingleRange<A> {
 //  Implementation
 //
 impl<A: Allocator> SingleRange<A> {
+    fn is_zst<T>() -> bool { mem::size_of::<T>() == 0 }
+
+    //  The handle of an empty range, or of an unbounded range of zero-sized elements; neither is ever allocated.
     fn dangling_handle<T>() -> NonNull<[MaybeUninit<T>]> {
-        NonNull::slice_from_raw_parts(NonNull::dangling(), 0)
+        let capacity = if Self::is_zst::<T>() { usize::MAX } else { 0 };
+
+        NonNull::slice_from_raw_parts(NonNull::dangling(), capacity)
     }
 
-    fn layout_for<T>(capacity: usize) -> Result<Layout, AllocError> {
+    fn layout_for<T>(capacity: usize) -> Result<Layout, StorageError> {
         debug_assert!(capacity > 0);
 
-        Layout::array::<T>(capacity).map_err(|_| AllocError)
+        utils::array_layout::<T>(capacity)
     }
 
     fn layout_of<T>(handle: NonNull<[MaybeUninit<T>]>) -> Layout {
@@ -139,14 +188,22 @@ impl<A: Allocator> SingleRange<A> {
         handle.as_non_null_ptr().cast()
     }
 
+    //  The allocator may grant a larger block than requested, in which case the capacity is increased accordingly.
     fn into_handle<T>(pointer: NonNull<[u8]>, capacity: usize) -> NonNull<[MaybeUninit<T>]> {
-        NonNull::slice_from_raw_parts(pointer.as_non_null_ptr().cast(), capacity)
+        let granted = pointer.len().checked_div(mem::size_of::<T>()).unwrap_or(capacity);
+
+        debug_assert!(granted >= capacity);
+
+        NonNull::slice_from_raw_parts(pointer.as_non_null_ptr().cast(), granted)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+use core::alloc::AllocError;
+
+use crate::traits::Placement;
 use crate::utils::{NonAllocator, SpyAllocator};
 
 use super::*;
@@ -192,4 +249,117 @@ fn allocate_failure() {
     storage.allocate::<String>(1).unwrap_err();
 }
 
+#[test]
+fn allocate_zst_unbounded() {
+    let mut storage = SingleRange::new(NonAllocator);
+
+    let handle = storage.allocate::<()>(1).unwrap();
+
+    assert_eq!(usize::MAX, handle.len());
+
+    unsafe { storage.deallocate(handle) };
+}
+
+#[test]
+fn allocate_zeroed_success() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = SingleRange::new(allocator.clone());
+    let handle = storage.allocate_zeroed::<u64>(4).unwrap();
+
+    let range = unsafe { storage.resolve(handle).as_ref() };
+
+    assert_eq!(4, range.len());
+    assert!(range.iter().all(|e| unsafe { e.assume_init() } == 0));
+
+    unsafe { storage.deallocate(handle) };
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+//  Rounds up all allocations to a multiple of 32 bytes.
+struct RoundingAllocator;
+
+fn round_up(layout: Layout) -> Layout {
+    Layout::from_size_align(layout.size().div_ceil(32) * 32, layout.align()).unwrap()
+}
+
+unsafe impl Allocator for RoundingAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        std::alloc::Global.allocate(round_up(layout))
+    }
+
+    unsafe fn deallocate(&self, pointer: NonNull<u8>, layout: Layout) {
+        std::alloc::Global.deallocate(pointer, round_up(layout))
+    }
+}
+
+#[test]
+fn allocate_granted_capacity() {
+    let mut storage = SingleRange::new(RoundingAllocator);
+
+    let handle = storage.allocate::<u32>(3).unwrap();
+    assert_eq!(8, handle.len());
+
+    let handle = unsafe { storage.try_grow(handle, 9) }.unwrap();
+    assert_eq!(16, handle.len());
+
+    unsafe { storage.deallocate(handle) };
+}
+
+#[test]
+fn try_grow_tracked_relocated() {
+    let mut storage = SingleRange::new(SpyAllocator::default());
+
+    let handle = storage.allocate::<u8>(0).unwrap();
+    let (handle, placement) = unsafe { storage.try_grow_tracked(handle, 4) }.unwrap();
+
+    assert_eq!(Placement::Relocated, placement);
+
+    unsafe { storage.deallocate(handle) };
+}
+
+#[test]
+fn try_grow_front_success() {
+    let mut storage = SingleRange::new(SpyAllocator::default());
+
+    let handle = storage.allocate::<u8>(2).unwrap();
+
+    unsafe {
+        let slice = &mut *storage.resolve_mut(handle).as_ptr();
+        slice[0].write(1);
+        slice[1].write(2);
+
+        let handle = storage.try_grow_front(handle, 5).unwrap();
+        let slice = &*storage.resolve(handle).as_ptr();
+
+        assert_eq!(5, slice.len());
+        assert_eq!([1, 2], [slice[3].assume_init(), slice[4].assume_init()]);
+
+        storage.deallocate(handle);
+    }
+}
+
+#[test]
+fn try_grow_front_granted_capacity() {
+    let mut storage = SingleRange::new(RoundingAllocator);
+
+    let handle = storage.allocate::<u32>(8).unwrap();
+
+    unsafe {
+        let slice = &mut *storage.resolve_mut(handle).as_ptr();
+        for (i, element) in slice.iter_mut().enumerate() {
+            element.write(i as u32);
+        }
+
+        let handle = storage.try_grow_front(handle, 9).unwrap();
+        let slice = &*storage.resolve(handle).as_ptr();
+
+        assert_eq!(16, slice.len());
+        assert_eq!([0, 7], [slice[8].assume_init(), slice[15].assume_init()]);
+
+        storage.deallocate(handle);
+    }
+}
+
 } // mod tests
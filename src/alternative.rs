@@ -8,14 +8,17 @@
 //! -   Switching storages on the fly imply the ability to summon a storage from nothingness, hence the juggling of
 //!     builders, and the Poisoned state in case user provided functions panic.
 //! -   Switching handles, as storages switch, is easy for Single storages -- as the only handle is invalidated --
-//!     however there doesn't seem to be an elegant solution for Multi storages, therefore they are not implemented.
+//!     however Multi storages require an indirection layer, translating their handles into those of the current
+//!     storage, which bounds the number of elements.
 
 mod builder;
 mod inner;
+mod multi_element;
 mod single_element;
 mod single_range;
 
 pub use builder::{Builder, DefaultBuilder};
+pub use multi_element::MultiElement;
 pub use single_element::SingleElement;
 pub use single_range::SingleRange;
 
@@ -10,7 +10,7 @@ pub trait Builder<S> {
 }
 
 /// An empty builder state, when storages can be default constructed.
-#[derive(Debug, Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct DefaultBuilder;
 
 impl<S: Default> Builder<S> for DefaultBuilder {
@@ -2,6 +2,8 @@
 
 use core::ops::{Deref, DerefMut};
 
+use crate::traits::CloneStorage;
+
 use super::Builder;
 
 //  Alternative type.
@@ -19,6 +21,24 @@ impl<F, S, FB, SB> Inner<F, S, FB, SB> {
     pub(crate) fn second(value: S, builder: FB) -> Self {
         Self::Second(InnerElement{ value, builder })
     }
+
+    pub(crate) fn is_first(&self) -> bool { matches!(self, Self::First(_)) }
+}
+
+impl<F, S, FB, SB> CloneStorage for Inner<F, S, FB, SB>
+    where
+        F: CloneStorage,
+        S: CloneStorage,
+        FB: Clone,
+        SB: Clone,
+{
+    fn clone_storage(&self) -> Self {
+        match self {
+            Self::First(first) => Self::first(first.value.clone_storage(), first.builder.clone()),
+            Self::Second(second) => Self::second(second.value.clone_storage(), second.builder.clone()),
+            Self::Poisoned => panic!("Poisoned"),
+        }
+    }
 }
 
 impl<F: Default, S, FB, SB: Default> Default for Inner<F, S, FB, SB> {
@@ -46,6 +66,28 @@ impl<V, B> InnerElement<V, B> {
 
         (InnerElement { value: other_value, builder: other_builder }, result)
     }
+
+    //  Like `transform`, except that if `fun` fails, the transformation is reverted, and `self` is returned.
+    pub(crate) fn try_transform<OV, OB, Fun, R, E>(self, fun: Fun) -> Result<(InnerElement<OV, OB>, R), (Self, E)>
+        where
+            B: Builder<OV>,
+            OB: Builder<V>,
+            Fun: FnOnce(&mut V, &mut OV) -> Result<R, E>,
+    {
+        let InnerElement { mut value, builder } = self;
+        let mut other_value = B::into_storage(builder);
+
+        match fun(&mut value, &mut other_value) {
+            Ok(result) => {
+                let other_builder = OB::from_storage(value);
+                Ok((InnerElement { value: other_value, builder: other_builder }, result))
+            },
+            Err(error) => {
+                let builder = B::from_storage(other_value);
+                Err((InnerElement { value, builder }, error))
+            },
+        }
+    }
 }
 
 impl<V, B> Deref for InnerElement<V, B> {
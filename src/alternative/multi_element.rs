@@ -0,0 +1,492 @@
+//! Alternative implementation of `MultiElementStorage`.
+
+use core::{
+    fmt::{self, Debug},
+    hint,
+    marker::Unsize,
+    mem::{self, MaybeUninit},
+    num::NonZeroUsize,
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::{error::StorageError, traits::{CloneStorage, ElementStorage, MultiElementStorage}, utils};
+
+use super::{Builder, Inner};
+
+/// MultiElement is a composite of 2 MultiElementStorage.
+///
+/// It will first attempt to allocate from the first storage if possible, and otherwise switch to the second storage,
+/// migrating all the elements of the first storage to the second.
+///
+/// Since migrating the elements invalidates the handles issued by the first storage, the handles of MultiElement
+/// designate one of `N` entries of a translation table, which records the handle of the element in the current storage
+/// and is updated on migration. As a result:
+///
+/// -   At most `N` elements may be allocated at any time, regardless of the current storage.
+/// -   Allocation fails if the handles of either storage are too large to be recorded in an entry.
+/// -   The elements are moved bitwise on migration, hence any pointer to them is invalidated.
+pub struct MultiElement<F, S, FB, SB, const N: usize> {
+    inner: Inner<F, S, FB, SB>,
+    entries: [Option<Entry<F, S>>; N],
+}
+
+impl<F, S, FB, SB, const N: usize> MultiElement<F, S, FB, SB, N> {
+    /// Creates an instance containing the First alternative.
+    pub fn first(first: F, second_builder: SB) -> Self {
+        Self { inner: Inner::first(first, second_builder), entries: [(); N].map(|_| None) }
+    }
+
+    /// Creates an instance containing the Second alternative.
+    pub fn second(second: S, first_builder: FB) -> Self {
+        Self { inner: Inner::second(second, first_builder), entries: [(); N].map(|_| None) }
+    }
+}
+
+impl<F, S, FB, SB, const N: usize> ElementStorage for MultiElement<F, S, FB, SB, N>
+    where
+        F: MultiElementStorage,
+        S: MultiElementStorage,
+{
+    type Handle<T: ?Sized + Pointee> = MultiElementHandle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its index is within bounds, and its entry is occupied.
+        let entry = occupied(self.entries.get_unchecked_mut(handle.index()).take());
+
+        match &mut self.inner {
+            Inner::First(ref mut first) => (entry.vtable.first.deallocate)(first, entry.handle),
+            Inner::Second(ref mut second) => (entry.vtable.second.deallocate)(second, entry.handle),
+            Inner::Poisoned => panic!("Poisoned"),
+        }
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its index is within bounds, and its entry is occupied.
+        let entry = occupied(self.entries.get_unchecked(handle.index()).as_ref());
+
+        let pointer = match &self.inner {
+            Inner::First(ref first) => (entry.vtable.first.resolve)(first, entry.handle),
+            Inner::Second(ref second) => (entry.vtable.second.resolve)(second, entry.handle),
+            Inner::Poisoned => panic!("Poisoned"),
+        };
+
+        NonNull::from_raw_parts(pointer.cast::<()>(), handle.1)
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its index is within bounds, and its entry is occupied.
+        let entry = occupied(self.entries.get_unchecked(handle.index()).as_ref());
+
+        let pointer = match &mut self.inner {
+            Inner::First(ref mut first) => (entry.vtable.first.resolve_mut)(first, entry.handle),
+            Inner::Second(ref mut second) => (entry.vtable.second.resolve_mut)(second, entry.handle),
+            Inner::Poisoned => panic!("Poisoned"),
+        };
+
+        NonNull::from_raw_parts(pointer.cast::<()>(), handle.1)
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid.
+        let element = self.resolve(handle);
+
+        let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
+
+        MultiElementHandle(handle.0, meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        MultiElementHandle(handle.0, handle.1)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its index is within bounds, and its entry is occupied.
+        let entry = occupied(self.entries.get_unchecked_mut(handle.index()).as_mut());
+
+        //  Safety:
+        //  -   `entry.handle` is a valid handle of the current storage, for `T`, which fits as it was erased before.
+        entry.handle = match &mut self.inner {
+            Inner::First(ref mut first) =>
+                erase(first.update_metadata(restore::<F::Handle<T>>(entry.handle), meta)?),
+            Inner::Second(ref mut second) =>
+                erase(second.update_metadata(restore::<S::Handle<T>>(entry.handle), meta)?),
+            Inner::Poisoned => panic!("Poisoned"),
+        };
+
+        Ok(MultiElementHandle(handle.0, meta))
+    }
+}
+
+impl<F, S, FB, SB, const N: usize> MultiElementStorage for MultiElement<F, S, FB, SB, N>
+    where
+        F: MultiElementStorage,
+        S: MultiElementStorage,
+        FB: Builder<F>,
+        SB: Builder<S>,
+{
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        if !fits::<F::Handle<T>>() || !fits::<S::Handle<T>>() {
+            return Err(StorageError::unsupported());
+        }
+
+        let index = self.entries.iter().position(Option::is_none)
+            .ok_or_else(|| StorageError::exhausted(utils::layout_of::<T>(meta)))?;
+
+        //  Safety:
+        //  -   The handles fit, as checked above.
+        let handle = unsafe {
+            match &mut self.inner {
+                Inner::First(ref mut first) =>
+                    match first.allocate::<T>(meta) {
+                        Ok(handle) => erase(handle),
+                        Err(_) => erase(self.migrate()?.allocate::<T>(meta)?),
+                    },
+                Inner::Second(ref mut second) => erase(second.allocate::<T>(meta)?),
+                Inner::Poisoned => panic!("Poisoned"),
+            }
+        };
+
+        self.entries[index] = Some(Entry { handle, vtable: VTable::of::<T>() });
+
+        Ok(MultiElementHandle::new(index, meta))
+    }
+}
+
+impl<F, S, FB, SB, const N: usize> CloneStorage for MultiElement<F, S, FB, SB, N>
+    where
+        F: CloneStorage,
+        S: CloneStorage,
+        FB: Clone,
+        SB: Clone,
+{
+    fn clone_storage(&self) -> Self { Self { inner: self.inner.clone_storage(), entries: [(); N].map(|_| None) } }
+}
+
+impl<F, S, FB, SB, const N: usize> Debug for MultiElement<F, S, FB, SB, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "MultiElement")
+    }
+}
+
+impl<F: Default, S, FB, SB: Default, const N: usize> Default for MultiElement<F, S, FB, SB, N> {
+    fn default() -> Self { Self { inner: Inner::default(), entries: [(); N].map(|_| None) } }
+}
+
+/// MultiElementHandle, the index of an entry of the translation table.
+///
+/// The index is stored off-by-one, so that an `Option` of the handle is no larger than the handle itself.
+pub struct MultiElementHandle<T: ?Sized + Pointee>(NonZeroUsize, T::Metadata);
+
+impl<T: ?Sized + Pointee> Clone for MultiElementHandle<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized + Pointee> Copy for MultiElementHandle<T> {}
+
+impl<T: ?Sized + Pointee> Debug for MultiElementHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "MultiElementHandle({})", self.index())
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<T: ?Sized + Pointee> MultiElementHandle<T> {
+    //  `index` is less than `N`, hence cannot saturate.
+    fn new(index: usize, meta: T::Metadata) -> Self { Self(NonZeroUsize::MIN.saturating_add(index), meta) }
+
+    fn index(&self) -> usize { self.0.get() - 1 }
+}
+
+//  The handle of an element, in either storage, with its type erased.
+type Erased = MaybeUninit<[usize; 3]>;
+
+//  An entry of the translation table.
+struct Entry<F, S> {
+    handle: Erased,
+    vtable: VTable<F, S>,
+}
+
+//  The operations on the erased handle of an entry, specific to the type of its element.
+struct VTable<F, S> {
+    first: Operations<F>,
+    second: Operations<S>,
+    migrate: unsafe fn(&mut F, &mut S, Erased) -> Result<Erased, StorageError>,
+}
+
+impl<F: ElementStorage, S: MultiElementStorage> VTable<F, S> {
+    fn of<T: ?Sized + Pointee>() -> Self {
+        Self { first: Operations::of::<T>(), second: Operations::of::<T>(), migrate: migrate::<T, F, S> }
+    }
+}
+
+struct Operations<X> {
+    resolve: unsafe fn(&X, Erased) -> NonNull<u8>,
+    resolve_mut: unsafe fn(&mut X, Erased) -> NonNull<u8>,
+    deallocate: unsafe fn(&mut X, Erased),
+}
+
+impl<X: ElementStorage> Operations<X> {
+    fn of<T: ?Sized + Pointee>() -> Self {
+        Self { resolve: resolve::<T, X>, resolve_mut: resolve_mut::<T, X>, deallocate: deallocate::<T, X> }
+    }
+}
+
+impl<F, S, FB, SB, const N: usize> MultiElement<F, S, FB, SB, N>
+    where
+        F: MultiElementStorage,
+        S: MultiElementStorage,
+        FB: Builder<F>,
+        SB: Builder<S>,
+{
+    //  Migrates all the elements from the first storage to the second, and switches to the second storage.
+    //
+    //  On failure, the elements remain in the first storage, which remains the current storage.
+    fn migrate(&mut self) -> Result<&mut S, StorageError> {
+        let first = match mem::replace(&mut self.inner, Inner::Poisoned) {
+            Inner::First(first) => first,
+            //  Safety:
+            //  -   `migrate` is only invoked when self.inner is First.
+            _ => unsafe { hint::unreachable_unchecked() },
+        };
+
+        let entries = &mut self.entries;
+
+        let result = first.try_transform(|first: &mut F, second: &mut S| {
+            let mut migrated: [Option<Erased>; N] = [None; N];
+
+            for (index, entry) in entries.iter().enumerate() {
+                let entry = if let Some(entry) = entry { entry } else { continue };
+
+                //  Safety:
+                //  -   `entry.handle` is a valid handle of `first`, of the type of `entry.vtable`.
+                match unsafe { (entry.vtable.migrate)(first, second, entry.handle) } {
+                    Ok(handle) => migrated[index] = Some(handle),
+                    Err(error) => {
+                        for (handle, entry) in migrated.iter().zip(entries.iter()) {
+                            if let (Some(handle), Some(entry)) = (handle, entry) {
+                                //  Safety:
+                                //  -   `handle` is a valid handle of `second`, of the type of `entry.vtable`.
+                                unsafe { (entry.vtable.second.deallocate)(second, *handle) };
+                            }
+                        }
+
+                        return Err(error);
+                    },
+                }
+            }
+
+            for (handle, entry) in migrated.iter().zip(entries.iter_mut()) {
+                if let (Some(handle), Some(entry)) = (handle, entry) {
+                    //  Safety:
+                    //  -   `entry.handle` is a valid handle of `first`, of the type of `entry.vtable`.
+                    unsafe { (entry.vtable.first.deallocate)(first, entry.handle) };
+
+                    entry.handle = *handle;
+                }
+            }
+
+            Ok(())
+        });
+
+        match result {
+            Ok((second, ())) => self.inner = Inner::Second(second),
+            Err((first, error)) => {
+                self.inner = Inner::First(first);
+                return Err(error);
+            },
+        }
+
+        match &mut self.inner {
+            Inner::Second(ref mut second) => Ok(second),
+            //  Safety:
+            //  -   self.inner was set to Second above.
+            _ => unsafe { hint::unreachable_unchecked() },
+        }
+    }
+}
+
+//  Returns whether a handle of type `H` can be erased.
+const fn fits<H>() -> bool {
+    mem::size_of::<H>() <= mem::size_of::<Erased>() && mem::align_of::<H>() <= mem::align_of::<Erased>()
+}
+
+//  #   Safety
+//
+//  -   Assumes `fits::<H>()`.
+unsafe fn erase<H: Copy>(handle: H) -> Erased {
+    let mut erased = Erased::uninit();
+    ptr::write(erased.as_mut_ptr() as *mut H, handle);
+    erased
+}
+
+//  #   Safety
+//
+//  -   Assumes `erased` was obtained by `erase::<H>`.
+unsafe fn restore<H: Copy>(erased: Erased) -> H { ptr::read(erased.as_ptr() as *const H) }
+
+//  #   Safety
+//
+//  -   Assumes `entry` is occupied.
+unsafe fn occupied<E>(entry: Option<E>) -> E {
+    debug_assert!(entry.is_some(), "MultiElement: handle to a vacant entry");
+
+    match entry {
+        Some(entry) => entry,
+        None => hint::unreachable_unchecked(),
+    }
+}
+
+unsafe fn resolve<T: ?Sized + Pointee, X: ElementStorage>(storage: &X, handle: Erased) -> NonNull<u8> {
+    storage.resolve(restore::<X::Handle<T>>(handle)).cast()
+}
+
+unsafe fn resolve_mut<T: ?Sized + Pointee, X: ElementStorage>(storage: &mut X, handle: Erased) -> NonNull<u8> {
+    storage.resolve_mut(restore::<X::Handle<T>>(handle)).cast()
+}
+
+unsafe fn deallocate<T: ?Sized + Pointee, X: ElementStorage>(storage: &mut X, handle: Erased) {
+    storage.deallocate(restore::<X::Handle<T>>(handle))
+}
+
+//  Copies the element of `handle` from `first` into a newly allocated slot of `second`, whose erased handle is
+//  returned. The element is left in `first`.
+unsafe fn migrate<T: ?Sized + Pointee, F: ElementStorage, S: MultiElementStorage>(
+    first: &mut F,
+    second: &mut S,
+    handle: Erased,
+)
+    -> Result<Erased, StorageError>
+{
+    let source = first.resolve_mut(restore::<F::Handle<T>>(handle));
+    let meta = source.as_ptr().to_raw_parts().1;
+
+    let target = second.allocate::<T>(meta)?;
+    let destination = second.resolve_mut(target);
+
+    let size = utils::layout_of::<T>(meta).size();
+
+    ptr::copy_nonoverlapping(source.as_ptr() as *const u8, destination.as_ptr() as *mut u8, size);
+
+    Ok(erase(target))
+}
+
+#[cfg(test)]
+mod tests {
+
+use crate::{allocator::{self, AllocatorBuilder}, alternative::DefaultBuilder, collections::RawLinkedList, inline};
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+type Storage<S, const M: usize, const N: usize> = MultiElement<
+    inline::MultiElement<S, M>,
+    allocator::MultiElement<SpyAllocator>,
+    DefaultBuilder,
+    AllocatorBuilder<SpyAllocator>,
+    N,
+>;
+
+fn storage<S, const M: usize, const N: usize>(allocator: &SpyAllocator) -> Storage<S, M, N> {
+    MultiElement::first(inline::MultiElement::new(), AllocatorBuilder(allocator.clone()))
+}
+
+#[test]
+fn create_migrate() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = storage::<u32, 2, 4>(&allocator);
+
+    let one = storage.create(1u32).unwrap();
+    let two = storage.create(2u32).unwrap();
+
+    assert_eq!(0, allocator.allocated());
+
+    let three = storage.create(3u32).unwrap();
+
+    assert_eq!(3, allocator.allocated());
+
+    unsafe {
+        assert_eq!(1, *storage.resolve(one).as_ref());
+        assert_eq!(2, *storage.resolve(two).as_ref());
+        assert_eq!(3, *storage.resolve(three).as_ref());
+
+        storage.destroy(one);
+        storage.destroy(two);
+        storage.destroy(three);
+    }
+
+    assert_eq!(3, allocator.deallocated());
+}
+
+#[test]
+fn create_insufficient_entries() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = storage::<u32, 4, 2>(&allocator);
+
+    let one = storage.create(1u32).unwrap();
+    let _ = storage.create(2u32).unwrap();
+
+    storage.create(3u32).unwrap_err();
+
+    //  Destroying an element frees its entry.
+    unsafe { storage.destroy(one) };
+
+    storage.create(3u32).unwrap();
+
+    assert_eq!(0, allocator.allocated());
+}
+
+#[test]
+fn coerce_migrate() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = storage::<[u32; 3], 1, 2>(&allocator);
+
+    let handle = storage.create([1u32, 2, 3]).unwrap();
+    let handle = unsafe { storage.coerce::<[u32], _>(handle) };
+
+    let other = storage.create(4u32).unwrap();
+
+    assert_eq!(2, allocator.allocated());
+
+    unsafe {
+        assert_eq!(&[1, 2, 3], storage.resolve(handle).as_ref());
+
+        storage.destroy(handle);
+        storage.destroy(other);
+    }
+
+    assert_eq!(2, allocator.deallocated());
+}
+
+#[test]
+fn linked_list() {
+    let allocator = SpyAllocator::default();
+
+    let mut list = RawLinkedList::new(storage::<[u64; 4], 2, 8>(&allocator));
+
+    for i in 0..4 {
+        list.push(i.to_string()).unwrap();
+    }
+
+    assert_eq!(4, allocator.allocated());
+    assert_eq!(Some("3".to_string()), list.pop());
+    assert_eq!(Some("2".to_string()), list.pop());
+
+    drop(list);
+
+    assert_eq!(4, allocator.deallocated());
+}
+
+} // mod tests
@@ -1,8 +1,8 @@
 //! Alternative implementation of `SingleElementStorage`.
 
-use core::{alloc::AllocError, fmt::{self, Debug}, hint, marker::Unsize, mem, ptr::{NonNull, Pointee}};
+use core::{fmt::{self, Debug}, hint, marker::Unsize, mem, ptr::{NonNull, Pointee}};
 
-use crate::traits::{ElementStorage, SingleElementStorage};
+use crate::{error::StorageError, traits::{CloneStorage, ElementStorage, SingleElementStorage, SpillingElementStorage}};
 
 use super::{Builder, Inner};
 
@@ -18,6 +18,9 @@ impl<F, S, FB, SB> SingleElement<F, S, FB, SB> {
 
     /// Creates an instance containing the Second alternative.
     pub fn second(second: S, first_builder: FB) -> Self { Self(Inner::second(second, first_builder)) }
+
+    /// Returns whether the First alternative is in use.
+    pub fn is_first(&self) -> bool { self.0.is_first() }
 }
 
 impl<F, S, FB, SB> ElementStorage for SingleElement<F, S, FB, SB>
@@ -58,6 +61,26 @@ impl<F, S, FB, SB> ElementStorage for SingleElement<F, S, FB, SB>
             Inner::Poisoned => panic!("Poisoned"),
         }
     }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        match &self.0 {
+            Inner::First(ref first) => SingleElementHandle { first: first.cast(handle.first) },
+            Inner::Second(ref second) => SingleElementHandle { second: second.cast(handle.second) },
+            Inner::Poisoned => panic!("Poisoned"),
+        }
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        match &mut self.0 {
+            Inner::First(ref mut first) =>
+                first.update_metadata(handle.first, meta).map(|first| SingleElementHandle { first }),
+            Inner::Second(ref mut second) =>
+                second.update_metadata(handle.second, meta).map(|second| SingleElementHandle { second }),
+            Inner::Poisoned => panic!("Poisoned"),
+        }
+    }
 }
 
 impl<F, S, FB, SB> SingleElementStorage for SingleElement<F, S, FB, SB>
@@ -91,7 +114,7 @@ impl<F, S, FB, SB> SingleElementStorage for SingleElement<F, S, FB, SB>
         }
     }
 
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
         match &mut self.0 {
             Inner::First(ref mut first) =>
                 match first.allocate(meta) {
@@ -116,6 +139,24 @@ impl<F, S, FB, SB> SingleElementStorage for SingleElement<F, S, FB, SB>
     }
 }
 
+impl<F, S, FB, SB> SpillingElementStorage for SingleElement<F, S, FB, SB>
+    where
+        F: SingleElementStorage,
+        S: SingleElementStorage,
+{
+    unsafe fn is_spilled<T: ?Sized + Pointee>(&self, _: Self::Handle<T>) -> bool { !self.is_first() }
+}
+
+impl<F, S, FB, SB> CloneStorage for SingleElement<F, S, FB, SB>
+    where
+        F: CloneStorage,
+        S: CloneStorage,
+        FB: Clone,
+        SB: Clone,
+{
+    fn clone_storage(&self) -> Self { Self(self.0.clone_storage()) }
+}
+
 impl<F, S, FB, SB> Debug for SingleElement<F, S, FB, SB> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleElement")
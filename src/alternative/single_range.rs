@@ -1,8 +1,11 @@
 //! Alternative implementation of `SingleRangeStorage`.
 
-use core::{alloc::AllocError, cmp, fmt::{self, Debug}, hint, mem::{self, MaybeUninit}, ptr::{self, NonNull}};
+use core::{cmp, fmt::{self, Debug}, hint, mem::{self, MaybeUninit}, ptr::{self, NonNull}};
 
-use crate::traits::{Capacity, RangeStorage, SingleRangeStorage};
+use crate::{
+    error::StorageError,
+    traits::{Capacity, CloneStorage, RangeStorage, SingleRangeStorage, SpillingRangeStorage},
+};
 
 use super::{Builder, Inner};
 
@@ -18,6 +21,9 @@ impl<F, S, FB, SB> SingleRange<F, S, FB, SB> {
 
     /// Creates an instance containing the Second alternative.
     pub fn second(second: S, first_builder: FB) -> Self { Self(Inner::second(second, first_builder)) }
+
+    /// Returns whether the First alternative is in use.
+    pub fn is_first(&self) -> bool { self.0.is_first() }
 }
 
 impl<F, S, FB, SB> RangeStorage for SingleRange<F, S, FB, SB>
@@ -63,7 +69,7 @@ impl<F, S, FB, SB> RangeStorage for SingleRange<F, S, FB, SB>
         }
     }
 
-    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         match &mut self.0 {
             Inner::First(ref mut first) => {
                 let grow = into_first::<F, S>(new_capacity)
@@ -93,7 +99,37 @@ impl<F, S, FB, SB> RangeStorage for SingleRange<F, S, FB, SB>
         }
     }
 
-    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    unsafe fn try_grow_front<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        match &mut self.0 {
+            Inner::First(ref mut first) => {
+                let grow = into_first::<F, S>(new_capacity)
+                    .and_then(|new_capacity| first.try_grow_front(handle.first, new_capacity));
+
+                match grow {
+                    Ok(first) => Ok(SingleRangeHandle { first }),
+                    Err(_) => {
+                        if let Inner::First(first) = mem::replace(&mut self.0, Inner::Poisoned) {
+                            let (second, result) = first.transform(|first: &mut F, second: &mut S| {
+                                let new_handle = second.allocate(new_capacity)?;
+                                transfer_back(first.resolve_mut(handle.first), second.resolve_mut(new_handle));
+                                Ok(SingleRangeHandle { second: new_handle })
+                            });
+                            self.0 = Inner::Second(second);
+                            return result;
+                        }
+                        //  Safety:
+                        //  -   self.0 was First before invoking replace, hence replace returns First.
+                        hint::unreachable_unchecked();
+                    },
+                }
+            },
+            Inner::Second(ref mut second) =>
+                second.try_grow_front(handle.second, new_capacity).map(|second| SingleRangeHandle{ second }),
+            Inner::Poisoned => panic!("Poisoned"),
+        }
+    }
+
+    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         match &mut self.0 {
             Inner::First(ref mut first) =>
                 first.try_shrink(handle.first, into_first::<F, S>(new_capacity)?)
@@ -134,7 +170,7 @@ impl<F, S, FB, SB> SingleRangeStorage for SingleRange<F, S, FB, SB>
         FB: Builder<F>,
         SB: Builder<S>,
 {
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         match &mut self.0 {
             Inner::First(ref mut first) => {
                 let handle = into_first::<F, S>(capacity)
@@ -163,6 +199,26 @@ impl<F, S, FB, SB> SingleRangeStorage for SingleRange<F, S, FB, SB>
     }
 }
 
+impl<F, S, FB, SB> SpillingRangeStorage for SingleRange<F, S, FB, SB>
+    where
+        F: SingleRangeStorage,
+        S: SingleRangeStorage,
+        FB: Builder<F>,
+        SB: Builder<S>,
+{
+    unsafe fn is_spilled<T>(&self, _: Self::Handle<T>) -> bool { !self.is_first() }
+}
+
+impl<F, S, FB, SB> CloneStorage for SingleRange<F, S, FB, SB>
+    where
+        F: CloneStorage,
+        S: CloneStorage,
+        FB: Clone,
+        SB: Clone,
+{
+    fn clone_storage(&self) -> Self { Self(self.0.clone_storage()) }
+}
+
 impl<F, S, FB, SB> Debug for SingleRange<F, S, FB, SB> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleRange")
@@ -190,9 +246,9 @@ impl<F: Copy, S: Copy> Debug for SingleRangeHandle<F, S> {
 //  Implementation
 //
 
-fn into_first<F: RangeStorage, S: RangeStorage>(capacity: S::Capacity) -> Result<F::Capacity, AllocError> {
+fn into_first<F: RangeStorage, S: RangeStorage>(capacity: S::Capacity) -> Result<F::Capacity, StorageError> {
     F::Capacity::from_usize(capacity.into_usize())
-        .ok_or(AllocError)
+        .ok_or_else(StorageError::capacity_overflow)
 }
 
 fn into_second<F: RangeStorage, S: RangeStorage>(capacity: F::Capacity) -> S::Capacity {
@@ -206,3 +262,13 @@ unsafe fn transfer<T>(from: NonNull<[MaybeUninit<T>]>, mut to: NonNull<[MaybeUni
 
     ptr::copy_nonoverlapping(from.as_ptr(), to.as_mut_ptr(), cmp::min(from.len(), to.len()));
 }
+
+//  Transfers the elements of `from` to the back of `to`, which is assumed to be at least as large.
+unsafe fn transfer_back<T>(from: NonNull<[MaybeUninit<T>]>, mut to: NonNull<[MaybeUninit<T>]>) {
+    let from = from.as_ref();
+    let to = to.as_mut();
+
+    debug_assert!(from.len() <= to.len());
+
+    ptr::copy_nonoverlapping(from.as_ptr(), to.as_mut_ptr().add(to.len() - from.len()), from.len());
+}
@@ -0,0 +1,54 @@
+//! Compile-time memory budget assertions.
+//!
+//! The fully inline storages allow computing, at compile-time, the worst-case memory footprint of a collection: it is
+//! simply its size, since no element ever lives outside of it. This in turn allows checking, at compile-time, that a
+//! set of collections fits within a given RAM budget.
+//!
+//! For collections using non-inline storages, only the inline part is accounted for.
+
+use core::mem;
+
+/// Returns the worst-case inline footprint of `T`, in bytes.
+pub const fn footprint_of<T>() -> usize { mem::size_of::<T>() }
+
+/// Fails compilation if the cumulative footprint of the listed types exceeds the budget, in bytes.
+///
+/// The footprint of each type is computed by `footprint_of`, and each type is accounted for as many times as listed.
+#[macro_export]
+macro_rules! static_assert_fits {
+    ($budget:expr; $($t:ty),+ $(,)?) => {
+        const _: () = assert!(
+            0 $(+ $crate::budget::footprint_of::<$t>())+ <= $budget,
+            "Memory budget exceeded"
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+use crate::collections::{RawBox, RawVec};
+use crate::inline::{SingleElement, SingleRange};
+
+use super::*;
+
+type Inputs = RawVec<u8, SingleRange<u8, u8, 16>>;
+type Output = RawBox<u32, SingleElement<u32>>;
+
+static_assert_fits!(64; Inputs, Inputs, Output);
+
+#[test]
+fn footprint_of_inline() {
+    assert_eq!(1, footprint_of::<u8>());
+    assert_eq!(mem::size_of::<Inputs>(), footprint_of::<Inputs>());
+    assert!(footprint_of::<Inputs>() >= 16);
+}
+
+#[test]
+fn footprint_of_sum() {
+    const TOTAL: usize = footprint_of::<Output>() + footprint_of::<u64>();
+
+    assert_eq!(4 + 8, TOTAL);
+}
+
+} // mod tests
@@ -1,9 +1,63 @@
 //! Proof of Concept implementations of some collections, to demonstrate the use of Storages.
 
+mod collect_in;
+mod extensions;
+mod handle_registry;
+mod hash_table;
+mod raw_any_vec;
+mod raw_arc;
 mod raw_box;
+mod raw_btree_map;
+mod raw_btree_set;
+mod raw_compact_hash_map;
+mod raw_cow;
+mod raw_dense_slot_map;
+mod raw_gap_buffer;
+mod raw_hash_map;
+mod raw_hash_set;
+mod raw_history_buffer;
+mod raw_interner;
+mod raw_intrusive_list;
 mod raw_linked_list;
+mod raw_mpmc_queue;
+mod raw_once_cell;
+mod raw_priority_queue;
+mod raw_rc;
+mod raw_skip_list;
+mod raw_soa;
+mod raw_spsc_queue;
+mod raw_stable_vec;
+mod raw_string;
 mod raw_vec;
 
+pub use collect_in::{CollectIn, FromIteratorIn};
+pub use extensions::{BoxedIn, ToRawVecIn};
+pub use handle_registry::HandleRegistry;
+pub use hash_table::{DefaultHashBuilder, FxHasher};
+pub use raw_any_vec::{RawAnyDrop, RawAnyVec};
+pub use raw_arc::{RawArc, RawArcWeak};
 pub use raw_box::RawBox;
-pub use raw_linked_list::{RawLinkedList, RawLinkedListNodeStorage};
+pub use raw_btree_map::{RawBTreeMap, RawBTreeMapIter};
+pub use raw_btree_set::{RawBTreeSet, RawBTreeSetIter};
+pub use raw_compact_hash_map::{RawCompactHashMap, RawCompactHashMapIter};
+pub use raw_cow::{RawCow, ToOwnedIn};
+pub use raw_dense_slot_map::{RawDenseSlotMap, RawDenseSlotMapKey};
+pub use raw_gap_buffer::RawGapBuffer;
+pub use raw_hash_map::{
+    RawHashMap, RawHashMapEntry, RawHashMapIter, RawHashMapOccupiedEntry, RawHashMapVacantEntry,
+};
+pub use raw_hash_set::{RawHashSet, RawHashSetIntersection, RawHashSetIter, RawHashSetUnion};
+pub use raw_history_buffer::RawHistoryBuffer;
+pub use raw_interner::{RawInterner, RawInternerSymbol};
+pub use raw_intrusive_list::{RawIntrusiveList, RawIntrusiveListIter, RawLink, RawLinked};
+pub use raw_linked_list::{RawLinkedList, RawLinkedListExtractIf, RawLinkedListIter, RawLinkedListNodeStorage};
+pub use raw_mpmc_queue::{RawMpmcQueue, RawMpmcSlot};
+pub use raw_once_cell::{RawLazy, RawOnceCell};
+pub use raw_priority_queue::{RawPriorityQueue, RawPriorityQueueHandle};
+pub use raw_rc::{RawRc, RawWeak};
+pub use raw_skip_list::{RawSkipList, RawSkipListIter, RawSkipListNode};
+pub use raw_soa::{RawSoa, RawSoaIter, RawSoaIterMut, SoaFields};
+pub use raw_spsc_queue::{RawSpscConsumer, RawSpscProducer, RawSpscQueue};
+pub use raw_stable_vec::{RawStableVec, RawStableVecIter};
+pub use raw_string::{FromUtf8Error, RawString};
 pub use raw_vec::RawVec;
@@ -0,0 +1,50 @@
+//! Extension trait to collect iterators into collections parameterized by a Storage.
+
+use crate::error::StorageError;
+
+/// Conversion from an iterator, with the storage to use.
+///
+/// The storage counterpart of `FromIterator`.
+pub trait FromIteratorIn<T>: Sized {
+    /// The storage used by the collection.
+    type Storage;
+
+    /// Creates an instance from `iter`, using `storage`.
+    ///
+    /// #   Panics
+    ///
+    /// If `storage` cannot accomodate all elements.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, storage: Self::Storage) -> Self;
+
+    /// Attempts to create an instance from `iter`, using `storage`.
+    ///
+    /// Fails if `storage` cannot accomodate all elements.
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, storage: Self::Storage) -> Result<Self, StorageError>;
+}
+
+/// Extension trait for iterators, to collect them into a collection with a given storage.
+///
+/// Unlike `collect`, the storage is passed explicitly, hence need not be `Default`, and `try_collect_in` reports the
+/// failure of the storage rather than panicking. The collection is pre-sized according to the `size_hint` of the
+/// iterator, whenever the storage allows.
+pub trait CollectIn: Iterator + Sized {
+    /// Collects `self` into a collection using `storage`.
+    ///
+    /// #   Panics
+    ///
+    /// If `storage` cannot accomodate all elements.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    fn collect_in<C: FromIteratorIn<Self::Item>>(self, storage: C::Storage) -> C {
+        C::from_iter_in(self, storage)
+    }
+
+    /// Attempts to collect `self` into a collection using `storage`.
+    ///
+    /// Fails if `storage` cannot accomodate all elements.
+    fn try_collect_in<C: FromIteratorIn<Self::Item>>(self, storage: C::Storage) -> Result<C, StorageError> {
+        C::try_from_iter_in(self, storage)
+    }
+}
+
+impl<I: Iterator> CollectIn for I {}
@@ -0,0 +1,124 @@
+//! Extension traits to move or copy values into collections parameterized by a Storage.
+//!
+//! These mirror the `to_vec_in` and `Box::new_in` of the Allocator API.
+
+use crate::{error::StorageError, traits::{SingleElementStorage, SingleRangeStorage}};
+
+use super::{CollectIn, RawBox, RawVec};
+
+/// Extension trait for slices, to clone them into a `RawVec`.
+pub trait ToRawVecIn<T> {
+    /// Clones `self` into a `RawVec` using `storage`.
+    ///
+    /// #   Panics
+    ///
+    /// If `storage` cannot accomodate all elements.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    fn to_raw_vec_in<S: SingleRangeStorage>(&self, storage: S) -> RawVec<T, S>;
+
+    /// Attempts to clone `self` into a `RawVec` using `storage`.
+    ///
+    /// Fails if `storage` cannot accomodate all elements.
+    fn try_to_raw_vec_in<S: SingleRangeStorage>(&self, storage: S) -> Result<RawVec<T, S>, StorageError>;
+}
+
+impl<T: Clone> ToRawVecIn<T> for [T] {
+    #[cfg(any(not(feature = "no-panic"), test))]
+    fn to_raw_vec_in<S: SingleRangeStorage>(&self, storage: S) -> RawVec<T, S> {
+        self.iter().cloned().collect_in(storage)
+    }
+
+    fn try_to_raw_vec_in<S: SingleRangeStorage>(&self, storage: S) -> Result<RawVec<T, S>, StorageError> {
+        self.iter().cloned().try_collect_in(storage)
+    }
+}
+
+/// Extension trait for values, to move them into a `RawBox`.
+pub trait BoxedIn: Sized {
+    /// Moves `self` into a `RawBox` using `storage`.
+    ///
+    /// #   Panics
+    ///
+    /// If `storage` cannot accomodate `self`.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    fn boxed_in<S: SingleElementStorage>(self, storage: S) -> RawBox<Self, S> {
+        self.try_boxed_in(storage)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Attempts to move `self` into a `RawBox` using `storage`.
+    ///
+    /// Fails if `storage` cannot accomodate `self`, returning both.
+    fn try_boxed_in<S: SingleElementStorage>(self, storage: S) -> Result<RawBox<Self, S>, (Self, S)> {
+        RawBox::new(self, storage)
+    }
+}
+
+impl<T> BoxedIn for T {}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::{SingleElement, SingleRange};
+
+use super::*;
+
+#[test]
+fn to_raw_vec_in() {
+    let vec = [1u8, 2, 3].to_raw_vec_in(SingleRange::<u8, u8, 4>::new());
+
+    assert_eq!([1, 2, 3], &*vec);
+}
+
+#[test]
+fn try_to_raw_vec_in_failure() {
+    [1u8, 2, 3].try_to_raw_vec_in(SingleRange::<u8, u8, 2>::new()).unwrap_err();
+}
+
+#[test]
+fn boxed_in() {
+    let boxed = 42u32.boxed_in(SingleElement::<u32>::new());
+
+    assert_eq!(42, *boxed);
+}
+
+#[test]
+fn try_boxed_in_failure() {
+    let (value, _) = 42u32.try_boxed_in(SingleElement::<u8>::new()).unwrap_err();
+
+    assert_eq!(42, value);
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::{SingleElement, SingleRange};
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn to_raw_vec_in() {
+    let allocator = SpyAllocator::default();
+
+    let strings = ["Hello".to_string(), "World".to_string()];
+    let vec = strings.to_raw_vec_in(SingleRange::new(allocator.clone()));
+
+    assert_eq!(strings, &*vec);
+    assert_eq!(1, allocator.allocated());
+}
+
+#[test]
+fn boxed_in() {
+    let allocator = SpyAllocator::default();
+
+    let boxed = "Hello".to_string().boxed_in(SingleElement::new(allocator.clone()));
+
+    assert_eq!("Hello", &*boxed);
+    assert_eq!(1, allocator.allocated());
+}
+
+} // mod test_allocator
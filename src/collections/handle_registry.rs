@@ -0,0 +1,315 @@
+//! Proof-of-Concept implementation of a registry of handles, assigning them stable IDs.
+
+use core::fmt::{self, Debug};
+
+use crate::{global, traits::SingleRangeStorage};
+
+use super::RawVec;
+
+/// A PoC HandleRegistry.
+///
+/// Assigns stable `u64` IDs to handles, and resolves IDs back to handles, so that IDs can be sent over the network or
+/// stored while handles remain process-local.
+///
+/// IDs embed a generation, so that the ID of an unregistered handle does not resolve to a handle registered later.
+pub struct HandleRegistry<H: Copy, S: SingleRangeStorage = global::SingleRange> {
+    len: usize,
+    free: u32,
+    entries: RawVec<Entry<H>, S>,
+}
+
+impl<H: Copy, S: SingleRangeStorage> HandleRegistry<H, S> {
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self { Self { len: 0, free: INVALID_FREE, entries: RawVec::new(storage), } }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of handles registered in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Attempts to register `handle`, returning its ID.
+    pub fn try_register(&mut self, handle: H) -> Result<u64, H> {
+        if self.free != INVALID_FREE {
+            let index = self.free;
+            let entry = &mut self.entries[index as usize];
+
+            let generation = match *entry {
+                Entry::Vacant { generation, next } => {
+                    self.free = next;
+                    generation
+                },
+                Entry::Occupied { .. } => unreachable!("Free list to only contain vacant entries"),
+            };
+
+            *entry = Entry::Occupied { generation, handle };
+            self.len += 1;
+
+            return Ok(into_id(index, generation));
+        }
+
+        let index = self.entries.len();
+
+        if index >= INVALID_FREE as usize {
+            return Err(handle);
+        }
+
+        self.entries.try_push(Entry::Occupied { generation: 0, handle }).map_err(|entry| entry.into_handle())?;
+        self.len += 1;
+
+        Ok(into_id(index as u32, 0))
+    }
+
+    /// Registers `handle`, returning its ID.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn register(&mut self, handle: H) -> u64 {
+        self.try_register(handle)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Resolves `id` back into its handle, if still registered.
+    pub fn resolve(&self, id: u64) -> Option<H> {
+        let (index, generation) = from_id(id);
+
+        match self.entries.get(index as usize) {
+            Some(Entry::Occupied { generation: g, handle }) if *g == generation => Some(*handle),
+            _ => None,
+        }
+    }
+
+    /// Unregisters `id`, returning its handle, if still registered.
+    ///
+    /// The ID of the handle is never re-issued; until its slot has been reused 2^32 times, that is.
+    pub fn unregister(&mut self, id: u64) -> Option<H> {
+        let (index, generation) = from_id(id);
+
+        let entry = self.entries.get_mut(index as usize)?;
+
+        let handle = match *entry {
+            Entry::Occupied { generation: g, handle } if g == generation => handle,
+            _ => return None,
+        };
+
+        *entry = Entry::Vacant { generation: generation.wrapping_add(1), next: self.free };
+        self.free = index;
+        self.len -= 1;
+
+        Some(handle)
+    }
+
+    /// Returns the ID of `handle`, if registered.
+    ///
+    /// This is a linear search.
+    pub fn id_of(&self, handle: H) -> Option<u64>
+        where
+            H: PartialEq,
+    {
+        self.entries.iter().enumerate().find_map(|(index, entry)| match *entry {
+            Entry::Occupied { generation, handle: h } if h == handle => Some(into_id(index as u32, generation)),
+            _ => None,
+        })
+    }
+
+    /// Unregisters all handles.
+    ///
+    /// Previously issued IDs are invalidated, and are not re-issued: the entries are kept, and their generations
+    /// bumped, as if each handle had been unregistered.
+    pub fn clear(&mut self) {
+        let mut free = INVALID_FREE;
+
+        //  Iterating in reverse, so that the free list starts with the first entry.
+        for (index, entry) in self.entries.iter_mut().enumerate().rev() {
+            let generation = match *entry {
+                Entry::Occupied { generation, .. } => generation.wrapping_add(1),
+                Entry::Vacant { generation, .. } => generation,
+            };
+
+            *entry = Entry::Vacant { generation, next: free };
+            free = index as u32;
+        }
+
+        self.free = free;
+        self.len = 0;
+    }
+}
+
+impl<H: Copy + Debug, S: SingleRangeStorage> Debug for HandleRegistry<H, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut map = f.debug_map();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Entry::Occupied { generation, handle } = entry {
+                map.entry(&into_id(index as u32, *generation), handle);
+            }
+        }
+
+        map.finish()
+    }
+}
+
+impl<H: Copy, S: Default + SingleRangeStorage> Default for HandleRegistry<H, S> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+//
+//  Implementation
+//
+
+const INVALID_FREE: u32 = u32::MAX;
+
+enum Entry<H> {
+    Occupied { generation: u32, handle: H },
+    Vacant { generation: u32, next: u32 },
+}
+
+impl<H> Entry<H> {
+    fn into_handle(self) -> H {
+        match self {
+            Entry::Occupied { handle, .. } => handle,
+            Entry::Vacant { .. } => unreachable!("Only called on occupied entries"),
+        }
+    }
+}
+
+fn into_id(index: u32, generation: u32) -> u64 { ((generation as u64) << 32) | (index as u64) }
+
+fn from_id(id: u64) -> (u32, u32) { (id as u32, (id >> 32) as u32) }
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::{MultiElement, SingleRange};
+use crate::traits::{ElementStorage, MultiElementStorage};
+
+use super::*;
+
+#[test]
+fn smoke_test() {
+    type Storage = SingleRange<u8, [usize; 3], 4>;
+    type Registry = HandleRegistry<usize, Storage>;
+
+    let mut registry = Registry::default();
+
+    let one = registry.register(1);
+    let two = registry.register(2);
+
+    assert_eq!(2, registry.len());
+    assert_eq!(Some(1), registry.resolve(one));
+    assert_eq!(Some(2), registry.resolve(two));
+    assert_eq!(Some(two), registry.id_of(2));
+    assert_eq!(None, registry.id_of(3));
+}
+
+#[test]
+fn unregister_invalidates() {
+    type Storage = SingleRange<u8, [usize; 3], 4>;
+    type Registry = HandleRegistry<usize, Storage>;
+
+    let mut registry = Registry::default();
+
+    let one = registry.register(1);
+
+    assert_eq!(Some(1), registry.unregister(one));
+    assert_eq!(None, registry.unregister(one));
+    assert!(registry.is_empty());
+
+    let two = registry.register(2);
+
+    assert_ne!(one, two);
+    assert_eq!(None, registry.resolve(one));
+    assert_eq!(Some(2), registry.resolve(two));
+}
+
+#[test]
+fn clear_invalidates() {
+    type Storage = SingleRange<u8, [usize; 3], 4>;
+    type Registry = HandleRegistry<usize, Storage>;
+
+    let mut registry = Registry::default();
+
+    let one = registry.register(1);
+    let two = registry.register(2);
+
+    registry.clear();
+
+    assert!(registry.is_empty());
+    assert_eq!(None, registry.resolve(one));
+    assert_eq!(None, registry.resolve(two));
+
+    let three = registry.register(3);
+    let four = registry.register(4);
+
+    assert_ne!(one, three);
+    assert_ne!(two, four);
+    assert_eq!(None, registry.resolve(one));
+    assert_eq!(None, registry.resolve(two));
+    assert_eq!(Some(3), registry.resolve(three));
+    assert_eq!(Some(4), registry.resolve(four));
+}
+
+#[test]
+fn try_register_failure() {
+    type Storage = SingleRange<u8, [usize; 3], 1>;
+    type Registry = HandleRegistry<usize, Storage>;
+
+    let mut registry = Registry::default();
+
+    registry.register(1);
+
+    assert_eq!(Err(2), registry.try_register(2));
+}
+
+#[test]
+fn storage_handles() {
+    type Handle = <MultiElement<u32, 4> as ElementStorage>::Handle<u32>;
+    type Registry = HandleRegistry<Handle, SingleRange<u8, [usize; 3], 4>>;
+
+    let mut storage = MultiElement::<u32, 4>::new();
+    let mut registry = Registry::default();
+
+    let id = registry.register(storage.create(42u32).unwrap());
+
+    let handle = registry.resolve(id).unwrap();
+
+    assert_eq!(42, unsafe { *storage.resolve(handle).as_ref() });
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn smoke_test() {
+    let allocator = SpyAllocator::default();
+    let mut registry = HandleRegistry::new(SingleRange::new(allocator.clone()));
+
+    let ids: Vec<_> = (0..10).map(|i| registry.register(i)).collect();
+
+    for (i, id) in ids.iter().enumerate() {
+        assert_eq!(Some(i), registry.resolve(*id));
+    }
+
+    drop(registry);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_register_failure() {
+    let mut registry = HandleRegistry::new(SingleRange::new(NonAllocator));
+
+    assert_eq!(Err(1), registry.try_register(1));
+}
+
+} // mod test_allocator
@@ -0,0 +1,267 @@
+//! Proof-of-Concept implementation of an open-addressing hash table parameterized by a Storage.
+//!
+//! The table is shared by the hash-based collections, which provide the hashing and comparison of their elements.
+//!
+//! Elements are stored in a single range of buckets, using linear probing and backward-shift deletion. Since a single
+//! range storage cannot hold 2 ranges at once, growing is achieved by growing the range in place, then rehashing the
+//! elements in place.
+
+use core::{cmp, hash::{BuildHasherDefault, Hasher}, mem, slice};
+
+use crate::{error::StorageError, traits::SingleRangeStorage};
+
+use super::RawVec;
+
+/// The default `BuildHasher` of the hash-based collections.
+pub type DefaultHashBuilder = BuildHasherDefault<FxHasher>;
+
+/// A fast, non-cryptographic, Hasher.
+///
+/// It offers no protection against HashDoS, and should not be used with untrusted keys.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) { self.add(n as u64) }
+
+    fn write_u16(&mut self, n: u16) { self.add(n as u64) }
+
+    fn write_u32(&mut self, n: u32) { self.add(n as u64) }
+
+    fn write_u64(&mut self, n: u64) { self.add(n) }
+
+    fn write_usize(&mut self, n: usize) { self.add(n as u64) }
+
+    fn finish(&self) -> u64 {
+        //  Fold the high bits, which are best mixed, into the low bits, which are used to select the bucket.
+        self.hash ^ (self.hash >> 32)
+    }
+}
+
+//
+//  Implementation
+//
+
+pub(crate) struct RawTable<T, S: SingleRangeStorage> {
+    len: usize,
+    buckets: RawVec<Bucket<T>, S>,
+}
+
+impl<T, S: SingleRangeStorage> RawTable<T, S> {
+    pub(crate) fn new(storage: S) -> Self { Self { len: 0, buckets: RawVec::new(storage), } }
+
+    pub(crate) fn len(&self) -> usize { self.len }
+
+    pub(crate) fn clear(&mut self) {
+        for bucket in self.buckets.iter_mut() {
+            *bucket = Bucket::Empty;
+        }
+
+        self.len = 0;
+    }
+
+    pub(crate) fn get(&self, index: usize) -> &T {
+        match &self.buckets[index] {
+            Bucket::Occupied(value) => value,
+            _ => unreachable!("Index to be occupied"),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, index: usize) -> &mut T {
+        match &mut self.buckets[index] {
+            Bucket::Occupied(value) => value,
+            _ => unreachable!("Index to be occupied"),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> RawTableIter<'_, T> { RawTableIter(self.buckets.iter()) }
+
+    //  Returns the index of the element matching `eq`, if any.
+    pub(crate) fn find<F: Fn(&T) -> bool>(&self, hash: u64, eq: F) -> Option<usize> {
+        let number = self.buckets.len();
+
+        if number == 0 {
+            return None;
+        }
+
+        let mut index = home(hash, number);
+
+        loop {
+            match &self.buckets[index] {
+                Bucket::Empty => return None,
+                Bucket::Occupied(value) if eq(value) => return Some(index),
+                _ => (),
+            }
+
+            index = next(index, number);
+        }
+    }
+
+    //  Inserts `value`, which is assumed not to be present, and returns its index.
+    //
+    //  `hasher` is used to rehash the elements, should the table grow.
+    pub(crate) fn try_insert<H: Fn(&T) -> u64>(&mut self, hash: u64, value: T, hasher: H) -> Result<usize, T> {
+        if self.try_reserve_one(&hasher).is_err() {
+            return Err(value);
+        }
+
+        let number = self.buckets.len();
+        let mut index = home(hash, number);
+
+        while let Bucket::Occupied(_) = self.buckets[index] {
+            index = next(index, number);
+        }
+
+        self.buckets[index] = Bucket::Occupied(value);
+        self.len += 1;
+
+        Ok(index)
+    }
+
+    //  Removes the element at `index`, shifting back the elements of the cluster which follows it.
+    pub(crate) fn remove<H: Fn(&T) -> u64>(&mut self, index: usize, hasher: H) -> T {
+        let number = self.buckets.len();
+
+        let result = match mem::replace(&mut self.buckets[index], Bucket::Empty) {
+            Bucket::Occupied(value) => value,
+            _ => unreachable!("Index to be occupied"),
+        };
+
+        self.len -= 1;
+
+        let mut hole = index;
+        let mut current = next(index, number);
+
+        while let Bucket::Occupied(value) = &self.buckets[current] {
+            let home = home(hasher(value), number);
+
+            //  The element may fill the hole if its home is not within (hole, current], cyclically.
+            if distance(home, current, number) >= distance(hole, current, number) {
+                self.buckets.swap(hole, current);
+                hole = current;
+            }
+
+            current = next(current, number);
+        }
+
+        result
+    }
+}
+
+pub(crate) struct RawTableIter<'a, T>(slice::Iter<'a, Bucket<T>>);
+
+impl<'a, T> Iterator for RawTableIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().find_map(|bucket| match bucket {
+            Bucket::Occupied(value) => Some(value),
+            _ => None,
+        })
+    }
+}
+
+//  The maximum load factor is 7/8th, and at least one bucket is kept empty to terminate probing.
+const LOAD_NUMERATOR: usize = 7;
+const LOAD_DENOMINATOR: usize = 8;
+
+const MINIMUM_BUCKETS: usize = 4;
+
+enum Bucket<T> {
+    Empty,
+    Occupied(T),
+    //  Only used during rehashing, for an element not yet at its final position.
+    Pending(T),
+}
+
+impl<T, S: SingleRangeStorage> RawTable<T, S> {
+    fn try_reserve_one<H: Fn(&T) -> u64>(&mut self, hasher: &H) -> Result<(), StorageError> {
+        let required = self.len + 1;
+        let number = self.buckets.len();
+
+        if required * LOAD_DENOMINATOR <= number * LOAD_NUMERATOR {
+            return Ok(());
+        }
+
+        let target = cmp::max(MINIMUM_BUCKETS, number * 2);
+
+        //  Growth may be partial, in which case the table is rehashed with whatever buckets could be added.
+        let grown = self.buckets.try_grow_for_push();
+
+        while self.buckets.len() < target && self.buckets.try_push(Bucket::Empty).is_ok() {}
+
+        if self.buckets.len() != number {
+            self.rehash(hasher);
+        }
+
+        if required < self.buckets.len() { Ok(()) } else { grown.and(Err(StorageError::capacity_overflow())) }
+    }
+
+    //  Moves every element to its position in the current number of buckets.
+    fn rehash<H: Fn(&T) -> u64>(&mut self, hasher: &H) {
+        let number = self.buckets.len();
+
+        for bucket in self.buckets.iter_mut() {
+            if let Bucket::Occupied(value) = mem::replace(bucket, Bucket::Empty) {
+                *bucket = Bucket::Pending(value);
+            }
+        }
+
+        for start in 0..number {
+            let mut value = match mem::replace(&mut self.buckets[start], Bucket::Empty) {
+                Bucket::Pending(value) => value,
+                other => {
+                    self.buckets[start] = other;
+                    continue;
+                },
+            };
+
+            //  Place `value`, displacing any pending element found in the way, until an empty bucket is found.
+            let mut index = home(hasher(&value), number);
+
+            loop {
+                match mem::replace(&mut self.buckets[index], Bucket::Empty) {
+                    Bucket::Empty => {
+                        self.buckets[index] = Bucket::Occupied(value);
+                        break;
+                    },
+                    Bucket::Pending(displaced) => {
+                        self.buckets[index] = Bucket::Occupied(value);
+                        value = displaced;
+                        index = home(hasher(&value), number);
+                    },
+                    occupied => {
+                        self.buckets[index] = occupied;
+                        index = next(index, number);
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn home(hash: u64, number: usize) -> usize { (hash % number as u64) as usize }
+
+fn next(index: usize, number: usize) -> usize { if index + 1 == number { 0 } else { index + 1 } }
+
+//  Returns the distance from `from` to `to`, cyclically.
+fn distance(from: usize, to: usize, number: usize) -> usize { if from <= to { to - from } else { to + number - from } }
+
+impl FxHasher {
+    fn add(&mut self, word: u64) {
+        const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
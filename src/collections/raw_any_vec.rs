@@ -0,0 +1,543 @@
+//! Proof-of-Concept implementation of a type-erased Vec parameterized by a Storage.
+//!
+//! The elements are described by a `Layout`, and an optional drop function, known only at run-time; this suits the
+//! columns of an Entity-Component-System, whose component types are only known through a registry.
+//!
+//! As the alignment of the elements is only known at run-time, the vector is built atop the untyped `Storage`, upon
+//! which the typed range storages are themselves layered, see `adapter::Typed`.
+
+use core::{
+    alloc::Layout,
+    cmp,
+    fmt::{self, Debug},
+    mem,
+    ptr::{self, NonNull},
+    slice,
+};
+
+use crate::{error::StorageError, global, traits::Storage};
+
+/// A type-erased drop function, dropping the element pointed to.
+pub type RawAnyDrop = unsafe fn(NonNull<u8>);
+
+/// A PoC type-erased Vec.
+///
+/// The elements are untyped blocks of bytes, and may only be accessed as a specific type through unsafe views.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawAnyVec<S: Storage = global::MultiElement> {
+    //  Layout of one element, padded to its alignment.
+    layout: Layout,
+    drop: Option<RawAnyDrop>,
+    len: usize,
+    capacity: usize,
+    block: Option<S::Handle>,
+    storage: S,
+}
+
+impl<S: Storage> RawAnyVec<S> {
+    /// Creates a new, empty, instance for elements of `layout`, destroyed by `drop` if any.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `drop`, if any, may be invoked on any element pushed into `self`.
+    pub unsafe fn new(layout: Layout, drop: Option<RawAnyDrop>, storage: S) -> Self {
+        let layout = layout.pad_to_align();
+        let capacity = if layout.size() == 0 { usize::MAX } else { 0 };
+
+        Self { layout, drop, len: 0, capacity, block: None, storage }
+    }
+
+    /// Creates a new, empty, instance for elements of type `T`.
+    pub fn new_for<T>(storage: S) -> Self {
+        let drop: Option<RawAnyDrop> = if mem::needs_drop::<T>() { Some(drop_erased::<T>) } else { None };
+
+        //  Safety:
+        //  -   `drop`, if any, drops a `T`, which all elements are.
+        unsafe { Self::new(Layout::new::<T>(), drop, storage) }
+    }
+
+    /// Returns the layout of the elements, padded to its alignment.
+    pub fn layout(&self) -> Layout { self.layout }
+
+    /// Returns whether the elements are of type `T`, according to their layout.
+    pub fn is_layout_of<T>(&self) -> bool { self.layout == Layout::new::<T>() }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Returns the number of elements `self` can hold without growing.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Returns a pointer to the element at `index`, if any.
+    pub fn get(&self, index: usize) -> Option<NonNull<u8>> {
+        if index >= self.len {
+            return None;
+        }
+
+        //  Safety:
+        //  -   `index` is within bounds.
+        Some(unsafe { self.element(index) })
+    }
+
+    /// Returns a pointer to the element at `index`, if any, for writing.
+    pub fn get_mut(&mut self, index: usize) -> Option<NonNull<u8>> {
+        if index >= self.len {
+            return None;
+        }
+
+        //  Safety:
+        //  -   `index` is within bounds.
+        Some(unsafe { self.element_mut(index) })
+    }
+
+    /// Attempts to ensure there is room for at least `additional` more elements.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), StorageError> {
+        let required = self.len.checked_add(additional).ok_or_else(StorageError::capacity_overflow)?;
+
+        if required <= self.capacity {
+            return Ok(());
+        }
+
+        let amortized = cmp::max(self.capacity.saturating_mul(2), MINIMUM_CAPACITY);
+
+        self.try_grow_to(cmp::max(required, amortized))
+            .or_else(|_| self.try_grow_to(required))
+    }
+
+    /// Ensures there is room for at least `additional` more elements.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).expect("Sufficient capacity");
+    }
+
+    /// Attempts to push the element pointed to by `element` at the back, moving its bytes.
+    ///
+    /// On success, the element is owned by `self`; on failure, it is left untouched.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `element` points to a valid element of the layout of `self`.
+    pub unsafe fn try_push(&mut self, element: NonNull<u8>) -> Result<(), StorageError> {
+        self.try_reserve(1)?;
+
+        let slot = self.element_mut(self.len);
+
+        ptr::copy_nonoverlapping(element.as_ptr(), slot.as_ptr(), self.layout.size());
+
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Attempts to push `value` at the back, returning it on failure.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that the elements of `self` are of type `T`.
+    pub unsafe fn try_push_as<T>(&mut self, value: T) -> Result<(), T> {
+        debug_assert!(self.is_layout_of::<T>());
+
+        let value = mem::ManuallyDrop::new(value);
+
+        match self.try_push(NonNull::from(&*value).cast()) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(mem::ManuallyDrop::into_inner(value)),
+        }
+    }
+
+    /// Pushes `value` at the back.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that the elements of `self` are of type `T`.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub unsafe fn push_as<T>(&mut self, value: T) {
+        self.try_push_as(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity");
+    }
+
+    /// Pops the last element, if any, moving its bytes into `destination`.
+    ///
+    /// Returns whether an element was popped.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `destination` is valid for writing an element of the layout of `self`.
+    pub unsafe fn pop_to(&mut self, destination: NonNull<u8>) -> bool {
+        if self.len == 0 {
+            return false;
+        }
+
+        self.len -= 1;
+
+        let element = self.element_mut(self.len);
+
+        ptr::copy_nonoverlapping(element.as_ptr(), destination.as_ptr(), self.layout.size());
+
+        true
+    }
+
+    /// Pops the last element, if any.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that the elements of `self` are of type `T`.
+    pub unsafe fn pop_as<T>(&mut self) -> Option<T> {
+        debug_assert!(self.is_layout_of::<T>());
+
+        let mut value = mem::MaybeUninit::<T>::uninit();
+
+        self.pop_to(NonNull::from(&mut value).cast()).then(|| value.assume_init())
+    }
+
+    /// Removes the element at `index`, moving its bytes into `destination`, and replacing it by the last element.
+    ///
+    /// Returns whether an element was removed, that is whether `index` is within bounds.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `destination` is valid for writing an element of the layout of `self`.
+    pub unsafe fn swap_remove_to(&mut self, index: usize, destination: NonNull<u8>) -> bool {
+        if index >= self.len {
+            return false;
+        }
+
+        let size = self.layout.size();
+        let (element, last) = (self.element_mut(index), self.element_mut(self.len - 1));
+
+        ptr::copy_nonoverlapping(element.as_ptr(), destination.as_ptr(), size);
+        ptr::copy(last.as_ptr(), element.as_ptr(), size);
+
+        self.len -= 1;
+
+        true
+    }
+
+    /// Removes, and destroys, the element at `index`, replacing it by the last element.
+    ///
+    /// Returns whether an element was removed, that is whether `index` is within bounds.
+    pub fn swap_remove(&mut self, index: usize) -> bool {
+        if index >= self.len {
+            return false;
+        }
+
+        let last = self.len - 1;
+
+        //  Safety:
+        //  -   `index` and `last` are within bounds.
+        //  -   The removed element is moved to `last`, which is no longer considered initialized before it is dropped,
+        //      hence a panicking drop neither drops it twice nor loses the former last element.
+        unsafe {
+            let (element, last_element) = (self.element_mut(index), self.element_mut(last));
+
+            if index != last {
+                ptr::swap_nonoverlapping(element.as_ptr(), last_element.as_ptr(), self.layout.size());
+            }
+
+            self.len = last;
+
+            if let Some(drop) = self.drop {
+                drop(last_element);
+            }
+        }
+
+        true
+    }
+
+    /// Truncates `self` to `len` elements, destroying the elements beyond.
+    ///
+    /// Does nothing if `len` is greater than the current number of elements.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+
+            if let Some(drop) = self.drop {
+                //  Safety:
+                //  -   `self.len` was within bounds, and is no longer considered initialized.
+                unsafe { drop(self.element_mut(self.len)) };
+            }
+        }
+    }
+
+    /// Clears `self`, destroying all elements.
+    pub fn clear(&mut self) { self.truncate(0) }
+
+    /// Returns a typed view of the elements.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that the elements of `self` are of type `T`.
+    pub unsafe fn as_slice<T>(&self) -> &[T] {
+        debug_assert!(self.is_layout_of::<T>());
+
+        slice::from_raw_parts(self.base().cast().as_ptr(), self.len)
+    }
+
+    /// Returns a typed mutable view of the elements.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that the elements of `self` are of type `T`.
+    pub unsafe fn as_mut_slice<T>(&mut self) -> &mut [T] {
+        debug_assert!(self.is_layout_of::<T>());
+
+        slice::from_raw_parts_mut(self.base_mut().cast().as_ptr(), self.len)
+    }
+}
+
+impl<S: Storage> Debug for RawAnyVec<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "RawAnyVec{{ layout: {:?}, len: {}, capacity: {} }}", self.layout, self.len, self.capacity)
+    }
+}
+
+impl<S: Storage> Drop for RawAnyVec<S> {
+    fn drop(&mut self) {
+        self.clear();
+
+        if let Some(block) = self.block.take() {
+            //  Safety:
+            //  -   `block` is valid, and was allocated with the layout of `self.capacity` elements.
+            unsafe { self.storage.deallocate(block, self.block_layout(self.capacity).expect("Valid layout")) };
+        }
+    }
+}
+
+
+//
+//  Implementation
+//
+
+const MINIMUM_CAPACITY: usize = 4;
+
+//  Drops the `T` pointed to by `element`.
+unsafe fn drop_erased<T>(element: NonNull<u8>) { ptr::drop_in_place(element.cast::<T>().as_ptr()) }
+
+impl<S: Storage> RawAnyVec<S> {
+    //  Returns the layout of a block of `capacity` elements, if representable.
+    fn block_layout(&self, capacity: usize) -> Option<Layout> {
+        let size = self.layout.size().checked_mul(capacity)?;
+
+        Layout::from_size_align(size, self.layout.align()).ok()
+    }
+
+    //  Returns a pointer to the first element.
+    fn base(&self) -> NonNull<u8> {
+        match self.block {
+            //  Safety:
+            //  -   `block` is valid, as it is owned by `self`.
+            Some(block) => unsafe { self.storage.resolve(block) },
+            None => dangling(self.layout),
+        }
+    }
+
+    //  Returns a pointer to the first element, for writing.
+    fn base_mut(&mut self) -> NonNull<u8> {
+        match self.block {
+            //  Safety:
+            //  -   `block` is valid, as it is owned by `self`.
+            Some(block) => unsafe { self.storage.resolve_mut(block) },
+            None => dangling(self.layout),
+        }
+    }
+
+    //  Returns a pointer to the element at `index`.
+    //
+    //  #   Safety
+    //
+    //  -   Assumes that `index` is within capacity.
+    unsafe fn element(&self, index: usize) -> NonNull<u8> {
+        debug_assert!(index < self.capacity);
+
+        NonNull::new_unchecked(self.base().as_ptr().add(index * self.layout.size()))
+    }
+
+    //  Returns a pointer to the element at `index`, for writing.
+    //
+    //  #   Safety
+    //
+    //  -   Assumes that `index` is within capacity.
+    unsafe fn element_mut(&mut self, index: usize) -> NonNull<u8> {
+        debug_assert!(index < self.capacity);
+
+        let size = self.layout.size();
+
+        NonNull::new_unchecked(self.base_mut().as_ptr().add(index * size))
+    }
+
+    //  Attempts to grow the block to `capacity` elements.
+    //
+    //  On failure, `self` is left unmodified.
+    fn try_grow_to(&mut self, capacity: usize) -> Result<(), StorageError> {
+        if capacity <= self.capacity {
+            return Ok(());
+        }
+
+        let new_layout = self.block_layout(capacity).ok_or_else(StorageError::capacity_overflow)?;
+
+        let block = match self.block {
+            //  Safety:
+            //  -   `block` is valid, and was allocated with the layout of `self.capacity` elements.
+            Some(block) => unsafe {
+                let old_layout = self.block_layout(self.capacity).expect("Valid layout");
+
+                self.storage.try_grow(block, old_layout, new_layout)?
+            },
+            None => self.storage.allocate(new_layout)?,
+        };
+
+        self.block = Some(block);
+        self.capacity = capacity;
+
+        Ok(())
+    }
+}
+
+//  Returns a dangling, yet aligned, pointer for `layout`.
+fn dangling(layout: Layout) -> NonNull<u8> {
+    //  Safety:
+    //  -   The alignment of a layout is never 0.
+    unsafe { NonNull::new_unchecked(ptr::null_mut::<u8>().wrapping_add(layout.align())) }
+}
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::MultiElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn typed_views() {
+    let allocator = SpyAllocator::default();
+    let mut vec = RawAnyVec::new_for::<String>(MultiElement::new(allocator.clone()));
+
+    unsafe {
+        for i in 0..5 {
+            vec.push_as(i.to_string());
+        }
+
+        vec.as_mut_slice::<String>()[0].push('!');
+
+        assert_eq!(["0!", "1", "2", "3", "4"], vec.as_slice::<String>());
+        assert_eq!(Some("4".to_string()), vec.pop_as::<String>());
+    }
+
+    assert!(vec.swap_remove(1));
+    assert!(!vec.swap_remove(3));
+
+    assert_eq!(["0!", "3", "2"], unsafe { vec.as_slice::<String>() });
+
+    //  Grown from 4 to 8 elements.
+    assert_eq!(8, vec.capacity());
+    assert_eq!(2, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+
+    drop(vec);
+
+    assert_eq!(2, allocator.deallocated());
+}
+
+#[test]
+fn swap_remove_panicking_drop() {
+    use std::{panic::{self, AssertUnwindSafe}, rc::Rc};
+
+    struct PanicOnDrop { _counter: Rc<()>, panic: bool }
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            if self.panic {
+                panic!("Oops");
+            }
+        }
+    }
+
+    let counter = Rc::new(());
+    let mut vec = RawAnyVec::new_for::<PanicOnDrop>(MultiElement::new(SpyAllocator::default()));
+
+    unsafe {
+        vec.push_as(PanicOnDrop { _counter: counter.clone(), panic: true });
+        vec.push_as(PanicOnDrop { _counter: counter.clone(), panic: false });
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| vec.swap_remove(0)));
+
+    assert!(result.is_err());
+    assert_eq!(1, vec.len());
+    assert!(unsafe { !vec.as_slice::<PanicOnDrop>()[0].panic });
+
+    //  The panicking element was dropped once, and the former last element is still there.
+    assert_eq!(2, Rc::strong_count(&counter));
+
+    drop(vec);
+
+    assert_eq!(1, Rc::strong_count(&counter));
+}
+
+#[test]
+fn erased_columns() {
+    let layout = Layout::from_size_align(3, 1).unwrap();
+
+    //  Safety:
+    //  -   The elements are bytes, which need no drop.
+    let mut source = unsafe { RawAnyVec::new(layout, None, MultiElement::new(SpyAllocator::default())) };
+    let mut destination = unsafe { RawAnyVec::new(layout, None, MultiElement::new(SpyAllocator::default())) };
+
+    for element in [[1u8, 2, 3], [4, 5, 6], [7, 8, 9]].iter() {
+        unsafe { source.try_push(NonNull::from(element).cast()).unwrap() };
+    }
+
+    let mut moved = [0u8; 3];
+
+    assert!(unsafe { source.swap_remove_to(0, NonNull::from(&mut moved).cast()) });
+    unsafe { destination.try_push(NonNull::from(&moved).cast()).unwrap() };
+
+    assert_eq!([1, 2, 3], moved);
+    assert_eq!(Some([7, 8, 9]), source.get(0).map(|e| unsafe { *e.cast::<[u8; 3]>().as_ptr() }));
+    assert_eq!(2, source.len());
+    assert_eq!(1, destination.len());
+    assert_eq!(4, destination.capacity());
+}
+
+#[test]
+fn zero_sized() {
+    let allocator = SpyAllocator::default();
+    let mut vec = RawAnyVec::new_for::<()>(MultiElement::new(allocator.clone()));
+
+    unsafe {
+        vec.push_as(());
+        vec.push_as(());
+    }
+
+    assert_eq!(2, vec.len());
+    assert_eq!(usize::MAX, vec.capacity());
+    assert_eq!(0, allocator.allocated());
+}
+
+#[test]
+fn try_reserve_failure() {
+    let mut vec = RawAnyVec::new_for::<u32>(MultiElement::new(NonAllocator));
+
+    vec.try_reserve(1).unwrap_err();
+
+    assert_eq!(Err(1), unsafe { vec.try_push_as(1u32) });
+    assert_eq!(0, vec.capacity());
+}
+
+} // mod test_allocator
@@ -0,0 +1,434 @@
+//! Proof-of-Concept implementation of an Arc, and its Weak counterpart, parameterized by a Storage.
+//!
+//! As for `RawRc`, the reference counts, the value, and the storage itself, are stored together in a single element,
+//! hence the storage must be a `PinningStorage`. The counts are atomic, and the storage is released by whichever thread
+//! drops the last reference, hence the storage must be `Send` and `Sync` for the Arc to be.
+
+use core::{
+    cmp::Ordering as CmpOrdering,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    hint,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::Deref,
+    ptr::{self, NonNull},
+    sync::atomic::{self, AtomicUsize, Ordering},
+};
+
+use crate::{global, traits::{ElementStorage, PinningStorage, SingleElementStorage}};
+
+/// A PoC Arc.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawArc<T, S: SingleElementStorage + PinningStorage = global::SingleElement> {
+    inner: NonNull<ArcInner<T, S>>,
+    _marker: PhantomData<ArcInner<T, S>>,
+}
+
+/// A PoC Weak, a non-owning reference to the value of a RawArc.
+pub struct RawArcWeak<T, S: SingleElementStorage + PinningStorage = global::SingleElement> {
+    inner: NonNull<ArcInner<T, S>>,
+    _marker: PhantomData<ArcInner<T, S>>,
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> RawArc<T, S> {
+    /// Creates an instance of Self, containing `value` stored in `storage`, which is moved alongside it.
+    pub fn new(value: T, mut storage: S) -> Result<Self, (T, S)> {
+        let handle = match storage.allocate::<ArcInner<T, S>>(()) {
+            Ok(handle) => handle,
+            Err(_) => return Err((value, storage)),
+        };
+
+        //  Safety:
+        //  -   `handle` is valid, having just been allocated.
+        let inner = unsafe { storage.resolve_mut(handle) };
+
+        let value = ArcInner {
+            strong: AtomicUsize::new(1),
+            weak: AtomicUsize::new(1),
+            handle,
+            storage: ManuallyDrop::new(storage),
+            value: ManuallyDrop::new(value),
+        };
+
+        //  Safety:
+        //  -   `inner` points to a memory area suitable for `ArcInner<T, S>`.
+        //  -   `inner` remains valid as `storage` is moved, as `S` is a `PinningStorage`.
+        unsafe { ptr::write(inner.as_ptr(), value) };
+
+        Ok(RawArc { inner, _marker: PhantomData })
+    }
+
+    /// Creates an instance of Self, containing `value` stored in a default-constructed storage.
+    pub fn try_new(value: T) -> Result<Self, T>
+        where
+            S: Default,
+    {
+        Self::new(value, S::default()).map_err(|(value, _)| value)
+    }
+
+    /// Creates a RawArcWeak pointing to the same value.
+    pub fn downgrade(this: &Self) -> RawArcWeak<T, S> {
+        let weak = &this.inner().weak;
+        let mut current = weak.load(Ordering::Relaxed);
+
+        loop {
+            //  The weak count is locked by `get_mut`, on another RawArc.
+            if current == LOCKED {
+                hint::spin_loop();
+                current = weak.load(Ordering::Relaxed);
+                continue;
+            }
+
+            match weak.compare_exchange_weak(current, current + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return RawArcWeak { inner: this.inner, _marker: PhantomData },
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Returns the number of RawArc pointing to the same value.
+    ///
+    /// The count may be outdated by the time it is returned, should other threads clone or drop references.
+    pub fn strong_count(this: &Self) -> usize { this.inner().strong.load(Ordering::Acquire) }
+
+    /// Returns the number of RawArcWeak pointing to the same value.
+    ///
+    /// The count may be outdated by the time it is returned, should other threads clone or drop references.
+    pub fn weak_count(this: &Self) -> usize {
+        match this.inner().weak.load(Ordering::Acquire) {
+            //  Only locked when there is no RawArcWeak.
+            LOCKED => 0,
+            weak => weak - 1,
+        }
+    }
+
+    /// Returns whether `this` and `other` point to the same value, or not.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool { this.inner == other.inner }
+
+    /// Returns a mutable reference to the value, if no other RawArc or RawArcWeak point to it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if !Self::is_unique(this) {
+            return None;
+        }
+
+        //  Safety:
+        //  -   `this` is the unique reference to the value.
+        Some(unsafe { &mut (*this.inner.as_ptr()).value })
+    }
+
+    /// Returns the value, if `this` is the only RawArc pointing to it, or `this` otherwise.
+    ///
+    /// The remaining RawArcWeak, if any, can no longer be upgraded.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if this.inner().strong.compare_exchange(1, 0, Ordering::Relaxed, Ordering::Relaxed).is_err() {
+            return Err(this);
+        }
+
+        atomic::fence(Ordering::Acquire);
+
+        let this = ManuallyDrop::new(this);
+
+        //  Safety:
+        //  -   The value is no longer reachable, as the strong count reached 0, hence is moved out exactly once.
+        let value = unsafe { ManuallyDrop::take(&mut (*this.inner.as_ptr()).value) };
+
+        //  Safety:
+        //  -   The implicit weak reference held by the strong references is released.
+        unsafe { ArcInner::release_weak(this.inner) };
+
+        Ok(value)
+    }
+
+    //  Returns whether `this` is the only reference, strong or weak, to the value.
+    //
+    //  The weak count is locked while the strong count is checked, so that no RawArcWeak may be upgraded, then
+    //  dropped, in between; the acquisitions synchronize with the releases of the references dropped by other threads.
+    fn is_unique(this: &mut Self) -> bool {
+        let inner = this.inner();
+
+        if inner.weak.compare_exchange(1, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            return false;
+        }
+
+        let unique = inner.strong.load(Ordering::Acquire) == 1;
+
+        inner.weak.store(1, Ordering::Release);
+
+        unique
+    }
+
+    fn inner(&self) -> &ArcInner<T, S> {
+        //  Safety:
+        //  -   `self.inner` is valid, as long as `self` holds a strong reference.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> RawArcWeak<T, S> {
+    /// Attempts to obtain a RawArc pointing to the same value, if it is still alive.
+    ///
+    /// The weak count cannot be locked by `get_mut` while `self` exists, hence the upgraded RawArc is always accounted
+    /// for by a concurrent `get_mut`.
+    pub fn upgrade(&self) -> Option<RawArc<T, S>> {
+        let strong = &self.inner().strong;
+        let mut current = strong.load(Ordering::Relaxed);
+
+        loop {
+            if current == 0 {
+                return None;
+            }
+
+            match strong.compare_exchange_weak(current, current + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return Some(RawArc { inner: self.inner, _marker: PhantomData }),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Returns the number of RawArc pointing to the same value.
+    pub fn strong_count(&self) -> usize { self.inner().strong.load(Ordering::Acquire) }
+
+    /// Returns whether `self` and `other` point to the same value, or not.
+    pub fn ptr_eq(&self, other: &Self) -> bool { self.inner == other.inner }
+
+    fn inner(&self) -> &ArcInner<T, S> {
+        //  Safety:
+        //  -   `self.inner` is valid, as long as `self` holds a weak reference.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+//  Safety:
+//  -   The value is shared across threads, and dropped by whichever thread releases the last strong reference.
+//  -   The storage is dropped by whichever thread releases the last weak reference.
+unsafe impl<T: Send + Sync, S: SingleElementStorage + PinningStorage + Send + Sync> Send for RawArc<T, S> {}
+
+//  Safety:
+//  -   As per `Send`, since a reference to a RawArc may be cloned into a RawArc.
+unsafe impl<T: Send + Sync, S: SingleElementStorage + PinningStorage + Send + Sync> Sync for RawArc<T, S> {}
+
+//  Safety:
+//  -   As per RawArc, since a RawArcWeak may be upgraded into a RawArc.
+unsafe impl<T: Send + Sync, S: SingleElementStorage + PinningStorage + Send + Sync> Send for RawArcWeak<T, S> {}
+
+//  Safety:
+//  -   As per RawArc, since a RawArcWeak may be upgraded into a RawArc.
+unsafe impl<T: Send + Sync, S: SingleElementStorage + PinningStorage + Send + Sync> Sync for RawArcWeak<T, S> {}
+
+impl<T, S: SingleElementStorage + PinningStorage> Clone for RawArc<T, S> {
+    fn clone(&self) -> Self {
+        self.inner().strong.fetch_add(1, Ordering::Relaxed);
+
+        RawArc { inner: self.inner, _marker: PhantomData }
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Clone for RawArcWeak<T, S> {
+    fn clone(&self) -> Self {
+        self.inner().weak.fetch_add(1, Ordering::Relaxed);
+
+        RawArcWeak { inner: self.inner, _marker: PhantomData }
+    }
+}
+
+impl<T: Debug, S: SingleElementStorage + PinningStorage> Debug for RawArc<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "{:?}", **self) }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Debug for RawArcWeak<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "(Weak)") }
+}
+
+impl<T: Display, S: SingleElementStorage + PinningStorage> Display for RawArc<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "{}", **self) }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Deref for RawArc<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T { &self.inner().value }
+}
+
+impl<T: PartialEq, S: SingleElementStorage + PinningStorage> PartialEq for RawArc<T, S> {
+    fn eq(&self, other: &Self) -> bool { **self == **other }
+}
+
+impl<T: Eq, S: SingleElementStorage + PinningStorage> Eq for RawArc<T, S> {}
+
+impl<T: PartialOrd, S: SingleElementStorage + PinningStorage> PartialOrd for RawArc<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> { (**self).partial_cmp(&**other) }
+}
+
+impl<T: Ord, S: SingleElementStorage + PinningStorage> Ord for RawArc<T, S> {
+    fn cmp(&self, other: &Self) -> CmpOrdering { (**self).cmp(&**other) }
+}
+
+impl<T: Hash, S: SingleElementStorage + PinningStorage> Hash for RawArc<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) { (**self).hash(state) }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Drop for RawArc<T, S> {
+    fn drop(&mut self) {
+        if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        //  Synchronizes with the releases of the other strong references, prior to dropping the value.
+        atomic::fence(Ordering::Acquire);
+
+        //  Safety:
+        //  -   The value is no longer reachable, as the strong count reached 0, hence is dropped exactly once.
+        unsafe { ManuallyDrop::drop(&mut (*self.inner.as_ptr()).value) };
+
+        //  Safety:
+        //  -   The implicit weak reference held by the strong references is released.
+        unsafe { ArcInner::release_weak(self.inner) };
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Drop for RawArcWeak<T, S> {
+    fn drop(&mut self) {
+        //  Safety:
+        //  -   `self` holds a weak reference, which is released.
+        unsafe { ArcInner::release_weak(self.inner) };
+    }
+}
+
+
+//
+//  Implementation
+//
+
+//  Sentinel value of the weak count, while locked by `get_mut`.
+const LOCKED: usize = usize::MAX;
+
+struct ArcInner<T, S: ElementStorage> {
+    strong: AtomicUsize,
+    //  The strong references collectively hold one weak reference.
+    weak: AtomicUsize,
+    handle: S::Handle<Self>,
+    storage: ManuallyDrop<S>,
+    value: ManuallyDrop<T>,
+}
+
+impl<T, S: SingleElementStorage> ArcInner<T, S> {
+    //  Releases a weak reference, deallocating the element if it was the last one.
+    //
+    //  #   Safety
+    //
+    //  -   Assumes that `this` is valid, and that the caller holds a weak reference.
+    unsafe fn release_weak(this: NonNull<Self>) {
+        if this.as_ref().weak.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        //  Synchronizes with the releases of the other weak references, prior to deallocating the element.
+        atomic::fence(Ordering::Acquire);
+
+        //  The storage is moved out of the element, prior to deallocating it.
+        let handle = this.as_ref().handle;
+        let mut storage = ManuallyDrop::take(&mut (*this.as_ptr()).storage);
+
+        storage.deallocate(handle);
+
+        mem::drop(storage);
+    }
+}
+
+#[cfg(test)]
+mod test_allocator {
+
+use std::{alloc::Global, thread};
+
+use crate::allocator::SingleElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn smoke_test() {
+    let allocator = SpyAllocator::default();
+
+    let arc = RawArc::new("Hello".to_string(), SingleElement::new(allocator.clone())).unwrap();
+    let weak = RawArc::downgrade(&arc);
+    let other = arc.clone();
+
+    assert_eq!(2, RawArc::strong_count(&arc));
+    assert_eq!(1, RawArc::weak_count(&arc));
+    assert_eq!("Hello", &*other);
+
+    drop(arc);
+
+    assert_eq!(Err(other.clone()), RawArc::try_unwrap(other.clone()));
+    assert_eq!(Ok("Hello".to_string()), RawArc::try_unwrap(other));
+    assert!(weak.upgrade().is_none());
+    assert_eq!(0, allocator.deallocated());
+
+    drop(weak);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn threads() {
+    let arc = RawArc::new(vec![1u32, 2, 3], SingleElement::new(Global)).unwrap();
+    let weak = RawArc::downgrade(&arc);
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let arc = arc.clone();
+            thread::spawn(move || arc.iter().sum::<u32>())
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(6, handle.join().unwrap());
+    }
+
+    let mut arc = weak.upgrade().unwrap();
+
+    assert_eq!(2, RawArc::strong_count(&arc));
+    assert_eq!(None, RawArc::get_mut(&mut arc));
+
+    drop(weak);
+    drop(arc);
+}
+
+#[test]
+fn get_mut_upgrade_race() {
+    for _ in 0..100 {
+        let mut arc = RawArc::new(0u64, SingleElement::new(Global)).unwrap();
+        let weak = RawArc::downgrade(&arc);
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                let other = weak.upgrade().unwrap();
+                drop(weak);
+
+                //  No mutable reference may be handed out while `other` is alive.
+                let before = *other;
+                thread::yield_now();
+                assert_eq!(before, *other);
+            });
+
+            for _ in 0..1_000 {
+                if let Some(value) = RawArc::get_mut(&mut arc) {
+                    *value += 1;
+                }
+            }
+        });
+
+        assert!(RawArc::get_mut(&mut arc).is_some());
+    }
+}
+
+#[test]
+fn allocation_failure() {
+    let result = RawArc::new(1u32, SingleElement::new(NonAllocator));
+
+    assert_eq!(1, result.map(|_| ()).unwrap_err().0);
+}
+
+} // mod test_allocator
@@ -0,0 +1,878 @@
+//! Proof-of-Concept implementation of a BTreeMap parameterized by a Storage.
+//!
+//! Each node of the tree is an element of a MultiElementStorage, referring to its children through handles.
+//!
+//! The capacity of the nodes, `N`, is a const generic parameter, so that the size of the nodes can be tuned to match
+//! the size of the slots of the storage, see `RawBTreeMap::NODE_SIZE`.
+
+use core::{borrow::Borrow, cmp::Ordering, fmt::{self, Debug}, mem::{self, MaybeUninit}, ptr};
+
+use crate::{global, traits::{ElementStorage, MultiElementStorage}};
+
+/// A PoC BTreeMap.
+///
+/// `N` is the maximum number of keys per node, it must be odd and at least 3.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawBTreeMap<K, V, S: MultiElementStorage = global::MultiElement, const N: usize = 11> {
+    len: usize,
+    root: Option<NodeHandle<K, V, S, N>>,
+    storage: S,
+}
+
+impl<K, V, S: MultiElementStorage, const N: usize> RawBTreeMap<K, V, S, N> {
+    /// The size of a node, in bytes.
+    pub const NODE_SIZE: usize = mem::size_of::<Node<K, V, S, N>>();
+
+    /// The alignment of a node, in bytes.
+    pub const NODE_ALIGN: usize = mem::align_of::<Node<K, V, S, N>>();
+
+    //  Compile-time check of the validity of `N`.
+    const VALID_CAPACITY: () = assert!(N >= 3 && N % 2 == 1, "N must be odd and at least 3");
+
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self {
+        let () = Self::VALID_CAPACITY;
+
+        Self { len: 0, root: None, storage, }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Clears `self`, destroying all elements and deallocating all nodes.
+    pub fn clear(&mut self) {
+        if let Some(root) = self.root.take() {
+            self.destroy_node(root);
+        }
+
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the elements, in order of their keys.
+    pub fn iter(&self) -> RawBTreeMapIter<'_, K, V, S, N> {
+        let mut iter = RawBTreeMapIter { map: self, stack: [(None, 0); MAX_DEPTH], depth: 0, remaining: self.len, };
+
+        iter.descend(self.root);
+
+        iter
+    }
+}
+
+impl<K: Ord, V, S: MultiElementStorage, const N: usize> RawBTreeMap<K, V, S, N> {
+    /// Returns whether `key` is contained in `self`, or not.
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+        where
+            K: Borrow<Q>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns a reference to the value associated to `key`, if any.
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+        where
+            K: Borrow<Q>,
+    {
+        let (handle, index) = self.find(key)?;
+
+        Some(self.node(handle).value(index))
+    }
+
+    /// Returns a mutable reference to the value associated to `key`, if any.
+    pub fn get_mut<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<&mut V>
+        where
+            K: Borrow<Q>,
+    {
+        let (handle, index) = self.find(key)?;
+
+        Some(self.node_mut(handle).value_mut(index))
+    }
+
+    /// Returns the element with the smallest key, if any.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut current = self.root?;
+
+        while let Some(child) = self.node(current).edge(0) {
+            current = child;
+        }
+
+        let node = self.node(current);
+
+        Some((node.key(0), node.value(0)))
+    }
+
+    /// Returns the element with the greatest key, if any.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut current = self.root?;
+
+        while let Some(child) = self.node(current).edge(self.node(current).len) {
+            current = child;
+        }
+
+        let node = self.node(current);
+
+        Some((node.key(node.len - 1), node.value(node.len - 1)))
+    }
+
+    /// Attempts to insert `value` associated to `key`, returning the previous value associated to `key`, if any.
+    ///
+    /// If `key` is already present, the key is left untouched.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if let Some(current) = self.get_mut(&key) {
+            return Ok(Some(mem::replace(current, value)));
+        }
+
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                let mut node = Node::new();
+                node.insert_kv(0, key, value);
+
+                let handle = match self.storage.create(node) {
+                    Ok(handle) => handle,
+                    Err(mut node) => return Err(node.remove_kv(0)),
+                };
+
+                self.root = Some(handle);
+                self.len = 1;
+
+                return Ok(None);
+            },
+        };
+
+        if self.node(root).len == N {
+            let mut node = Node::new();
+            node.first = Some(root);
+
+            let new_root = match self.storage.create(node) {
+                Ok(handle) => handle,
+                Err(_) => return Err((key, value)),
+            };
+
+            if self.split_child(new_root, 0).is_err() {
+                //  Safety:
+                //  -   `new_root` is valid, and contains no element.
+                unsafe { self.storage.deallocate(new_root) };
+                return Err((key, value));
+            }
+
+            self.root = Some(new_root);
+        }
+
+        let mut current = self.root.expect("Non-empty");
+
+        loop {
+            let node = self.node(current);
+            let index = node.search(&key).expect_err("Absent key");
+
+            let child = match node.edge(index) {
+                Some(child) => child,
+                None => {
+                    let node = self.node_mut(current);
+                    node.insert_kv(index, key, value);
+                    node.insert_edge(index + 1, None);
+
+                    self.len += 1;
+
+                    return Ok(None);
+                },
+            };
+
+            if self.node(child).len < N {
+                current = child;
+                continue;
+            }
+
+            if self.split_child(current, index).is_err() {
+                return Err((key, value));
+            }
+
+            let node = self.node(current);
+
+            current = match key.cmp(node.key(index)) {
+                Ordering::Less => node.edge(index),
+                _ => node.edge(index + 1),
+            }.expect("Internal node");
+        }
+    }
+
+    /// Inserts `value` associated to `key`, returning the previous value associated to `key`, if any.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot allocate a node.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes the element associated to `key`, returning its value, if any.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V>
+        where
+            K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the element associated to `key`, returning it, if any.
+    pub fn remove_entry<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<(K, V)>
+        where
+            K: Borrow<Q>,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+
+        let root = self.root.expect("Non-empty");
+        let result = self.remove_from(root, Target::Key(key));
+
+        self.len -= 1;
+        self.shrink_root();
+
+        Some(result)
+    }
+}
+
+impl<K: Debug + Ord, V: Debug, S: MultiElementStorage, const N: usize> Debug for RawBTreeMap<K, V, S, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S: Default + MultiElementStorage, const N: usize> Default for RawBTreeMap<K, V, S, N> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<K, V, S: MultiElementStorage, const N: usize> Drop for RawBTreeMap<K, V, S, N> {
+    fn drop(&mut self) { self.clear(); }
+}
+
+/// Iterator over the elements of a RawBTreeMap, in order of their keys.
+pub struct RawBTreeMapIter<'a, K, V, S: MultiElementStorage, const N: usize> {
+    map: &'a RawBTreeMap<K, V, S, N>,
+    //  The path from the root to the current node, each node alongside the index of its next key.
+    stack: [Cursor<K, V, S, N>; MAX_DEPTH],
+    depth: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V, S: MultiElementStorage, const N: usize> Iterator for RawBTreeMapIter<'a, K, V, S, N> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let map = self.map;
+
+        while self.depth > 0 {
+            let (handle, index) = &mut self.stack[self.depth - 1];
+            let node = map.node(handle.expect("Valid stack"));
+
+            if *index == node.len {
+                self.depth -= 1;
+                continue;
+            }
+
+            let key = *index;
+            *index += 1;
+
+            self.descend(node.edge(key + 1));
+            self.remaining -= 1;
+
+            return Some((node.key(key), node.value(key)));
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining, Some(self.remaining)) }
+}
+
+//
+//  Implementation
+//
+
+type NodeHandle<K, V, S, const N: usize> = <S as ElementStorage>::Handle<Node<K, V, S, N>>;
+
+//  A node of the path of an iterator, alongside the index of its next key; `None` past the path.
+type Cursor<K, V, S, const N: usize> = (Option<NodeHandle<K, V, S, N>>, usize);
+
+//  The maximum depth of a tree: any node has at least 1 key, and any internal node at least 2 children, hence a tree
+//  of depth `d` holds at least `2^(d-1)` keys.
+const MAX_DEPTH: usize = usize::BITS as usize;
+
+impl<'a, K, V, S: MultiElementStorage, const N: usize> RawBTreeMapIter<'a, K, V, S, N> {
+    //  Pushes `current`, and its leftmost descendants, onto the stack.
+    fn descend(&mut self, mut current: Option<NodeHandle<K, V, S, N>>) {
+        while let Some(handle) = current {
+            self.stack[self.depth] = (Some(handle), 0);
+            self.depth += 1;
+
+            current = self.map.node(handle).edge(0);
+        }
+    }
+}
+
+struct Node<K, V, S: ElementStorage, const N: usize> {
+    len: usize,
+    keys: [MaybeUninit<K>; N],
+    values: [MaybeUninit<V>; N],
+    //  The edges, split in 2 to obtain `N + 1` edges. Leaves have no edges.
+    first: Option<S::Handle<Self>>,
+    edges: [Option<S::Handle<Self>>; N],
+}
+
+//  The element targeted by a removal.
+enum Target<'a, Q: ?Sized> {
+    Key(&'a Q),
+    Min,
+    Max,
+}
+
+impl<K, V, S: MultiElementStorage, const N: usize> RawBTreeMap<K, V, S, N> {
+    //  The minimum number of keys of any node but the root.
+    const MINIMUM: usize = N / 2;
+
+    fn node(&self, handle: NodeHandle<K, V, S, N>) -> &Node<K, V, S, N> {
+        //  Safety:
+        //  -   `handle` is valid, as all handles of the tree are.
+        //  -   The lifetime of the node is actually that of `self.storage`.
+        unsafe { &*self.storage.resolve(handle).as_ptr() }
+    }
+
+    fn node_mut(&mut self, handle: NodeHandle<K, V, S, N>) -> &mut Node<K, V, S, N> {
+        //  Safety:
+        //  -   `handle` is valid, as all handles of the tree are.
+        //  -   The lifetime of the node is actually that of `self.storage`.
+        unsafe { &mut *self.storage.resolve_mut(handle).as_ptr() }
+    }
+
+    //  Returns mutable references to 2 distinct nodes.
+    fn nodes_mut(&mut self, a: NodeHandle<K, V, S, N>, b: NodeHandle<K, V, S, N>)
+        -> (&mut Node<K, V, S, N>, &mut Node<K, V, S, N>)
+    {
+        //  Safety:
+        //  -   `a` and `b` are valid, as all handles of the tree are.
+        //  -   `a` and `b` are distinct nodes, hence the references do not alias.
+        unsafe { (&mut *self.storage.resolve_mut(a).as_ptr(), &mut *self.storage.resolve_mut(b).as_ptr()) }
+    }
+
+    //  Destroys the elements of the node and its children, recursively, and deallocates them.
+    fn destroy_node(&mut self, handle: NodeHandle<K, V, S, N>) {
+        let len = self.node(handle).len;
+
+        for index in 0..=len {
+            if let Some(child) = self.node(handle).edge(index) {
+                self.destroy_node(child);
+            }
+        }
+
+        let node = self.node_mut(handle);
+
+        for index in 0..len {
+            //  Safety:
+            //  -   `index < node.len`, hence the key and value are initialized, and never used again.
+            unsafe {
+                ptr::drop_in_place(node.keys[index].as_mut_ptr());
+                ptr::drop_in_place(node.values[index].as_mut_ptr());
+            }
+        }
+
+        //  Safety:
+        //  -   `handle` is valid, and its elements have been destroyed.
+        unsafe { self.storage.deallocate(handle) };
+    }
+
+    //  Splits the full child at `index` of `parent`, which is not full, moving its median key up into `parent`.
+    fn split_child(&mut self, parent: NodeHandle<K, V, S, N>, index: usize) -> Result<(), ()> {
+        let right = self.storage.create(Node::new()).map_err(|_| ())?;
+
+        //  Resolved after creating `right`, as creating may relocate existing elements.
+        let left = self.node(parent).edge(index).expect("Internal node");
+
+        let (left_node, right_node) = self.nodes_mut(left, right);
+
+        debug_assert!(left_node.len == N);
+
+        let median = Self::MINIMUM;
+        let moved = N - median - 1;
+
+        //  Safety:
+        //  -   `median + 1 + moved == N`, hence the keys and values moved are initialized.
+        //  -   `right_node` is empty, hence has room for `moved` keys and values.
+        unsafe {
+            ptr::copy_nonoverlapping(left_node.keys.as_ptr().add(median + 1), right_node.keys.as_mut_ptr(), moved);
+            ptr::copy_nonoverlapping(left_node.values.as_ptr().add(median + 1), right_node.values.as_mut_ptr(), moved);
+        }
+
+        for edge in 0..=moved {
+            right_node.set_edge(edge, left_node.edge(median + 1 + edge));
+            left_node.set_edge(median + 1 + edge, None);
+        }
+
+        right_node.len = moved;
+        left_node.len = median + 1;
+
+        let (key, value) = left_node.remove_kv(median);
+
+        let parent_node = self.node_mut(parent);
+
+        parent_node.insert_kv(index, key, value);
+        parent_node.insert_edge(index + 1, Some(right));
+
+        Ok(())
+    }
+
+    //  Merges the children at `index` and `index + 1` of `parent`, and the key separating them, into the former.
+    fn merge_children(&mut self, parent: NodeHandle<K, V, S, N>, index: usize) {
+        let parent_node = self.node_mut(parent);
+
+        let (key, value) = parent_node.remove_kv(index);
+        let right = parent_node.remove_edge(index + 1).expect("Internal node");
+        let left = parent_node.edge(index).expect("Internal node");
+
+        let (left_node, right_node) = self.nodes_mut(left, right);
+
+        let left_len = left_node.len;
+        let right_len = right_node.len;
+
+        debug_assert!(left_len + 1 + right_len <= N);
+
+        left_node.insert_kv(left_len, key, value);
+
+        //  Safety:
+        //  -   The keys and values of `right_node` are initialized, and moved out of it.
+        //  -   `left_node` has room for them, as checked above.
+        unsafe {
+            let (keys, values) = (left_node.keys.as_mut_ptr(), left_node.values.as_mut_ptr());
+
+            ptr::copy_nonoverlapping(right_node.keys.as_ptr(), keys.add(left_len + 1), right_len);
+            ptr::copy_nonoverlapping(right_node.values.as_ptr(), values.add(left_len + 1), right_len);
+        }
+
+        for edge in 0..=right_len {
+            left_node.set_edge(left_len + 1 + edge, right_node.edge(edge));
+        }
+
+        left_node.len = left_len + 1 + right_len;
+
+        //  Safety:
+        //  -   `right` is valid, and its elements have been moved out.
+        unsafe { self.storage.deallocate(right) };
+    }
+
+    //  Ensures the child at `index` of `parent` has more than the minimum number of keys, returning its new index.
+    fn fill_child(&mut self, parent: NodeHandle<K, V, S, N>, index: usize) -> usize {
+        let parent_node = self.node(parent);
+        let parent_len = parent_node.len;
+
+        let child = parent_node.edge(index).expect("Internal node");
+
+        if self.node(child).len > Self::MINIMUM {
+            return index;
+        }
+
+        if index > 0 {
+            let left = parent_node.edge(index - 1).expect("Internal node");
+
+            if self.node(left).len > Self::MINIMUM {
+                let left_node = self.node_mut(left);
+
+                let (key, value) = left_node.remove_kv(left_node.len - 1);
+                let edge = left_node.remove_edge(left_node.len + 1);
+
+                let (key, value) = self.node_mut(parent).replace_kv(index - 1, key, value);
+
+                let child_node = self.node_mut(child);
+
+                child_node.insert_kv(0, key, value);
+                child_node.insert_edge(0, edge);
+
+                return index;
+            }
+        }
+
+        if index < parent_len {
+            let right = parent_node.edge(index + 1).expect("Internal node");
+
+            if self.node(right).len > Self::MINIMUM {
+                let right_node = self.node_mut(right);
+
+                let (key, value) = right_node.remove_kv(0);
+                let edge = right_node.remove_edge(0);
+
+                let (key, value) = self.node_mut(parent).replace_kv(index, key, value);
+
+                let child_node = self.node_mut(child);
+                let child_len = child_node.len;
+
+                child_node.insert_kv(child_len, key, value);
+                child_node.insert_edge(child_len + 1, edge);
+
+                return index;
+            }
+
+            self.merge_children(parent, index);
+            return index;
+        }
+
+        self.merge_children(parent, index - 1);
+        index - 1
+    }
+
+    //  Removes the targeted element from the sub-tree rooted at `current`, which is assumed to contain it.
+    //
+    //  Unless `current` is the root, it is assumed to have more than the minimum number of keys.
+    fn remove_from<Q: ?Sized + Ord>(&mut self, mut current: NodeHandle<K, V, S, N>, target: Target<'_, Q>) -> (K, V)
+        where
+            K: Borrow<Q>,
+    {
+        loop {
+            let node = self.node(current);
+            let is_leaf = node.is_leaf();
+
+            let position = match &target {
+                Target::Key(key) => node.search(*key),
+                Target::Min => if is_leaf { Ok(0) } else { Err(0) },
+                Target::Max => if is_leaf { Ok(node.len - 1) } else { Err(node.len) },
+            };
+
+            match position {
+                Ok(index) if is_leaf => {
+                    let node = self.node_mut(current);
+
+                    let result = node.remove_kv(index);
+                    node.remove_edge(index + 1);
+
+                    return result;
+                },
+                Ok(index) => {
+                    let left = node.edge(index).expect("Internal node");
+                    let right = node.edge(index + 1).expect("Internal node");
+
+                    if self.node(left).len > Self::MINIMUM {
+                        let (key, value) = self.remove_from(left, Target::Max);
+                        return self.node_mut(current).replace_kv(index, key, value);
+                    }
+
+                    if self.node(right).len > Self::MINIMUM {
+                        let (key, value) = self.remove_from(right, Target::Min);
+                        return self.node_mut(current).replace_kv(index, key, value);
+                    }
+
+                    self.merge_children(current, index);
+                    current = left;
+                },
+                Err(_) if is_leaf => unreachable!("Target to be present"),
+                Err(index) => {
+                    let index = self.fill_child(current, index);
+
+                    current = self.node(current).edge(index).expect("Internal node");
+                },
+            }
+        }
+    }
+
+    //  Removes the root if it has no key, replacing it by its only child, if any.
+    fn shrink_root(&mut self) {
+        let root = match self.root {
+            Some(root) => root,
+            None => return,
+        };
+
+        let node = self.node(root);
+
+        if node.len > 0 {
+            return;
+        }
+
+        self.root = node.edge(0);
+
+        //  Safety:
+        //  -   `root` is valid, and contains no element.
+        unsafe { self.storage.deallocate(root) };
+    }
+}
+
+impl<K: Ord, V, S: MultiElementStorage, const N: usize> RawBTreeMap<K, V, S, N> {
+    fn find<Q: ?Sized + Ord>(&self, key: &Q) -> Option<(NodeHandle<K, V, S, N>, usize)>
+        where
+            K: Borrow<Q>,
+    {
+        let mut current = self.root?;
+
+        loop {
+            let node = self.node(current);
+
+            match node.search(key) {
+                Ok(index) => return Some((current, index)),
+                Err(index) => current = node.edge(index)?,
+            }
+        }
+    }
+}
+
+impl<K, V, S: ElementStorage, const N: usize> Node<K, V, S, N> {
+    fn new() -> Self {
+        Self {
+            len: 0,
+            keys: MaybeUninit::uninit_array(),
+            values: MaybeUninit::uninit_array(),
+            first: None,
+            edges: [None; N],
+        }
+    }
+
+    fn is_leaf(&self) -> bool { self.first.is_none() }
+
+    fn key(&self, index: usize) -> &K {
+        debug_assert!(index < self.len);
+
+        //  Safety:
+        //  -   `index < self.len`, hence the key is initialized.
+        unsafe { &*self.keys[index].as_ptr() }
+    }
+
+    fn value(&self, index: usize) -> &V {
+        debug_assert!(index < self.len);
+
+        //  Safety:
+        //  -   `index < self.len`, hence the value is initialized.
+        unsafe { &*self.values[index].as_ptr() }
+    }
+
+    fn value_mut(&mut self, index: usize) -> &mut V {
+        debug_assert!(index < self.len);
+
+        //  Safety:
+        //  -   `index < self.len`, hence the value is initialized.
+        unsafe { &mut *self.values[index].as_mut_ptr() }
+    }
+
+    fn edge(&self, index: usize) -> Option<S::Handle<Self>> {
+        if index == 0 { self.first } else { self.edges[index - 1] }
+    }
+
+    fn set_edge(&mut self, index: usize, edge: Option<S::Handle<Self>>) {
+        if index == 0 { self.first = edge } else { self.edges[index - 1] = edge }
+    }
+
+    //  Returns the index of the key, or the index of the edge to descend into.
+    fn search<Q: ?Sized + Ord>(&self, key: &Q) -> Result<usize, usize>
+        where
+            K: Borrow<Q>,
+    {
+        for index in 0..self.len {
+            match self.key(index).borrow().cmp(key) {
+                Ordering::Less => continue,
+                Ordering::Equal => return Ok(index),
+                Ordering::Greater => return Err(index),
+            }
+        }
+
+        Err(self.len)
+    }
+
+    //  Inserts the key and value at `index`, shifting the following ones, but not the edges.
+    fn insert_kv(&mut self, index: usize, key: K, value: V) {
+        debug_assert!(self.len < N && index <= self.len);
+
+        //  Safety:
+        //  -   `self.len < N`, hence there is room to shift the keys and values in `[index, self.len)`.
+        unsafe {
+            ptr::copy(self.keys.as_ptr().add(index), self.keys.as_mut_ptr().add(index + 1), self.len - index);
+            ptr::copy(self.values.as_ptr().add(index), self.values.as_mut_ptr().add(index + 1), self.len - index);
+        }
+
+        self.keys[index] = MaybeUninit::new(key);
+        self.values[index] = MaybeUninit::new(value);
+        self.len += 1;
+    }
+
+    //  Inserts the edge at `index`, shifting the following ones; to be called after `insert_kv`.
+    fn insert_edge(&mut self, index: usize, edge: Option<S::Handle<Self>>) {
+        for i in (index..self.len).rev() {
+            self.set_edge(i + 1, self.edge(i));
+        }
+
+        self.set_edge(index, edge);
+    }
+
+    //  Removes the key and value at `index`, shifting the following ones, but not the edges.
+    fn remove_kv(&mut self, index: usize) -> (K, V) {
+        debug_assert!(index < self.len);
+
+        //  Safety:
+        //  -   `index < self.len`, hence the key and value are initialized, and the following ones shifted over them.
+        unsafe {
+            let key = ptr::read(self.keys[index].as_ptr());
+            let value = ptr::read(self.values[index].as_ptr());
+
+            ptr::copy(self.keys.as_ptr().add(index + 1), self.keys.as_mut_ptr().add(index), self.len - index - 1);
+            ptr::copy(self.values.as_ptr().add(index + 1), self.values.as_mut_ptr().add(index), self.len - index - 1);
+
+            self.len -= 1;
+
+            (key, value)
+        }
+    }
+
+    //  Removes the edge at `index`, shifting the following ones; to be called after `remove_kv`.
+    fn remove_edge(&mut self, index: usize) -> Option<S::Handle<Self>> {
+        let result = self.edge(index);
+
+        for i in index..=self.len {
+            self.set_edge(i, self.edge(i + 1));
+        }
+
+        self.set_edge(self.len + 1, None);
+
+        result
+    }
+
+    //  Replaces the key and value at `index`, returning the previous ones.
+    fn replace_kv(&mut self, index: usize, key: K, value: V) -> (K, V) {
+        debug_assert!(index < self.len);
+
+        let key = mem::replace(&mut self.keys[index], MaybeUninit::new(key));
+        let value = mem::replace(&mut self.values[index], MaybeUninit::new(value));
+
+        //  Safety:
+        //  -   `index < self.len`, hence the previous key and value are initialized.
+        unsafe { (key.assume_init(), value.assume_init()) }
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::MultiElement;
+
+use super::*;
+
+type Map = RawBTreeMap<u8, u8, MultiElement<[usize; 6], 8>, 3>;
+
+#[test]
+fn node_size() {
+    assert_eq!(mem::size_of::<[usize; 6]>(), Map::NODE_SIZE);
+    assert_eq!(mem::align_of::<usize>(), Map::NODE_ALIGN);
+}
+
+#[test]
+fn smoke_test() {
+    let mut map = Map::default();
+
+    for i in [5, 3, 8, 1, 4, 7, 9, 2, 6, 0] {
+        assert_eq!(None, map.insert(i, i * 10));
+    }
+
+    assert_eq!(10, map.len());
+    assert_eq!(Some(40), map.insert(4, 44));
+    assert_eq!(Some(&44), map.get(&4));
+    assert_eq!(Some((&0, &0)), map.first_key_value());
+    assert_eq!(Some((&9, &90)), map.last_key_value());
+
+    assert_eq!(
+        "{0: 0, 1: 10, 2: 20, 3: 30, 4: 44, 5: 50, 6: 60, 7: 70, 8: 80, 9: 90}",
+        format!("{:?}", map)
+    );
+
+    for i in [3, 0, 9, 5] {
+        assert_eq!(Some(i * 10), map.remove(&i));
+        assert_eq!(None, map.remove(&i));
+    }
+
+    assert_eq!("{1: 10, 2: 20, 4: 44, 6: 60, 7: 70, 8: 80}", format!("{:?}", map));
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut map = Map::default();
+
+    let mut inserted = 0;
+
+    for i in 0..=255 {
+        match map.try_insert(i, i) {
+            Ok(_) => inserted += 1,
+            Err(kv) => {
+                assert_eq!((i, i), kv);
+                break;
+            },
+        }
+    }
+
+    assert_eq!(inserted, map.len());
+    assert_eq!(inserted, map.iter().count());
+    assert!(map.iter().map(|(k, v)| (*k, *v)).eq((0..inserted as u8).map(|i| (i, i))));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use std::collections::BTreeMap;
+
+use crate::allocator::MultiElement;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+//  Checks against std's BTreeMap, with a pseudo-random sequence of insertions and removals.
+fn check_against_std<const N: usize>() {
+    let allocator = SpyAllocator::default();
+
+    let mut map = RawBTreeMap::<u32, String, _, N>::new(MultiElement::new(allocator.clone()));
+    let mut reference = BTreeMap::new();
+
+    let mut state = 12345u32;
+
+    for _ in 0..2000 {
+        state = state.wrapping_mul(1103515245).wrapping_add(12345);
+        let key = (state >> 16) % 200;
+
+        if state & 3 == 0 {
+            assert_eq!(reference.remove(&key), map.remove(&key));
+        } else {
+            assert_eq!(reference.insert(key, key.to_string()), map.insert(key, key.to_string()));
+        }
+
+        assert_eq!(reference.len(), map.len());
+    }
+
+    assert!(map.iter().eq(reference.iter()));
+
+    drop(map);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn against_std_3() { check_against_std::<3>(); }
+
+#[test]
+fn against_std_5() { check_against_std::<5>(); }
+
+#[test]
+fn against_std_11() { check_against_std::<11>(); }
+
+#[test]
+fn clear_deallocates() {
+    let allocator = SpyAllocator::default();
+
+    let mut map = RawBTreeMap::<_, _, _>::new(MultiElement::new(allocator.clone()));
+
+    for i in 0..100 {
+        map.insert(i, i.to_string());
+    }
+
+    map.clear();
+
+    assert!(map.is_empty());
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+} // mod test_allocator
@@ -0,0 +1,179 @@
+//! Proof-of-Concept implementation of a BTreeSet parameterized by a Storage.
+
+use core::{borrow::Borrow, fmt::{self, Debug}};
+
+use crate::{global, traits::MultiElementStorage};
+
+use super::{RawBTreeMap, RawBTreeMapIter};
+
+/// A PoC BTreeSet.
+///
+/// `N` is the maximum number of elements per node, it must be odd and at least 3.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+///
+/// The set is a thin wrapper around a RawBTreeMap, whose values are `()`.
+pub struct RawBTreeSet<T, S: MultiElementStorage = global::MultiElement, const N: usize = 11> {
+    map: RawBTreeMap<T, (), S, N>,
+}
+
+impl<T, S: MultiElementStorage, const N: usize> RawBTreeSet<T, S, N> {
+    /// The size of a node, in bytes.
+    pub const NODE_SIZE: usize = RawBTreeMap::<T, (), S, N>::NODE_SIZE;
+
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self { Self { map: RawBTreeMap::new(storage) } }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.map.len() }
+
+    /// Clears `self`, destroying all elements and deallocating all nodes.
+    pub fn clear(&mut self) { self.map.clear() }
+
+    /// Returns an iterator over the elements, in order.
+    pub fn iter(&self) -> RawBTreeSetIter<'_, T, S, N> { RawBTreeSetIter(self.map.iter()) }
+}
+
+impl<T: Ord, S: MultiElementStorage, const N: usize> RawBTreeSet<T, S, N> {
+    /// Returns whether `value` is contained in `self`, or not.
+    pub fn contains<Q: ?Sized + Ord>(&self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns the smallest element, if any.
+    pub fn first(&self) -> Option<&T> { self.map.first_key_value().map(|(value, _)| value) }
+
+    /// Returns the largest element, if any.
+    pub fn last(&self) -> Option<&T> { self.map.last_key_value().map(|(value, _)| value) }
+
+    /// Attempts to insert `value`, returning whether it was newly inserted.
+    ///
+    /// If an equal element is already present, it is left untouched.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, T> {
+        self.map.try_insert(value, ()).map(|previous| previous.is_none()).map_err(|(value, _)| value)
+    }
+
+    /// Inserts `value`, returning whether it was newly inserted.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes the element equal to `value`, returning whether it was present.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Removes and returns the element equal to `value`, if any.
+    pub fn take<Q: ?Sized + Ord>(&mut self, value: &Q) -> Option<T>
+        where
+            T: Borrow<Q>,
+    {
+        self.map.remove_entry(value).map(|(value, _)| value)
+    }
+}
+
+impl<T: Debug + Ord, S: MultiElementStorage, const N: usize> Debug for RawBTreeSet<T, S, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: Default + MultiElementStorage, const N: usize> Default for RawBTreeSet<T, S, N> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+/// Iterator over the elements of a RawBTreeSet, in order.
+pub struct RawBTreeSetIter<'a, T, S: MultiElementStorage, const N: usize>(RawBTreeMapIter<'a, T, (), S, N>);
+
+impl<'a, T: Ord, S: MultiElementStorage, const N: usize> Iterator for RawBTreeSetIter<'a, T, S, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(value, _)| value) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::MultiElement;
+
+use super::*;
+
+type Set = RawBTreeSet<u8, MultiElement<[usize; 6], 8>, 3>;
+
+#[test]
+fn smoke_test() {
+    let mut set = Set::default();
+
+    for i in [5, 3, 8, 1, 4, 7, 9, 2, 6, 0] {
+        assert!(set.insert(i));
+    }
+
+    assert!(!set.insert(4));
+    assert_eq!(10, set.len());
+    assert!(set.contains(&4));
+    assert_eq!(Some(&0), set.first());
+    assert_eq!(Some(&9), set.last());
+
+    assert!(set.remove(&4));
+    assert!(!set.remove(&4));
+    assert_eq!(Some(5), set.take(&5));
+
+    assert_eq!(vec![0, 1, 2, 3, 6, 7, 8, 9], set.iter().copied().collect::<Vec<_>>());
+    assert_eq!("{0, 1, 2, 3, 6, 7, 8, 9}", format!("{:?}", set));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::MultiElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn drop_deallocates() {
+    let allocator = SpyAllocator::default();
+
+    let mut set = RawBTreeSet::<String, _, 5>::new(MultiElement::new(allocator.clone()));
+
+    for i in 0..100 {
+        assert!(set.insert(i.to_string()));
+    }
+
+    assert!(set.remove("42"));
+    assert_eq!(99, set.len());
+    assert!(allocator.allocated() > 1);
+
+    drop(set);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut set = RawBTreeSet::<u8, _>::new(MultiElement::new(NonAllocator));
+
+    assert_eq!(Err(1), set.try_insert(1));
+}
+
+} // mod test_allocator
@@ -0,0 +1,597 @@
+//! Proof-of-Concept implementation of a CompactHashMap parameterized by a Storage.
+//!
+//! The map is split in 2 ranges of a single MultiRangeStorage:
+//!
+//! -   The entries, stored densely in a range, in order of insertion unless removals occurred.
+//! -   The buckets, a sparse open-addressing table of indices into the entries.
+//!
+//! Since the buckets are only 4 bytes each, the map is more compact than a table storing the entries in its buckets,
+//! and iteration only ever visits live entries. Removal swaps the last entry into the hole, keeping the entries dense.
+
+use core::{
+    borrow::Borrow,
+    cmp,
+    fmt::{self, Debug},
+    hash::{BuildHasher, Hash},
+    mem::{self, MaybeUninit},
+    ptr,
+    slice,
+};
+
+use crate::{error::StorageError, global, traits::{Capacity, MultiRangeStorage}};
+
+use super::DefaultHashBuilder;
+
+/// A PoC CompactHashMap.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawCompactHashMap<K, V, S: MultiRangeStorage = global::MultiRange, H = DefaultHashBuilder> {
+    len: usize,
+    buckets: Option<S::Handle<u32>>,
+    entries: Option<S::Handle<Entry<K, V>>>,
+    storage: S,
+    hasher: H,
+}
+
+impl<K, V, S: MultiRangeStorage, H: Default> RawCompactHashMap<K, V, S, H> {
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self { Self::with_hasher(storage, H::default()) }
+}
+
+impl<K, V, S: MultiRangeStorage, H> RawCompactHashMap<K, V, S, H> {
+    /// Creates a new instance from `storage`, using `hasher` to hash the keys.
+    pub fn with_hasher(storage: S, hasher: H) -> Self {
+        Self { len: 0, buckets: None, entries: None, storage, hasher, }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of entries in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Returns the number of entries `self` may hold without growing.
+    ///
+    /// The storage may grant more entries than requested, yet only as many as half the buckets may be used, so that
+    /// probing always terminates.
+    pub fn capacity(&self) -> usize {
+        //  Safety:
+        //  -   `handle` is valid, as it is owned by `self`.
+        let entries = self.entries.map(|handle| unsafe { self.storage.resolve(handle).len() }).unwrap_or(0);
+
+        cmp::min(entries, self.buckets().len() / BUCKETS_PER_ENTRY)
+    }
+
+    /// Clears `self`, destroying all entries, and retaining the ranges.
+    pub fn clear(&mut self) {
+        let len = mem::replace(&mut self.len, 0);
+
+        let (buckets, entries) = self.parts_mut();
+
+        buckets.fill(EMPTY);
+
+        //  Safety:
+        //  -   The first `len` entries are initialized, and no longer considered so as `self.len` was reset.
+        unsafe { ptr::drop_in_place(MaybeUninit::slice_assume_init_mut(&mut entries[..len])) };
+    }
+
+    /// Returns an iterator over the entries, in order of insertion if no entry was ever removed.
+    pub fn iter(&self) -> RawCompactHashMapIter<'_, K, V> { RawCompactHashMapIter(self.entries().iter()) }
+}
+
+impl<K: Eq + Hash, V, S: MultiRangeStorage, H: BuildHasher> RawCompactHashMap<K, V, S, H> {
+    /// Returns whether an entry for `key` is contained in `self`, or not.
+    pub fn contains_key<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> bool
+        where
+            K: Borrow<Q>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns a reference to the value associated to `key`, if any.
+    pub fn get<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<&V>
+        where
+            K: Borrow<Q>,
+    {
+        self.find(key).map(|(_, index)| &self.entries()[index].value)
+    }
+
+    /// Returns a mutable reference to the value associated to `key`, if any.
+    pub fn get_mut<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<&mut V>
+        where
+            K: Borrow<Q>,
+    {
+        let (_, index) = self.find(key)?;
+
+        //  Safety:
+        //  -   `index` is within the first `len` entries, which are initialized.
+        Some(unsafe { &mut self.parts_mut().1[index].assume_init_mut().value })
+    }
+
+    /// Attempts to insert `value` for `key`, returning the previous value, if any.
+    ///
+    /// If an entry for `key` is already present, its value is replaced, and its key is left untouched.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if let Some(previous) = self.get_mut(&key) {
+            return Ok(Some(mem::replace(previous, value)));
+        }
+
+        if self.len == self.capacity() && self.try_grow().is_err() {
+            return Err((key, value));
+        }
+
+        let hash = self.hasher.hash_one(&key);
+        let index = self.len;
+
+        let (buckets, entries) = self.parts_mut();
+
+        entries[index].write(Entry { hash, key, value });
+
+        let number = buckets.len();
+        let mut position = home(hash, number);
+
+        while buckets[position] != EMPTY {
+            position = next(position, number);
+        }
+
+        buckets[position] = index as u32;
+        self.len += 1;
+
+        Ok(None)
+    }
+
+    /// Inserts `value` for `key`, returning the previous value, if any.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes the entry for `key`, returning its value, if any.
+    ///
+    /// The last entry is moved in place of the removed one, hence the order of iteration is altered.
+    pub fn remove<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<V>
+        where
+            K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the entry for `key`, returning its key and value, if any.
+    ///
+    /// The last entry is moved in place of the removed one, hence the order of iteration is altered.
+    pub fn remove_entry<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<(K, V)>
+        where
+            K: Borrow<Q>,
+    {
+        let (position, index) = self.find(key)?;
+
+        let entry = self.remove_at(position, index);
+
+        Some((entry.key, entry.value))
+    }
+}
+
+impl<K: Debug, V: Debug, S: MultiRangeStorage, H> Debug for RawCompactHashMap<K, V, S, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S: Default + MultiRangeStorage, H: Default> Default for RawCompactHashMap<K, V, S, H> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<K, V, S: MultiRangeStorage, H> Drop for RawCompactHashMap<K, V, S, H> {
+    fn drop(&mut self) {
+        self.clear();
+
+        //  Safety:
+        //  -   The handles are valid, as they are owned by `self`, and the entries were destroyed.
+        unsafe {
+            if let Some(buckets) = self.buckets.take() {
+                self.storage.deallocate(buckets);
+            }
+
+            if let Some(entries) = self.entries.take() {
+                self.storage.deallocate(entries);
+            }
+        }
+    }
+}
+
+/// Iterator over the entries of a RawCompactHashMap.
+pub struct RawCompactHashMapIter<'a, K, V>(slice::Iter<'a, Entry<K, V>>);
+
+impl<'a, K, V> Iterator for RawCompactHashMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|entry| (&entry.key, &entry.value)) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+
+//
+//  Implementation
+//
+
+//  The index of an empty bucket.
+const EMPTY: u32 = u32::MAX;
+
+//  There are twice as many buckets as entries, so that probing always terminates.
+const BUCKETS_PER_ENTRY: usize = 2;
+
+const MINIMUM_CAPACITY: usize = 4;
+
+struct Entry<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+//  The buckets, and the range of entries.
+type Parts<'a, K, V> = (&'a mut [u32], &'a mut [MaybeUninit<Entry<K, V>>]);
+
+impl<K, V, S: MultiRangeStorage, H> RawCompactHashMap<K, V, S, H> {
+    fn buckets(&self) -> &[u32] {
+        match self.buckets {
+            //  Safety:
+            //  -   `handle` is valid, as it is owned by `self`, and all its buckets are initialized.
+            Some(handle) => unsafe { MaybeUninit::slice_assume_init_ref(&*self.storage.resolve(handle).as_ptr()) },
+            None => &[],
+        }
+    }
+
+    fn entries(&self) -> &[Entry<K, V>] {
+        match self.entries {
+            //  Safety:
+            //  -   `handle` is valid, as it is owned by `self`, and its first `len` entries are initialized.
+            Some(handle) => unsafe {
+                let entries = &*self.storage.resolve(handle).as_ptr();
+                MaybeUninit::slice_assume_init_ref(&entries[..self.len])
+            },
+            None => &[],
+        }
+    }
+
+    //  Returns the buckets, and the range of entries, of which only the first `len` are initialized.
+    fn parts_mut(&mut self) -> Parts<'_, K, V> {
+        let (buckets, entries) = match (self.buckets, self.entries) {
+            (Some(buckets), Some(entries)) => (buckets, entries),
+            _ => return (&mut [], &mut []),
+        };
+
+        //  Safety:
+        //  -   The handles are valid, as they are owned by `self`.
+        //  -   The ranges are distinct, hence the slices do not alias, and all buckets are initialized.
+        unsafe {
+            let buckets = self.storage.resolve_mut(buckets);
+            let entries = self.storage.resolve_mut(entries);
+
+            (MaybeUninit::slice_assume_init_mut(&mut *buckets.as_ptr()), &mut *entries.as_ptr())
+        }
+    }
+
+    //  Removes the entry at `index`, referred to by the bucket at `position`.
+    fn remove_at(&mut self, position: usize, index: usize) -> Entry<K, V> {
+        let last = self.len - 1;
+
+        let (buckets, entries) = self.parts_mut();
+        let number = buckets.len();
+
+        //  Shift back the indices of the cluster which follows the removed bucket.
+        buckets[position] = EMPTY;
+
+        let mut hole = position;
+        let mut current = next(position, number);
+
+        while buckets[current] != EMPTY {
+            //  Safety:
+            //  -   All indices within the buckets refer to initialized entries.
+            let home = home(unsafe { entries[buckets[current] as usize].assume_init_ref().hash }, number);
+
+            //  The index may fill the hole if its home is not within (hole, current], cyclically.
+            if distance(home, current, number) >= distance(hole, current, number) {
+                buckets.swap(hole, current);
+                hole = current;
+            }
+
+            current = next(current, number);
+        }
+
+        //  Safety:
+        //  -   `index` refers to an initialized entry, which is no longer referred to.
+        let result = unsafe { entries[index].assume_init_read() };
+
+        if index != last {
+            //  Safety:
+            //  -   `last` refers to an initialized entry, which is moved to the now uninitialized `index`.
+            let moved = unsafe { entries[last].assume_init_read() };
+
+            let mut current = home(moved.hash, number);
+
+            while buckets[current] != last as u32 {
+                current = next(current, number);
+            }
+
+            buckets[current] = index as u32;
+            entries[index].write(moved);
+        }
+
+        self.len -= 1;
+
+        result
+    }
+
+    //  Doubles the capacity, allocating new buckets and growing the entries in place, if possible.
+    fn try_grow(&mut self) -> Result<(), StorageError> {
+        let capacity = cmp::max(MINIMUM_CAPACITY, self.capacity() * 2);
+        let number = capacity.checked_mul(BUCKETS_PER_ENTRY).ok_or_else(StorageError::capacity_overflow)?;
+
+        if number >= EMPTY as usize {
+            return Err(StorageError::capacity_overflow());
+        }
+
+        let new_buckets = self.storage.allocate::<u32>(to_capacity::<S>(number)?)?;
+
+        let new_entries = match self.try_grow_entries(capacity) {
+            Ok(entries) => entries,
+            Err(error) => {
+                //  Safety:
+                //  -   `new_buckets` is valid, having just been allocated.
+                unsafe { self.storage.deallocate(new_buckets) };
+
+                return Err(error);
+            },
+        };
+
+        if let Some(buckets) = self.buckets.replace(new_buckets) {
+            //  Safety:
+            //  -   `buckets` is valid, and no longer referred to.
+            unsafe { self.storage.deallocate(buckets) };
+        }
+
+        self.entries = Some(new_entries);
+
+        //  Rebuild the buckets, from the stored hashes.
+        let len = self.len;
+        let (buckets, entries) = self.parts_mut();
+        let number = buckets.len();
+
+        buckets.fill(EMPTY);
+
+        for (index, entry) in entries[..len].iter().enumerate() {
+            //  Safety:
+            //  -   The first `len` entries are initialized.
+            let mut position = home(unsafe { entry.assume_init_ref().hash }, number);
+
+            while buckets[position] != EMPTY {
+                position = next(position, number);
+            }
+
+            buckets[position] = index as u32;
+        }
+
+        Ok(())
+    }
+
+    //  Grows the range of entries to `capacity`, relocating them to a new range if it cannot be grown.
+    fn try_grow_entries(&mut self, capacity: usize) -> Result<S::Handle<Entry<K, V>>, StorageError> {
+        let capacity = to_capacity::<S>(capacity)?;
+
+        let entries = match self.entries {
+            Some(entries) => entries,
+            None => return self.storage.allocate(capacity),
+        };
+
+        //  Safety:
+        //  -   `entries` is valid, as it is owned by `self`.
+        if let Ok(grown) = unsafe { self.storage.try_grow(entries, capacity) } {
+            return Ok(grown);
+        }
+
+        let grown = self.storage.allocate::<Entry<K, V>>(capacity)?;
+
+        //  Safety:
+        //  -   Both handles are valid, and refer to distinct ranges, the new one being larger.
+        //  -   The first `len` entries are moved bitwise, after which the old range is released.
+        unsafe {
+            let source = self.storage.resolve(entries).as_mut_ptr();
+            let destination = self.storage.resolve_mut(grown).as_mut_ptr();
+
+            ptr::copy_nonoverlapping(source as *const MaybeUninit<Entry<K, V>>, destination, self.len);
+
+            self.storage.deallocate(entries);
+        }
+
+        Ok(grown)
+    }
+}
+
+impl<K: Eq, V, S: MultiRangeStorage, H: BuildHasher> RawCompactHashMap<K, V, S, H> {
+    //  Returns the position of the bucket, and the index of the entry, for `key`, if any.
+    fn find<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<(usize, usize)>
+        where
+            K: Borrow<Q>,
+    {
+        let buckets = self.buckets();
+        let number = buckets.len();
+
+        if self.len == 0 {
+            return None;
+        }
+
+        let hash = self.hasher.hash_one(key);
+        let entries = self.entries();
+
+        let mut position = home(hash, number);
+
+        loop {
+            let index = buckets[position];
+
+            if index == EMPTY {
+                return None;
+            }
+
+            let entry = &entries[index as usize];
+
+            if entry.hash == hash && entry.key.borrow() == key {
+                return Some((position, index as usize));
+            }
+
+            position = next(position, number);
+        }
+    }
+}
+
+fn to_capacity<S: MultiRangeStorage>(capacity: usize) -> Result<S::Capacity, StorageError> {
+    S::Capacity::from_usize(capacity).ok_or_else(StorageError::capacity_overflow)
+}
+
+fn home(hash: u64, number: usize) -> usize { (hash % number as u64) as usize }
+
+fn next(index: usize, number: usize) -> usize { if index + 1 == number { 0 } else { index + 1 } }
+
+//  Returns the distance from `from` to `to`, cyclically.
+fn distance(from: usize, to: usize, number: usize) -> usize { if from <= to { to - from } else { to + number - from } }
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::adapter::Typed;
+use crate::allocator::MultiElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+type Map = RawCompactHashMap<String, u32, Typed<MultiElement<SpyAllocator>>>;
+
+#[test]
+fn smoke_test() {
+    let allocator = SpyAllocator::default();
+    let mut map = Map::new(Typed::new(MultiElement::new(allocator.clone())));
+
+    assert!(map.is_empty());
+    assert_eq!(None, map.get("1"));
+
+    for i in 0..10 {
+        assert_eq!(None, map.insert(i.to_string(), i));
+    }
+
+    assert_eq!(Some(3), map.insert("3".to_string(), 33));
+    assert_eq!(10, map.len());
+    assert_eq!(16, map.capacity());
+    assert_eq!(Some(&33), map.get("3"));
+    assert!(!map.contains_key("10"));
+
+    *map.get_mut("4").unwrap() = 44;
+
+    //  Buckets and entries are allocated as separate ranges, and both are reallocated on growth.
+    assert_eq!(6, allocator.allocated());
+    assert_eq!(4, allocator.deallocated());
+
+    let keys: Vec<_> = map.iter().map(|(key, _)| key.clone()).collect();
+    assert_eq!((0..10).map(|i| i.to_string()).collect::<Vec<_>>(), keys);
+
+    assert_eq!(Some(("2".to_string(), 2)), map.remove_entry("2"));
+    assert_eq!(None, map.remove("2"));
+    assert_eq!(9, map.len());
+
+    //  The last entry took the place of the removed one.
+    let values: Vec<_> = map.iter().map(|(_, value)| *value).collect();
+    assert_eq!(vec![0, 1, 9, 33, 44, 5, 6, 7, 8], values);
+
+    for i in (0..10).filter(|i| *i != 2) {
+        assert!(map.contains_key(&i.to_string()));
+    }
+
+    drop(map);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn clear() {
+    let mut map = Map::new(Typed::new(MultiElement::new(SpyAllocator::default())));
+
+    for i in 0..5 {
+        map.insert(i.to_string(), i);
+    }
+
+    map.clear();
+
+    assert!(map.is_empty());
+    assert_eq!(8, map.capacity());
+    assert_eq!("{}", format!("{:?}", map));
+
+    map.insert("1".to_string(), 1);
+
+    assert_eq!(r#"{"1": 1}"#, format!("{:?}", map));
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut map = RawCompactHashMap::<u32, u32, Typed<MultiElement<NonAllocator>>>::default();
+
+    assert_eq!(Err((1, 2)), map.try_insert(1, 2));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn granted_entries() {
+    use core::ptr::NonNull;
+
+    use crate::traits::RangeStorage;
+
+    type Inner = Typed<MultiElement<SpyAllocator>>;
+
+    //  Grants 4 times as many elements as requested, except for the 4 bytes buckets.
+    struct Granting(Inner);
+
+    fn granted<T>(capacity: usize) -> usize { if mem::size_of::<T>() == 4 { capacity } else { capacity * 4 } }
+
+    impl RangeStorage for Granting {
+        type Handle<T> = <Inner as RangeStorage>::Handle<T>;
+
+        type Capacity = usize;
+
+        fn maximum_capacity<T>(&self) -> usize { usize::MAX }
+
+        unsafe fn deallocate<T>(&mut self, handle: Self::Handle<T>) { self.0.deallocate(handle) }
+
+        unsafe fn resolve<T>(&self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> { self.0.resolve(handle) }
+
+        unsafe fn resolve_mut<T>(&mut self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
+            self.0.resolve_mut(handle)
+        }
+    }
+
+    impl MultiRangeStorage for Granting {
+        fn allocate<T>(&mut self, capacity: usize) -> Result<Self::Handle<T>, StorageError> {
+            self.0.allocate(granted::<T>(capacity))
+        }
+    }
+
+    let allocator = SpyAllocator::default();
+    let mut map = RawCompactHashMap::<u64, u64, _>::new(Granting(Typed::new(MultiElement::new(allocator.clone()))));
+
+    for i in 0..20 {
+        map.insert(i, i);
+    }
+
+    assert_eq!(20, map.len());
+    assert_eq!(32, map.capacity());
+    assert!((0..20).all(|i| map.get(&i) == Some(&i)));
+
+    drop(map);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+} // mod test_allocator
@@ -0,0 +1,241 @@
+//! Proof-of-Concept implementation of a Cow parameterized by a Storage.
+//!
+//! Unlike `alloc::borrow::Cow`, the owned form of a value is a collection parameterized by a Storage, as selected by
+//! the `ToOwnedIn` trait, and the storage is carried along the borrowed form, ready for the copy to spill into it.
+
+use core::{
+    fmt::{self, Debug, Display},
+    mem,
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+use crate::error::StorageError;
+
+/// Conversion of a borrowed value into an owned value, using a storage.
+///
+/// This mirrors `alloc::borrow::ToOwned`:
+///
+/// -   A sized value is cloned into a `RawBox`.
+/// -   A slice is cloned into a `RawVec`.
+/// -   A `str` is copied into a `RawString`.
+pub trait ToOwnedIn<S> {
+    /// The owned form of the value.
+    type Owned: DerefMut<Target = Self>;
+
+    /// Attempts to copy `self` into a new owned value, using `storage`.
+    ///
+    /// Fails if `storage` cannot accomodate the copy, in which case `storage` is returned alongside the error.
+    fn try_to_owned_in(&self, storage: S) -> Result<Self::Owned, (S, StorageError)>;
+}
+
+/// A PoC Cow, either borrowing a value, or owning a copy of it within a storage.
+pub enum RawCow<'a, B: ?Sized + ToOwnedIn<S>, S> {
+    /// A borrowed value, alongside the storage its copy will be made in, if need be.
+    Borrowed(&'a B, S),
+    /// An owned value.
+    Owned(B::Owned),
+}
+
+impl<'a, B: ?Sized + ToOwnedIn<S>, S> RawCow<'a, B, S> {
+    /// Returns whether `self` borrows its value, or not.
+    pub fn is_borrowed(&self) -> bool { matches!(self, RawCow::Borrowed(..)) }
+
+    /// Returns whether `self` owns its value, or not.
+    pub fn is_owned(&self) -> bool { !self.is_borrowed() }
+
+    /// Attempts to convert `self` into its owned form, copying the borrowed value into the storage if need be.
+    pub fn try_into_owned(self) -> Result<B::Owned, StorageError> {
+        match self {
+            RawCow::Borrowed(borrowed, storage) => borrowed.try_to_owned_in(storage).map_err(|(_, error)| error),
+            RawCow::Owned(owned) => Ok(owned),
+        }
+    }
+
+    /// Converts `self` into its owned form, copying the borrowed value into the storage if need be.
+    ///
+    /// #   Panics
+    ///
+    /// If the storage cannot accomodate the copy.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn into_owned(self) -> B::Owned {
+        self.try_into_owned()
+            .expect("Sufficient capacity")
+    }
+
+    /// Attempts to obtain a mutable reference to the owned form, copying the borrowed value into the storage if
+    /// need be.
+    ///
+    /// On failure, `self` is left untouched. Should the copy panic, the process aborts, as the storage is then
+    /// neither borrowed nor owned by `self`.
+    pub fn try_to_mut(&mut self) -> Result<&mut B::Owned, StorageError> {
+        if let RawCow::Borrowed(borrowed, storage) = self {
+            let borrowed: &B = borrowed;
+            let storage: *mut S = storage;
+
+            //  Safety:
+            //  -   `storage` is valid for reads.
+            //  -   The storage is not used through `self` until overwritten below, and `self` is not dropped in the
+            //      meantime, as `guard` aborts on unwinding.
+            let taken = unsafe { ptr::read(storage) };
+            let guard = AbortOnUnwind;
+
+            let result = borrowed.try_to_owned_in(taken);
+
+            mem::forget(guard);
+
+            match result {
+                //  Safety:
+                //  -   `self` is valid for writes, and its storage was moved out, hence nothing is leaked.
+                Ok(owned) => unsafe { ptr::write(self, RawCow::Owned(owned)) },
+                Err((taken, error)) => {
+                    //  Safety:
+                    //  -   `storage` is valid for writes, and its former value was moved out.
+                    unsafe { ptr::write(storage, taken) };
+
+                    return Err(error);
+                },
+            }
+        }
+
+        match self {
+            RawCow::Borrowed(..) => unreachable!("Converted to owned"),
+            RawCow::Owned(owned) => Ok(owned),
+        }
+    }
+
+    /// Obtains a mutable reference to the owned form, copying the borrowed value into the storage if need be.
+    ///
+    /// #   Panics
+    ///
+    /// If the storage cannot accomodate the copy.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn to_mut(&mut self) -> &mut B::Owned {
+        self.try_to_mut()
+            .expect("Sufficient capacity")
+    }
+}
+
+impl<'a, B: ?Sized + ToOwnedIn<S>, S> Deref for RawCow<'a, B, S> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        match self {
+            RawCow::Borrowed(borrowed, _) => borrowed,
+            RawCow::Owned(owned) => owned,
+        }
+    }
+}
+
+impl<'a, B: ?Sized + Debug + ToOwnedIn<S>, S> Debug for RawCow<'a, B, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "{:?}", &**self) }
+}
+
+impl<'a, B: ?Sized + Display + ToOwnedIn<S>, S> Display for RawCow<'a, B, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "{}", &**self) }
+}
+
+//
+//  Implementation
+//
+
+//  Aborts, by panicking during unwinding, unless forgotten.
+struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) { panic!("Storage lost while unwinding") }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::{SingleElement, SingleRange};
+
+use super::*;
+
+#[test]
+fn str_to_mut() {
+    let mut cow = RawCow::<str, _>::Borrowed("Hello", SingleRange::<u8, u8, 12>::new());
+
+    assert!(cow.is_borrowed());
+    assert_eq!("Hello", &*cow);
+
+    cow.to_mut().push_str(", World");
+
+    assert!(cow.is_owned());
+    assert_eq!("Hello, World", &*cow);
+    assert_eq!(r#""Hello, World""#, format!("{:?}", cow));
+}
+
+#[test]
+fn slice_to_mut_failure() {
+    let mut cow = RawCow::<[u8], _>::Borrowed(&[1, 2, 3], SingleRange::<u8, u8, 2>::new());
+
+    cow.try_to_mut().unwrap_err();
+
+    assert!(cow.is_borrowed());
+    assert_eq!([1, 2, 3], &*cow);
+}
+
+#[test]
+fn sized_to_mut_in_carried_storage() {
+    use crate::{adapter::{self, Borrowed}, inline::Obstack};
+
+    let mut arena = Obstack::<[u32; 4]>::new();
+
+    {
+        let value = 42u32;
+        let mut cow = RawCow::Borrowed(&value, adapter::SingleElement::new(Borrowed::new(&mut arena)));
+
+        **cow.to_mut() += 1;
+
+        assert!(cow.is_owned());
+        assert_eq!(43, *cow);
+    }
+
+    assert_eq!(0, arena.used());
+}
+
+#[test]
+fn sized_into_owned() {
+    let value = 42u32;
+    let cow = RawCow::Borrowed(&value, SingleElement::<u32>::new());
+
+    let mut owned = cow.into_owned();
+    *owned += 1;
+
+    assert_eq!(43, *owned);
+    assert_eq!(42, value);
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn borrowed_does_not_allocate() {
+    let allocator = SpyAllocator::default();
+
+    let strings = ["Hello".to_string(), "World".to_string()];
+    let mut cow = RawCow::<[String], _>::Borrowed(&strings, SingleRange::new(allocator.clone()));
+
+    assert_eq!(2, cow.len());
+    assert_eq!(0, allocator.allocated());
+
+    cow.to_mut()[1] = "All".to_string();
+
+    assert_eq!(["Hello".to_string(), "All".to_string()], &*cow);
+    assert_eq!(1, allocator.allocated());
+
+    drop(cow);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+} // mod test_allocator
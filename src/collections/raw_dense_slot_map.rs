@@ -0,0 +1,312 @@
+//! Proof-of-Concept implementation of a dense SlotMap parameterized by a Storage.
+//!
+//! The values are kept packed in a contiguous range, so that iterating over them is cache-friendly, while keys go
+//! through an indirection: each key designates a slot, which records the current position of its value.
+//!
+//! Removal swaps the last value into the position of the removed one, and thus requires updating its slot as well.
+
+use core::fmt::{self, Debug};
+
+use crate::{global, traits::SingleRangeStorage};
+
+use super::RawVec;
+
+/// A PoC dense SlotMap.
+///
+/// Keys embed a generation, so that the key of a removed value does not resolve to a value inserted later.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawDenseSlotMap<T, S: SingleRangeStorage = global::SingleRange> {
+    free: u32,
+    //  The values, packed.
+    values: RawVec<T, S>,
+    //  The slot of each value, for updating it when the value is moved.
+    owners: RawVec<u32, S>,
+    slots: RawVec<Slot, S>,
+}
+
+/// A key designating a value of a RawDenseSlotMap.
+///
+/// Keys are only meaningful for the slot map which created them.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RawDenseSlotMapKey {
+    index: u32,
+    generation: u32,
+}
+
+impl<T, S: SingleRangeStorage> RawDenseSlotMap<T, S> {
+    /// Creates a new instance, with the values stored in `values`, and the indirections in `owners` and `slots`.
+    pub fn new(values: S, owners: S, slots: S) -> Self {
+        let (values, owners, slots) = (RawVec::new(values), RawVec::new(owners), RawVec::new(slots));
+
+        Self { free: INVALID_FREE, values, owners, slots, }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+
+    /// Returns the number of values in `self`.
+    pub fn len(&self) -> usize { self.values.len() }
+
+    /// Returns whether `key` designates a value of `self`, or not.
+    pub fn contains_key(&self, key: RawDenseSlotMapKey) -> bool { self.position(key).is_some() }
+
+    /// Returns a reference to the value designated by `key`, if any.
+    pub fn get(&self, key: RawDenseSlotMapKey) -> Option<&T> {
+        self.position(key).map(|position| &self.values[position])
+    }
+
+    /// Returns a mutable reference to the value designated by `key`, if any.
+    pub fn get_mut(&mut self, key: RawDenseSlotMapKey) -> Option<&mut T> {
+        let position = self.position(key)?;
+
+        Some(&mut self.values[position])
+    }
+
+    /// Attempts to insert `value`, returning its key.
+    pub fn try_insert(&mut self, value: T) -> Result<RawDenseSlotMapKey, T> {
+        let position = self.values.len();
+
+        if position >= INVALID_FREE as usize {
+            return Err(value);
+        }
+
+        if self.values.try_grow_for_push().is_err() || self.owners.try_grow_for_push().is_err() {
+            return Err(value);
+        }
+
+        if self.free == INVALID_FREE && self.slots.try_grow_for_push().is_err() {
+            return Err(value);
+        }
+
+        let key = if self.free != INVALID_FREE {
+            let index = self.free;
+            let slot = &mut self.slots[index as usize];
+
+            let generation = match *slot {
+                Slot::Vacant { generation, next } => {
+                    self.free = next;
+                    generation
+                },
+                Slot::Occupied { .. } => unreachable!("Free list to only contain vacant slots"),
+            };
+
+            *slot = Slot::Occupied { generation, position: position as u32 };
+
+            RawDenseSlotMapKey { index, generation }
+        } else {
+            let index = self.slots.len() as u32;
+
+            //  Safety:
+            //  -   `self.slots` has room for one more element, as per `try_grow_for_push`.
+            unsafe { self.slots.push_unchecked(Slot::Occupied { generation: 0, position: position as u32 }) };
+
+            RawDenseSlotMapKey { index, generation: 0 }
+        };
+
+        //  Safety:
+        //  -   Both have room for one more element, as per `try_grow_for_push`.
+        unsafe {
+            self.values.push_unchecked(value);
+            self.owners.push_unchecked(key.index);
+        }
+
+        Ok(key)
+    }
+
+    /// Inserts `value`, returning its key.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, value: T) -> RawDenseSlotMapKey {
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes the value designated by `key`, if any.
+    ///
+    /// The last value is moved into the position of the removed one.
+    pub fn remove(&mut self, key: RawDenseSlotMapKey) -> Option<T> {
+        let position = self.position(key)?;
+        let last = self.values.len() - 1;
+
+        self.values.swap(position, last);
+        self.owners.swap(position, last);
+
+        let moved = self.owners[position] as usize;
+
+        if let Slot::Occupied { position: p, .. } = &mut self.slots[moved] {
+            *p = position as u32;
+        }
+
+        self.slots[key.index as usize] = Slot::Vacant { generation: key.generation.wrapping_add(1), next: self.free };
+        self.free = key.index;
+
+        self.owners.pop();
+        self.values.pop()
+    }
+
+    /// Removes all values.
+    ///
+    /// Previously issued keys are invalidated, and may be re-issued.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.owners.clear();
+        self.slots.clear();
+        self.free = INVALID_FREE;
+    }
+
+    /// Returns the values, packed, in arbitrary order.
+    pub fn values(&self) -> &[T] { &self.values }
+
+    /// Returns the values, packed, in arbitrary order.
+    pub fn values_mut(&mut self) -> &mut [T] { &mut self.values }
+
+    /// Returns an iterator over the keys and values, in the order of `values`.
+    pub fn iter(&self) -> impl Iterator<Item = (RawDenseSlotMapKey, &T)> {
+        self.owners.iter().zip(self.values.iter()).map(move |(index, value)| (self.key_of(*index), value))
+    }
+}
+
+impl<T: Debug, S: SingleRangeStorage> Debug for RawDenseSlotMap<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: Default + SingleRangeStorage> Default for RawDenseSlotMap<T, S> {
+    fn default() -> Self { Self::new(S::default(), S::default(), S::default()) }
+}
+
+//
+//  Implementation
+//
+
+const INVALID_FREE: u32 = u32::MAX;
+
+enum Slot {
+    Occupied { generation: u32, position: u32 },
+    Vacant { generation: u32, next: u32 },
+}
+
+impl<T, S: SingleRangeStorage> RawDenseSlotMap<T, S> {
+    //  Returns the position of the value designated by `key`, if any.
+    fn position(&self, key: RawDenseSlotMapKey) -> Option<usize> {
+        match self.slots.get(key.index as usize) {
+            Some(Slot::Occupied { generation, position }) if *generation == key.generation => Some(*position as usize),
+            _ => None,
+        }
+    }
+
+    fn key_of(&self, index: u32) -> RawDenseSlotMapKey {
+        match self.slots[index as usize] {
+            Slot::Occupied { generation, .. } => RawDenseSlotMapKey { index, generation },
+            Slot::Vacant { .. } => unreachable!("Owners to only refer to occupied slots"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Map = RawDenseSlotMap<u32, SingleRange<u8, [u32; 3], 4>>;
+
+#[test]
+fn smoke_test() {
+    let mut map = Map::default();
+
+    let one = map.insert(1);
+    let two = map.insert(2);
+
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&1), map.get(one));
+    assert_eq!(Some(&2), map.get(two));
+    assert_eq!(&[1, 2], map.values());
+
+    *map.get_mut(one).unwrap() = 11;
+
+    assert_eq!(format!("{{{:?}: 11, {:?}: 2}}", one, two), format!("{:?}", map));
+}
+
+#[test]
+fn remove_keeps_packed() {
+    let mut map = Map::default();
+
+    let keys: Vec<_> = (0..4).map(|i| map.insert(i)).collect();
+
+    assert_eq!(Some(1), map.remove(keys[1]));
+    assert_eq!(None, map.remove(keys[1]));
+
+    assert_eq!(&[0, 3, 2], map.values());
+    assert_eq!(Some(&3), map.get(keys[3]));
+    assert!(!map.contains_key(keys[1]));
+
+    let four = map.insert(4);
+
+    assert_ne!(keys[1], four);
+    assert_eq!(None, map.get(keys[1]));
+    assert_eq!(Some(&4), map.get(four));
+
+    for (key, value) in map.iter() {
+        assert_eq!(Some(value), map.get(key));
+    }
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut map = Map::default();
+
+    for i in 0..4 {
+        map.insert(i);
+    }
+
+    assert_eq!(Err(4), map.try_insert(4));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn churn() {
+    let allocator = SpyAllocator::default();
+
+    let mut map = RawDenseSlotMap::new(
+        SingleRange::new(allocator.clone()),
+        SingleRange::new(allocator.clone()),
+        SingleRange::new(allocator.clone()),
+    );
+
+    let mut keys: Vec<_> = (0..100).map(|i| (map.insert(i.to_string()), i)).collect();
+
+    for (key, _) in keys.iter().step_by(3) {
+        assert!(map.remove(*key).is_some());
+    }
+
+    keys.retain(|(key, _)| map.contains_key(*key));
+
+    assert_eq!(66, map.len());
+
+    for (key, i) in &keys {
+        assert_eq!(Some(&i.to_string()), map.get(*key));
+    }
+
+    drop(map);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+} // mod test_allocator
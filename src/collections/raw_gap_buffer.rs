@@ -0,0 +1,382 @@
+//! Proof-of-Concept implementation of a GapBuffer parameterized by a Storage.
+//!
+//! A gap buffer stores its elements in a single range, split in two by a gap located at the cursor, so that inserting
+//! or deleting at the cursor is O(1), and moving the cursor is O(distance).
+
+use core::{cmp, fmt::{self, Debug}, mem::MaybeUninit, ptr};
+
+use crate::{error::StorageError, global, traits::{Capacity, SingleRangeStorage}};
+
+/// A PoC GapBuffer.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawGapBuffer<T, S: SingleRangeStorage = global::SingleRange> {
+    //  Number of elements before the gap, which is also the position of the cursor.
+    front: usize,
+    //  Number of elements after the gap, at the very end of the range.
+    back: usize,
+    data: S::Handle<T>,
+    storage: S,
+}
+
+impl<T, S: SingleRangeStorage> RawGapBuffer<T, S> {
+    /// Creates a new instance.
+    pub fn new(mut storage: S) -> Self {
+        let zero = S::Capacity::from_usize(0).expect("0 <= S::maximum_capacity()");
+
+        let data = storage.allocate(zero).expect("Zero-capacity allocation should always succeed");
+
+        Self { front: 0, back: 0, data, storage, }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.front + self.back }
+
+    /// Returns the position of the cursor, that is the number of elements before it.
+    pub fn cursor(&self) -> usize { self.front }
+
+    /// Returns a reference to the element at `index`, if any.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let (front, back) = self.as_slices();
+
+        if index < front.len() { front.get(index) } else { back.get(index - front.len()) }
+    }
+
+    /// Returns the elements before, and after, the cursor.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let capacity = self.capacity();
+        let slice = self.raw_slice();
+
+        //  Safety:
+        //  -   Invariant: `self.front + self.back <= capacity`.
+        let (front, back) = unsafe { (slice.get_unchecked(0..self.front), slice.get_unchecked(capacity - self.back..)) };
+
+        //  Safety:
+        //  -   Invariant: `[0, self.front)` and `[capacity - self.back, capacity)` are initialized.
+        unsafe { (MaybeUninit::slice_assume_init_ref(front), MaybeUninit::slice_assume_init_ref(back)) }
+    }
+
+    /// Returns an iterator over the elements, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let (front, back) = self.as_slices();
+
+        front.iter().chain(back.iter())
+    }
+
+    /// Moves the cursor one element to the left, returning whether it moved.
+    pub fn move_left(&mut self) -> bool {
+        if self.front == 0 {
+            return false;
+        }
+
+        self.set_cursor(self.front - 1);
+        true
+    }
+
+    /// Moves the cursor one element to the right, returning whether it moved.
+    pub fn move_right(&mut self) -> bool {
+        if self.back == 0 {
+            return false;
+        }
+
+        self.set_cursor(self.front + 1);
+        true
+    }
+
+    /// Moves the cursor to `position`, clamped to `self.len()`.
+    pub fn set_cursor(&mut self, position: usize) {
+        let position = cmp::min(position, self.len());
+        let capacity = self.capacity();
+        let gap = capacity - self.len();
+
+        let base = self.raw_slice_mut().as_mut_ptr();
+
+        if position < self.front {
+            let count = self.front - position;
+
+            //  Safety:
+            //  -   `[position, self.front)` is initialized, and moved to the end of the gap, which is uninitialized.
+            unsafe { ptr::copy(base.add(position), base.add(position + gap), count) };
+
+            self.front -= count;
+            self.back += count;
+        } else if position > self.front {
+            let count = position - self.front;
+
+            //  Safety:
+            //  -   The first `count` elements after the gap are initialized, and moved to the start of the gap.
+            unsafe { ptr::copy(base.add(self.front + gap), base.add(self.front), count) };
+
+            self.front += count;
+            self.back -= count;
+        }
+    }
+
+    /// Attempts to insert `value` at the cursor, moving the cursor past it.
+    pub fn try_insert(&mut self, value: T) -> Result<(), T> {
+        if self.len() == self.capacity() && self.try_grow().is_err() {
+            return Err(value);
+        }
+
+        let front = self.front;
+
+        //  Safety:
+        //  -   `front < capacity`, as there is room in the gap.
+        let slot = unsafe { self.raw_slice_mut().get_unchecked_mut(front) };
+
+        slot.write(value);
+
+        self.front += 1;
+
+        Ok(())
+    }
+
+    /// Inserts `value` at the cursor, moving the cursor past it.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, value: T) {
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity");
+    }
+
+    /// Removes the element before the cursor, if any, as a backspace would.
+    pub fn delete_before(&mut self) -> Option<T> {
+        if self.front == 0 {
+            return None;
+        }
+
+        self.front -= 1;
+
+        let front = self.front;
+
+        //  Safety:
+        //  -   `front` is within bounds, and initialized.
+        let slot = unsafe { self.raw_slice().get_unchecked(front) };
+
+        //  Safety:
+        //  -   `slot` is initialized, and no longer considered so.
+        Some(unsafe { ptr::read(slot.as_ptr()) })
+    }
+
+    /// Removes the element after the cursor, if any, as a delete would.
+    pub fn delete_after(&mut self) -> Option<T> {
+        if self.back == 0 {
+            return None;
+        }
+
+        let index = self.capacity() - self.back;
+
+        self.back -= 1;
+
+        //  Safety:
+        //  -   `index` is within bounds, and initialized.
+        let slot = unsafe { self.raw_slice().get_unchecked(index) };
+
+        //  Safety:
+        //  -   `slot` is initialized, and no longer considered so.
+        Some(unsafe { ptr::read(slot.as_ptr()) })
+    }
+
+    /// Clears `self`, destroying all elements and resetting the cursor to 0.
+    pub fn clear(&mut self) {
+        while self.delete_before().is_some() {}
+        while self.delete_after().is_some() {}
+    }
+}
+
+impl<T: Debug, S: SingleRangeStorage> Debug for RawGapBuffer<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: Default + SingleRangeStorage> Default for RawGapBuffer<T, S> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<T, S: SingleRangeStorage> Drop for RawGapBuffer<T, S> {
+    fn drop(&mut self) {
+        self.clear();
+
+        //  Safety:
+        //  -   `self.data` is valid.
+        unsafe { self.storage.deallocate(self.data) };
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<T, S: SingleRangeStorage> RawGapBuffer<T, S> {
+    fn capacity(&self) -> usize { self.raw_slice().len() }
+
+    fn raw_slice(&self) -> &[MaybeUninit<T>] {
+        //  Safety:
+        //  -   `self.data` is valid and points to valid data.
+        let range = unsafe { self.storage.resolve(self.data) };
+
+        //  Safety:
+        //  -   `range` points to valid data.
+        //  -   The lifetime of the slice is actually that of `self.storage`.
+        unsafe { &*range.as_ptr() }
+    }
+
+    fn raw_slice_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        //  Safety:
+        //  -   `self.data` is valid and points to valid data.
+        let range = unsafe { self.storage.resolve_mut(self.data) };
+
+        //  Safety:
+        //  -   `range` points to valid data.
+        //  -   The lifetime of the slice is actually that of `self.storage`.
+        unsafe { &mut *range.as_ptr() }
+    }
+
+    //  Grows geometrically, moving the elements after the gap to the end of the new range.
+    #[inline(never)]
+    fn try_grow(&mut self) -> Result<(), StorageError> {
+        let old_capacity = self.capacity();
+        let new_capacity = match old_capacity {
+            0 => S::Capacity::from_usize(1),
+            _ => S::Capacity::from_usize(old_capacity).map(Capacity::saturating_double),
+        };
+        let new_capacity = new_capacity
+            .filter(|c| c.into_usize() > old_capacity)
+            .ok_or_else(StorageError::capacity_overflow)?;
+
+        //  Safety:
+        //  -   `self.data` is a valid handle pointing to valid data.
+        self.data = unsafe { self.storage.try_grow(self.data, new_capacity)? };
+
+        let new_capacity = self.capacity();
+        let back = self.back;
+        let base = self.raw_slice_mut().as_mut_ptr();
+
+        //  Safety:
+        //  -   The elements after the gap were preserved at the end of the old range, and are moved to the end of the
+        //      new range.
+        unsafe { ptr::copy(base.add(old_capacity - back), base.add(new_capacity - back), back) };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Buffer = RawGapBuffer<u8, SingleRange<u8, u8, 8>>;
+
+#[test]
+fn smoke_test() {
+    let mut buffer = Buffer::default();
+
+    for c in b"Hello" {
+        buffer.insert(*c);
+    }
+
+    assert!(buffer.move_left());
+    assert!(buffer.move_left());
+    assert_eq!(3, buffer.cursor());
+
+    buffer.insert(b'X');
+
+    assert_eq!((b"HelX".as_slice(), b"lo".as_slice()), buffer.as_slices());
+    assert_eq!(Some(b'X'), buffer.delete_before());
+    assert_eq!(Some(b'l'), buffer.delete_after());
+    assert_eq!(Some(&b'o'), buffer.get(3));
+
+    buffer.set_cursor(usize::MAX);
+
+    assert_eq!(4, buffer.cursor());
+    assert!(!buffer.move_right());
+    assert_eq!(None, buffer.delete_after());
+    assert_eq!("[72, 101, 108, 111]", format!("{:?}", buffer));
+}
+
+#[test]
+fn set_cursor() {
+    let mut buffer = Buffer::default();
+
+    for c in b"abcdef" {
+        buffer.insert(*c);
+    }
+
+    buffer.set_cursor(1);
+
+    assert_eq!((b"a".as_slice(), b"bcdef".as_slice()), buffer.as_slices());
+
+    buffer.set_cursor(4);
+
+    assert_eq!((b"abcd".as_slice(), b"ef".as_slice()), buffer.as_slices());
+
+    buffer.set_cursor(0);
+    buffer.insert(b'>');
+
+    assert_eq!(b">abcdef".as_slice(), &buffer.iter().copied().collect::<Vec<_>>()[..]);
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut buffer = Buffer::default();
+
+    for c in b"abcdefgh" {
+        buffer.insert(*c);
+    }
+
+    assert_eq!(Err(b'!'), buffer.try_insert(b'!'));
+    assert_eq!(8, buffer.len());
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn grow_preserves_back() {
+    let allocator = SpyAllocator::default();
+
+    let mut buffer = RawGapBuffer::new(SingleRange::new(allocator.clone()));
+
+    buffer.insert("a".to_string());
+    buffer.insert("z".to_string());
+    buffer.move_left();
+
+    for i in 0..10 {
+        buffer.insert(i.to_string());
+    }
+
+    assert_eq!(11, buffer.cursor());
+    assert_eq!(Some(&"z".to_string()), buffer.get(11));
+    assert_eq!(Some(&"9".to_string()), buffer.get(10));
+
+    drop(buffer);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut buffer = RawGapBuffer::new(SingleRange::new(NonAllocator));
+
+    assert_eq!(Err(1), buffer.try_insert(1));
+}
+
+} // mod test_allocator
@@ -0,0 +1,375 @@
+//! Proof-of-Concept implementation of a HashMap parameterized by a Storage.
+
+use core::{borrow::Borrow, fmt::{self, Debug}, hash::{BuildHasher, Hash}, mem};
+
+use crate::{global, traits::SingleRangeStorage};
+
+use super::{DefaultHashBuilder, hash_table::{RawTable, RawTableIter}};
+
+/// A PoC HashMap.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawHashMap<K, V, S: SingleRangeStorage = global::SingleRange, H = DefaultHashBuilder> {
+    table: RawTable<(K, V), S>,
+    hasher: H,
+}
+
+impl<K, V, S: SingleRangeStorage, H: Default> RawHashMap<K, V, S, H> {
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self { Self::with_hasher(storage, H::default()) }
+}
+
+impl<K, V, S: SingleRangeStorage, H> RawHashMap<K, V, S, H> {
+    /// Creates a new instance from `storage`, using `hasher` to hash the keys.
+    pub fn with_hasher(storage: S, hasher: H) -> Self { Self { table: RawTable::new(storage), hasher, } }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the number of entries in `self`.
+    pub fn len(&self) -> usize { self.table.len() }
+
+    /// Clears `self`, destroying all entries.
+    pub fn clear(&mut self) { self.table.clear() }
+
+    /// Returns an iterator over the entries, in arbitrary order.
+    pub fn iter(&self) -> RawHashMapIter<'_, K, V> { RawHashMapIter(self.table.iter()) }
+}
+
+impl<K: Eq + Hash, V, S: SingleRangeStorage, H: BuildHasher> RawHashMap<K, V, S, H> {
+    /// Returns whether an entry for `key` is contained in `self`, or not.
+    pub fn contains_key<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> bool
+        where
+            K: Borrow<Q>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns a reference to the value associated to `key`, if any.
+    pub fn get<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<&V>
+        where
+            K: Borrow<Q>,
+    {
+        self.find(key).map(|index| &self.table.get(index).1)
+    }
+
+    /// Returns references to the key and value of the entry for `key`, if any.
+    pub fn get_key_value<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<(&K, &V)>
+        where
+            K: Borrow<Q>,
+    {
+        self.find(key).map(|index| {
+            let (key, value) = self.table.get(index);
+            (key, value)
+        })
+    }
+
+    /// Returns a mutable reference to the value associated to `key`, if any.
+    pub fn get_mut<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<&mut V>
+        where
+            K: Borrow<Q>,
+    {
+        let index = self.find(key)?;
+
+        Some(&mut self.table.get_mut(index).1)
+    }
+
+    /// Attempts to insert `value` for `key`, returning the previous value, if any.
+    ///
+    /// If an entry for `key` is already present, its value is replaced, and its key is left untouched.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        match self.entry(key) {
+            RawHashMapEntry::Occupied(mut entry) => Ok(Some(entry.insert(value))),
+            RawHashMapEntry::Vacant(entry) => entry.try_insert(value).map(|_| None),
+        }
+    }
+
+    /// Inserts `value` for `key`, returning the previous value, if any.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes the entry for `key`, returning its value, if any.
+    pub fn remove<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<V>
+        where
+            K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the entry for `key`, returning its key and value, if any.
+    pub fn remove_entry<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<(K, V)>
+        where
+            K: Borrow<Q>,
+    {
+        let index = self.find(key)?;
+
+        Some(self.remove_at(index))
+    }
+
+    /// Returns the entry for `key`, for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> RawHashMapEntry<'_, K, V, S, H> {
+        let hash = self.hasher.hash_one(&key);
+
+        match self.table.find(hash, |e| e.0 == key) {
+            Some(index) => RawHashMapEntry::Occupied(RawHashMapOccupiedEntry { map: self, index }),
+            None => RawHashMapEntry::Vacant(RawHashMapVacantEntry { map: self, hash, key }),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: SingleRangeStorage, H> Debug for RawHashMap<K, V, S, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S: Default + SingleRangeStorage, H: Default> Default for RawHashMap<K, V, S, H> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+/// Iterator over the entries of a RawHashMap.
+pub struct RawHashMapIter<'a, K, V>(RawTableIter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for RawHashMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(key, value)| (key, value)) }
+}
+
+/// An entry of a RawHashMap, see `RawHashMap::entry`.
+pub enum RawHashMapEntry<'a, K, V, S: SingleRangeStorage, H> {
+    /// An entry already present in the map.
+    Occupied(RawHashMapOccupiedEntry<'a, K, V, S, H>),
+    /// An entry absent from the map.
+    Vacant(RawHashMapVacantEntry<'a, K, V, S, H>),
+}
+
+impl<'a, K: Eq + Hash, V, S: SingleRangeStorage, H: BuildHasher> RawHashMapEntry<'a, K, V, S, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Attempts to insert `value` if the entry is vacant, and returns a mutable reference to the value of the entry.
+    pub fn or_try_insert(self, value: V) -> Result<&'a mut V, (K, V)> {
+        match self {
+            Self::Occupied(entry) => Ok(entry.into_mut()),
+            Self::Vacant(entry) => entry.try_insert(value),
+        }
+    }
+
+    /// Inserts `value` if the entry is vacant, and returns a mutable reference to the value of the entry.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn or_insert(self, value: V) -> &'a mut V {
+        self.or_try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+}
+
+/// An entry already present in a RawHashMap.
+pub struct RawHashMapOccupiedEntry<'a, K, V, S: SingleRangeStorage, H> {
+    map: &'a mut RawHashMap<K, V, S, H>,
+    index: usize,
+}
+
+impl<'a, K: Eq + Hash, V, S: SingleRangeStorage, H: BuildHasher> RawHashMapOccupiedEntry<'a, K, V, S, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K { &self.map.table.get(self.index).0 }
+
+    /// Returns a reference to the value of the entry.
+    pub fn get(&self) -> &V { &self.map.table.get(self.index).1 }
+
+    /// Returns a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> &mut V { &mut self.map.table.get_mut(self.index).1 }
+
+    /// Converts into a mutable reference to the value of the entry, bound to the map.
+    pub fn into_mut(self) -> &'a mut V { &mut self.map.table.get_mut(self.index).1 }
+
+    /// Replaces the value of the entry with `value`, returning the previous one.
+    pub fn insert(&mut self, value: V) -> V { mem::replace(self.get_mut(), value) }
+
+    /// Removes the entry from the map, returning its value.
+    pub fn remove(self) -> V { self.remove_entry().1 }
+
+    /// Removes the entry from the map, returning its key and value.
+    pub fn remove_entry(self) -> (K, V) { self.map.remove_at(self.index) }
+}
+
+/// An entry absent from a RawHashMap.
+pub struct RawHashMapVacantEntry<'a, K, V, S: SingleRangeStorage, H> {
+    map: &'a mut RawHashMap<K, V, S, H>,
+    hash: u64,
+    key: K,
+}
+
+impl<'a, K: Eq + Hash, V, S: SingleRangeStorage, H: BuildHasher> RawHashMapVacantEntry<'a, K, V, S, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K { &self.key }
+
+    /// Returns the key of the entry.
+    pub fn into_key(self) -> K { self.key }
+
+    /// Attempts to insert `value` for the key of the entry, and returns a mutable reference to it.
+    pub fn try_insert(self, value: V) -> Result<&'a mut V, (K, V)> {
+        let hasher = &self.map.hasher;
+        let index = self.map.table.try_insert(self.hash, (self.key, value), |e| hasher.hash_one(&e.0))?;
+
+        Ok(&mut self.map.table.get_mut(index).1)
+    }
+
+    /// Inserts `value` for the key of the entry, and returns a mutable reference to it.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<K: Eq + Hash, V, S: SingleRangeStorage, H: BuildHasher> RawHashMap<K, V, S, H> {
+    fn find<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<usize>
+        where
+            K: Borrow<Q>,
+    {
+        self.table.find(self.hasher.hash_one(key), |e| e.0.borrow() == key)
+    }
+
+    fn remove_at(&mut self, index: usize) -> (K, V) {
+        let hasher = &self.hasher;
+
+        self.table.remove(index, |e| hasher.hash_one(&e.0))
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Map = RawHashMap<u32, u32, SingleRange<u8, [u32; 3], 8>>;
+
+#[test]
+fn smoke_test() {
+    let mut map = Map::default();
+
+    assert_eq!(None, map.insert(1, 10));
+    assert_eq!(None, map.insert(2, 20));
+    assert_eq!(Some(10), map.insert(1, 11));
+
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&11), map.get(&1));
+    assert_eq!(None, map.get(&3));
+    assert!(map.contains_key(&2));
+
+    *map.get_mut(&2).unwrap() += 1;
+
+    assert_eq!(Some(21), map.remove(&2));
+    assert_eq!(None, map.remove(&2));
+    assert_eq!("{1: 11}", format!("{:?}", map));
+}
+
+#[test]
+fn entry() {
+    let mut map = Map::default();
+
+    for key in [1, 2, 1, 3, 1] {
+        *map.entry(key).or_insert(0) += 1;
+    }
+
+    assert_eq!(Some(&3), map.get(&1));
+    assert_eq!(Some(&1), map.get(&2));
+
+    match map.entry(2) {
+        RawHashMapEntry::Occupied(entry) => assert_eq!((2, 1), entry.remove_entry()),
+        RawHashMapEntry::Vacant(_) => unreachable!(),
+    }
+
+    match map.entry(4) {
+        RawHashMapEntry::Occupied(_) => unreachable!(),
+        RawHashMapEntry::Vacant(entry) => assert_eq!(4, entry.into_key()),
+    }
+
+    assert_eq!(2, map.len());
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut map = Map::default();
+
+    for i in 0..7 {
+        map.insert(i, i);
+    }
+
+    assert_eq!(Err((7, 7)), map.try_insert(7, 7));
+    assert_eq!(Ok(Some(6)), map.try_insert(6, 60));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn grow_remove() {
+    let allocator = SpyAllocator::default();
+
+    let mut map = RawHashMap::<String, u32, _>::new(SingleRange::new(allocator.clone()));
+
+    for i in 0..100 {
+        assert_eq!(None, map.insert(i.to_string(), i));
+    }
+
+    for i in (0..100).step_by(3) {
+        assert_eq!(Some((i.to_string(), i)), map.remove_entry(i.to_string().as_str()));
+    }
+
+    for i in 0..100 {
+        assert_eq!((i % 3 != 0).then_some(&i), map.get(i.to_string().as_str()), "{}", i);
+    }
+
+    assert_eq!(66, map.len());
+
+    drop(map);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut map = RawHashMap::<u8, u8, _>::new(SingleRange::new(NonAllocator));
+
+    assert_eq!(Err((1, 2)), map.try_insert(1, 2));
+}
+
+} // mod test_allocator
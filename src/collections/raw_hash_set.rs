@@ -0,0 +1,269 @@
+//! Proof-of-Concept implementation of a HashSet parameterized by a Storage.
+
+use core::{borrow::Borrow, fmt::{self, Debug}, hash::{BuildHasher, Hash}};
+
+use crate::{global, traits::SingleRangeStorage};
+
+use super::{DefaultHashBuilder, RawHashMap, RawHashMapEntry, RawHashMapIter};
+
+/// A PoC HashSet.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+///
+/// The set is a thin wrapper around a RawHashMap, whose values are `()`.
+pub struct RawHashSet<T, S: SingleRangeStorage = global::SingleRange, H = DefaultHashBuilder> {
+    map: RawHashMap<T, (), S, H>,
+}
+
+impl<T, S: SingleRangeStorage, H: Default> RawHashSet<T, S, H> {
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self { Self::with_hasher(storage, H::default()) }
+}
+
+impl<T, S: SingleRangeStorage, H> RawHashSet<T, S, H> {
+    /// Creates a new instance from `storage`, using `hasher` to hash the elements.
+    pub fn with_hasher(storage: S, hasher: H) -> Self { Self { map: RawHashMap::with_hasher(storage, hasher) } }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.map.len() }
+
+    /// Clears `self`, destroying all elements.
+    pub fn clear(&mut self) { self.map.clear() }
+
+    /// Returns an iterator over the elements, in arbitrary order.
+    pub fn iter(&self) -> RawHashSetIter<'_, T> { RawHashSetIter(self.map.iter()) }
+}
+
+impl<T: Eq + Hash, S: SingleRangeStorage, H: BuildHasher> RawHashSet<T, S, H> {
+    /// Returns whether `value` is contained in `self`, or not.
+    pub fn contains<Q: ?Sized + Eq + Hash>(&self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the element equal to `value`, if any.
+    pub fn get<Q: ?Sized + Eq + Hash>(&self, value: &Q) -> Option<&T>
+        where
+            T: Borrow<Q>,
+    {
+        self.map.get_key_value(value).map(|(value, _)| value)
+    }
+
+    /// Attempts to insert `value`, returning whether it was newly inserted.
+    ///
+    /// If an equal element is already present, it is left untouched.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, T> {
+        match self.map.entry(value) {
+            RawHashMapEntry::Occupied(_) => Ok(false),
+            RawHashMapEntry::Vacant(entry) => entry.try_insert(()).map(|_| true).map_err(|(value, _)| value),
+        }
+    }
+
+    /// Inserts `value`, returning whether it was newly inserted.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes the element equal to `value`, returning whether it was present.
+    pub fn remove<Q: ?Sized + Eq + Hash>(&mut self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Removes and returns the element equal to `value`, if any.
+    pub fn take<Q: ?Sized + Eq + Hash>(&mut self, value: &Q) -> Option<T>
+        where
+            T: Borrow<Q>,
+    {
+        self.map.remove_entry(value).map(|(value, _)| value)
+    }
+
+    /// Returns an iterator over the elements of either `self` or `other`, without duplicates.
+    pub fn union<'a, OS: SingleRangeStorage, OH>(&'a self, other: &'a RawHashSet<T, OS, OH>)
+        -> RawHashSetUnion<'a, T, S, H>
+    {
+        RawHashSetUnion { set: self, iter: self.iter(), other: other.iter() }
+    }
+
+    /// Returns an iterator over the elements of both `self` and `other`.
+    pub fn intersection<'a, OS: SingleRangeStorage, OH: BuildHasher>(&'a self, other: &'a RawHashSet<T, OS, OH>)
+        -> RawHashSetIntersection<'a, T, OS, OH>
+    {
+        RawHashSetIntersection { iter: self.iter(), other }
+    }
+}
+
+impl<T: Debug, S: SingleRangeStorage, H> Debug for RawHashSet<T, S, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: Default + SingleRangeStorage, H: Default> Default for RawHashSet<T, S, H> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+/// Iterator over the elements of a RawHashSet.
+pub struct RawHashSetIter<'a, T>(RawHashMapIter<'a, T, ()>);
+
+impl<'a, T> Iterator for RawHashSetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(value, _)| value) }
+}
+
+/// Iterator over the union of 2 RawHashSets.
+pub struct RawHashSetUnion<'a, T, S: SingleRangeStorage, H> {
+    set: &'a RawHashSet<T, S, H>,
+    iter: RawHashSetIter<'a, T>,
+    other: RawHashSetIter<'a, T>,
+}
+
+impl<'a, T: Eq + Hash, S: SingleRangeStorage, H: BuildHasher> Iterator for RawHashSetUnion<'a, T, S, H> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.iter.next() {
+            return Some(value);
+        }
+
+        let set = self.set;
+
+        self.other.by_ref().find(|value| !set.contains(*value))
+    }
+}
+
+/// Iterator over the intersection of 2 RawHashSets.
+pub struct RawHashSetIntersection<'a, T, S: SingleRangeStorage, H> {
+    iter: RawHashSetIter<'a, T>,
+    other: &'a RawHashSet<T, S, H>,
+}
+
+impl<'a, T: Eq + Hash, S: SingleRangeStorage, H: BuildHasher> Iterator for RawHashSetIntersection<'a, T, S, H> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+
+        self.iter.by_ref().find(|value| other.contains(*value))
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Set = RawHashSet<u32, SingleRange<u8, u64, 8>>;
+
+#[test]
+fn smoke_test() {
+    let mut set = Set::default();
+
+    assert!(set.insert(1));
+    assert!(set.insert(2));
+    assert!(!set.insert(1));
+
+    assert_eq!(2, set.len());
+    assert!(set.contains(&1));
+    assert!(!set.contains(&3));
+
+    assert!(set.remove(&1));
+    assert!(!set.remove(&1));
+    assert_eq!("{2}", format!("{:?}", set));
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut set = Set::default();
+
+    for i in 0..7 {
+        set.insert(i);
+    }
+
+    assert_eq!(Err(7), set.try_insert(7));
+    assert_eq!(Ok(false), set.try_insert(6));
+}
+
+#[test]
+fn union_intersection() {
+    let mut a = Set::default();
+    let mut b = Set::default();
+
+    for i in [1, 2, 3, 4] {
+        a.insert(i);
+    }
+
+    for i in [3, 4, 5] {
+        b.insert(i);
+    }
+
+    let mut union: Vec<_> = a.union(&b).copied().collect();
+    union.sort();
+
+    let mut intersection: Vec<_> = a.intersection(&b).copied().collect();
+    intersection.sort();
+
+    assert_eq!(vec![1, 2, 3, 4, 5], union);
+    assert_eq!(vec![3, 4], intersection);
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn grow_remove() {
+    let allocator = SpyAllocator::default();
+
+    let mut set = RawHashSet::<String, _>::new(SingleRange::new(allocator.clone()));
+
+    for i in 0..100 {
+        assert!(set.insert(i.to_string()));
+    }
+
+    for i in (0..100).step_by(3) {
+        assert_eq!(Some(i.to_string()), set.take(i.to_string().as_str()));
+    }
+
+    for i in 0..100 {
+        assert_eq!(i % 3 != 0, set.contains(i.to_string().as_str()), "{}", i);
+    }
+
+    assert_eq!(66, set.len());
+
+    drop(set);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut set = RawHashSet::<u8, _>::new(SingleRange::new(NonAllocator));
+
+    assert_eq!(Err(1), set.try_insert(1));
+}
+
+} // mod test_allocator
@@ -0,0 +1,264 @@
+//! Proof-of-Concept implementation of an overwriting ring buffer parameterized by a Storage.
+//!
+//! The buffer only ever keeps the last `capacity` values pushed, overwriting the oldest value once full, which makes it
+//! suitable for telemetry and logging on fixed memory.
+
+use core::{cmp, fmt::{self, Debug}, mem::MaybeUninit, ptr};
+
+use crate::{global, traits::{Capacity, SingleRangeStorage}};
+
+/// A PoC overwriting ring buffer.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawHistoryBuffer<T, S: SingleRangeStorage = global::SingleRange> {
+    //  Index of the oldest element.
+    head: usize,
+    len: usize,
+    capacity: usize,
+    data: S::Handle<T>,
+    storage: S,
+}
+
+impl<T, S: SingleRangeStorage> RawHistoryBuffer<T, S> {
+    /// Creates a new instance, keeping the last `capacity` values.
+    ///
+    /// Returns `storage` if it cannot accomodate `capacity` values.
+    pub fn new(mut storage: S, capacity: usize) -> Result<Self, S> {
+        let data = match S::Capacity::from_usize(capacity).map(|c| storage.allocate(c)) {
+            Some(Ok(data)) => data,
+            _ => return Err(storage),
+        };
+
+        Ok(Self { head: 0, len: 0, capacity, data, storage, })
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns whether `self` is full, or not, in which case the next push overwrites the oldest value.
+    pub fn is_full(&self) -> bool { self.len == self.capacity }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Returns the maximum number of elements in `self`.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Pushes `value` as the newest value, returning the oldest value if it was overwritten.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+
+        let full = self.is_full();
+        let index = self.physical(self.len);
+        let slot = self.slot_mut(index);
+
+        if !full {
+            slot.write(value);
+            self.len += 1;
+            return None;
+        }
+
+        //  Safety:
+        //  -   `self` is full, hence `slot` is the oldest element, and is initialized.
+        let oldest = unsafe { ptr::replace(slot.as_mut_ptr(), value) };
+
+        self.head = self.physical(1);
+
+        Some(oldest)
+    }
+
+    /// Pops the oldest value, if any.
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let index = self.head;
+
+        self.head = self.physical(1);
+        self.len -= 1;
+
+        //  Safety:
+        //  -   `index` is the oldest element, and is initialized.
+        Some(unsafe { ptr::read(self.slot_mut(index).as_ptr()) })
+    }
+
+    /// Returns a reference to the `index`-th oldest value, if any.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let (front, back) = self.as_slices();
+
+        if index < front.len() { front.get(index) } else { back.get(index - front.len()) }
+    }
+
+    /// Returns a reference to the oldest value, if any.
+    pub fn oldest(&self) -> Option<&T> { self.get(0) }
+
+    /// Returns a reference to the newest value, if any.
+    pub fn newest(&self) -> Option<&T> { self.len.checked_sub(1).and_then(|i| self.get(i)) }
+
+    /// Returns the values, from oldest to newest, as 2 slices.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let slice = self.raw_slice();
+
+        let front_len = cmp::min(self.len, self.capacity - self.head);
+
+        //  Safety:
+        //  -   Invariant: `self.head + front_len <= self.capacity`, and `self.len - front_len <= self.head`.
+        let (front, back) = unsafe {
+            (slice.get_unchecked(self.head..self.head + front_len), slice.get_unchecked(0..self.len - front_len))
+        };
+
+        //  Safety:
+        //  -   Invariant: the `self.len` elements starting at `self.head`, wrapping around, are initialized.
+        unsafe { (MaybeUninit::slice_assume_init_ref(front), MaybeUninit::slice_assume_init_ref(back)) }
+    }
+
+    /// Returns an iterator over the values, from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (front, back) = self.as_slices();
+
+        front.iter().chain(back.iter())
+    }
+
+    /// Clears `self`, destroying all elements.
+    pub fn clear(&mut self) {
+        while self.pop_oldest().is_some() {}
+    }
+}
+
+impl<T: Debug, S: SingleRangeStorage> Debug for RawHistoryBuffer<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: SingleRangeStorage> Drop for RawHistoryBuffer<T, S> {
+    fn drop(&mut self) {
+        self.clear();
+
+        //  Safety:
+        //  -   `self.data` is valid.
+        unsafe { self.storage.deallocate(self.data) };
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<T, S: SingleRangeStorage> RawHistoryBuffer<T, S> {
+    //  Returns the physical index of the `offset`-th element, starting from the oldest.
+    fn physical(&self, offset: usize) -> usize {
+        debug_assert!(offset <= self.capacity);
+
+        let index = self.head + offset;
+
+        if index >= self.capacity { index - self.capacity } else { index }
+    }
+
+    fn raw_slice(&self) -> &[MaybeUninit<T>] {
+        //  Safety:
+        //  -   `self.data` is valid and points to valid data.
+        let range = unsafe { self.storage.resolve(self.data) };
+
+        //  Safety:
+        //  -   `range` points to valid data.
+        //  -   The lifetime of the slice is actually that of `self.storage`.
+        unsafe { &*range.as_ptr() }
+    }
+
+    fn slot_mut(&mut self, index: usize) -> &mut MaybeUninit<T> {
+        debug_assert!(index < self.capacity);
+
+        //  Safety:
+        //  -   `self.data` is valid and points to valid data.
+        let range = unsafe { self.storage.resolve_mut(self.data) };
+
+        //  Safety:
+        //  -   `range` points to valid data, of at least `self.capacity` elements.
+        //  -   The lifetime of the slot is actually that of `self.storage`.
+        unsafe { (&mut *range.as_ptr()).get_unchecked_mut(index) }
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Buffer = RawHistoryBuffer<u8, SingleRange<u8, u8, 4>>;
+
+#[test]
+fn new_failure() {
+    Buffer::new(SingleRange::new(), 5).unwrap_err();
+}
+
+#[test]
+fn smoke_test() {
+    let mut buffer = Buffer::new(SingleRange::new(), 3).unwrap();
+
+    assert_eq!(None, buffer.push(1));
+    assert_eq!(None, buffer.push(2));
+    assert_eq!(None, buffer.push(3));
+
+    assert!(buffer.is_full());
+    assert_eq!(Some(1), buffer.push(4));
+    assert_eq!(Some(2), buffer.push(5));
+
+    assert_eq!(Some(&3), buffer.oldest());
+    assert_eq!(Some(&5), buffer.newest());
+    assert_eq!("[3, 4, 5]", format!("{:?}", buffer));
+    assert_eq!(vec![5, 4, 3], buffer.iter().rev().copied().collect::<Vec<_>>());
+
+    assert_eq!(Some(3), buffer.pop_oldest());
+    assert_eq!(None, buffer.push(6));
+    assert_eq!("[4, 5, 6]", format!("{:?}", buffer));
+}
+
+#[test]
+fn zero_capacity() {
+    let mut buffer = Buffer::new(SingleRange::new(), 0).unwrap();
+
+    assert_eq!(Some(1), buffer.push(1));
+    assert!(buffer.is_empty());
+    assert_eq!(None, buffer.newest());
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn new_failure() {
+    RawHistoryBuffer::<u8, _>::new(SingleRange::new(NonAllocator), 1).unwrap_err();
+}
+
+#[test]
+fn drop_overwritten() {
+    let allocator = SpyAllocator::default();
+
+    let mut buffer = RawHistoryBuffer::new(SingleRange::new(allocator.clone()), 4).unwrap();
+
+    for i in 0..10 {
+        buffer.push(i.to_string());
+    }
+
+    assert_eq!(vec!["6", "7", "8", "9"], buffer.iter().collect::<Vec<_>>());
+
+    drop(buffer);
+
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+}
+
+} // mod test_allocator
@@ -0,0 +1,226 @@
+//! Proof-of-Concept implementation of a string Interner parameterized by Storages.
+//!
+//! Each distinct string is stored once, as an unsized element of a MultiElementStorage, and is designated by a small
+//! copyable symbol. The symbols are kept in an index sorted by string, to look up a string in O(log N).
+
+use core::{cmp::Ordering, fmt::{self, Debug}, ptr};
+
+use crate::{error::StorageError, global, traits::{MultiElementStorage, SingleRangeStorage}};
+
+use super::RawVec;
+
+/// A PoC Interner.
+///
+/// Unless specified, the storages are the process-wide default storages, see `global`.
+pub struct RawInterner<M: MultiElementStorage = global::MultiElement, S: SingleRangeStorage = global::SingleRange> {
+    //  The strings, indexed by symbol.
+    strings: RawVec<M::Handle<str>, S>,
+    //  The symbols, sorted by string.
+    index: RawVec<u32, S>,
+    storage: M,
+}
+
+/// A symbol designating an interned string.
+///
+/// Symbols are only meaningful for the interner which created them.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RawInternerSymbol(u32);
+
+impl RawInternerSymbol {
+    /// Returns the index of the symbol, symbols being allocated sequentially from 0.
+    pub fn index(&self) -> usize { self.0 as usize }
+}
+
+impl<M: MultiElementStorage, S: SingleRangeStorage> RawInterner<M, S> {
+    /// Creates a new instance, with the strings stored in `storage`, and the symbols in `strings` and `index`.
+    pub fn new(storage: M, strings: S, index: S) -> Self {
+        Self { strings: RawVec::new(strings), index: RawVec::new(index), storage, }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.strings.is_empty() }
+
+    /// Returns the number of distinct strings in `self`.
+    pub fn len(&self) -> usize { self.strings.len() }
+
+    /// Returns the symbol of `string`, if interned.
+    pub fn get(&self, string: &str) -> Option<RawInternerSymbol> {
+        self.search(string).ok().map(|position| RawInternerSymbol(self.index[position]))
+    }
+
+    /// Returns the string designated by `symbol`, if any.
+    pub fn resolve(&self, symbol: RawInternerSymbol) -> Option<&str> {
+        self.strings.get(symbol.index()).map(|handle| self.resolve_handle(*handle))
+    }
+
+    /// Attempts to intern `string`, returning its symbol.
+    pub fn try_intern(&mut self, string: &str) -> Result<RawInternerSymbol, StorageError> {
+        let position = match self.search(string) {
+            Ok(position) => return Ok(RawInternerSymbol(self.index[position])),
+            Err(position) => position,
+        };
+
+        let symbol = self.strings.len();
+
+        if symbol >= u32::MAX as usize {
+            return Err(StorageError::capacity_overflow());
+        }
+
+        self.strings.try_grow_for_push()?;
+        self.index.try_grow_for_push()?;
+
+        let handle = self.storage.allocate::<str>(string.len())?;
+
+        //  Safety:
+        //  -   `handle` is valid, fresh off the press.
+        let pointer = unsafe { self.storage.resolve_mut(handle) };
+
+        //  Safety:
+        //  -   `pointer` points to a memory area suitable for `string.len()` bytes.
+        unsafe { ptr::copy_nonoverlapping(string.as_ptr(), pointer.as_ptr() as *mut u8, string.len()) };
+
+        //  Safety:
+        //  -   Both have room for one more element, as per `try_grow_for_push`.
+        unsafe {
+            self.strings.push_unchecked(handle);
+            self.index.push_unchecked(symbol as u32);
+        }
+        self.index[position..].rotate_right(1);
+
+        Ok(RawInternerSymbol(symbol as u32))
+    }
+
+    /// Interns `string`, returning its symbol.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn intern(&mut self, string: &str) -> RawInternerSymbol {
+        self.try_intern(string).expect("Sufficient capacity")
+    }
+
+    /// Returns an iterator over the interned strings, in the order of their symbols.
+    pub fn iter(&self) -> impl Iterator<Item = (RawInternerSymbol, &str)> {
+        self.strings.iter()
+            .enumerate()
+            .map(move |(index, handle)| (RawInternerSymbol(index as u32), self.resolve_handle(*handle)))
+    }
+}
+
+impl<M: MultiElementStorage, S: SingleRangeStorage> Debug for RawInterner<M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.iter().map(|(_, string)| string)).finish()
+    }
+}
+
+impl<M: Default + MultiElementStorage, S: Default + SingleRangeStorage> Default for RawInterner<M, S> {
+    fn default() -> Self { Self::new(M::default(), S::default(), S::default()) }
+}
+
+impl<M: MultiElementStorage, S: SingleRangeStorage> Drop for RawInterner<M, S> {
+    fn drop(&mut self) {
+        for handle in self.strings.iter() {
+            //  Safety:
+            //  -   `handle` is valid, and `str` needs no drop.
+            unsafe { self.storage.deallocate(*handle) };
+        }
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<M: MultiElementStorage, S: SingleRangeStorage> RawInterner<M, S> {
+    fn resolve_handle(&self, handle: M::Handle<str>) -> &str {
+        //  Safety:
+        //  -   `handle` is valid, as all handles of `self.strings` are.
+        //  -   The lifetime of the string is actually that of `self.storage`.
+        unsafe { &*self.storage.resolve(handle).as_ptr() }
+    }
+
+    //  Returns the position of `string` in the index, or the position at which it should be inserted.
+    fn search(&self, string: &str) -> Result<usize, usize> {
+        self.index.binary_search_by(|symbol| -> Ordering {
+            self.resolve_handle(self.strings[*symbol as usize]).cmp(string)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::{MultiElement, SingleRange};
+
+use super::*;
+
+type Interner = RawInterner<MultiElement<[u8; 8], 4>, SingleRange<u8, [usize; 3], 4>>;
+
+#[test]
+fn smoke_test() {
+    let mut interner = Interner::default();
+
+    let hello = interner.intern("hello");
+    let world = interner.intern("world");
+    let again = interner.intern("hello");
+
+    assert_eq!(hello, again);
+    assert_ne!(hello, world);
+    assert_eq!(2, interner.len());
+
+    assert_eq!(Some("hello"), interner.resolve(hello));
+    assert_eq!(Some("world"), interner.resolve(world));
+    assert_eq!(Some(world), interner.get("world"));
+    assert_eq!(None, interner.get("nope"));
+    assert_eq!("[\"hello\", \"world\"]", format!("{:?}", interner));
+}
+
+#[test]
+fn try_intern_failure() {
+    let mut interner = Interner::default();
+
+    interner.try_intern("too long a string").unwrap_err();
+
+    assert!(interner.is_empty());
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::{MultiElement, SingleRange};
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn lookup_sorted() {
+    let allocator = SpyAllocator::default();
+
+    let mut interner = RawInterner::new(
+        MultiElement::new(allocator.clone()),
+        SingleRange::new(allocator.clone()),
+        SingleRange::new(allocator.clone()),
+    );
+
+    let words = ["delta", "alpha", "charlie", "bravo", "echo", "alpha", "delta"];
+
+    let symbols: Vec<_> = words.iter().map(|word| interner.intern(word)).collect();
+
+    assert_eq!(5, interner.len());
+    assert_eq!(symbols[1], symbols[5]);
+    assert_eq!(symbols[0], symbols[6]);
+
+    for (word, symbol) in words.iter().zip(symbols.iter()) {
+        assert_eq!(Some(*symbol), interner.get(word));
+        assert_eq!(Some(*word), interner.resolve(*symbol));
+    }
+
+    drop(interner);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+} // mod test_allocator
@@ -0,0 +1,382 @@
+//! Proof-of-Concept implementation of an intrusive doubly-linked List parameterized by a Storage.
+//!
+//! The elements embed their own links, see `RawLink`, and the list merely records its ends: it owns neither the
+//! elements nor the storage, which are passed to each operation. An element may be linked into several lists at once,
+//! as long as each list uses a distinct `Tag`, and thus a distinct link.
+//!
+//! The links are handles, not pointers, hence the elements need not have stable addresses: any MultiElementStorage,
+//! inline ones included, can serve as the pool through which the lists are threaded.
+
+use core::{fmt::{self, Debug}, marker::PhantomData};
+
+use crate::{global, traits::MultiElementStorage};
+
+/// The link embedded in the elements of a RawIntrusiveList.
+pub struct RawLink<T, S: MultiElementStorage = global::MultiElement> {
+    prev: Option<S::Handle<T>>,
+    next: Option<S::Handle<T>>,
+}
+
+impl<T, S: MultiElementStorage> RawLink<T, S> {
+    /// Creates a new, unlinked, instance.
+    pub fn new() -> Self { Self { prev: None, next: None } }
+
+    /// Returns the handle of the previous element, if any.
+    pub fn prev(&self) -> Option<S::Handle<T>> { self.prev }
+
+    /// Returns the handle of the next element, if any.
+    pub fn next(&self) -> Option<S::Handle<T>> { self.next }
+}
+
+impl<T, S: MultiElementStorage> Debug for RawLink<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "RawLink{{ prev: {}, next: {} }}", self.prev.is_some(), self.next.is_some())
+    }
+}
+
+impl<T, S: MultiElementStorage> Default for RawLink<T, S> {
+    fn default() -> Self { Self::new() }
+}
+
+/// Implemented by the elements of a RawIntrusiveList, to access the link embedded for the lists of tag `Tag`.
+pub trait RawLinked<S: MultiElementStorage, Tag = ()>: Sized {
+    /// Returns the link.
+    fn link(&self) -> &RawLink<Self, S>;
+
+    /// Returns the link.
+    fn link_mut(&mut self) -> &mut RawLink<Self, S>;
+}
+
+/// A PoC intrusive LinkedList.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawIntrusiveList<T: RawLinked<S, Tag>, S: MultiElementStorage = global::MultiElement, Tag = ()> {
+    len: usize,
+    head: Option<S::Handle<T>>,
+    tail: Option<S::Handle<T>>,
+    _marker: PhantomData<fn(Tag)>,
+}
+
+impl<T: RawLinked<S, Tag>, S: MultiElementStorage, Tag> RawIntrusiveList<T, S, Tag> {
+    /// Creates a new, empty, instance.
+    pub fn new() -> Self { Self { len: 0, head: None, tail: None, _marker: PhantomData } }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Returns the handle of the front element, if any.
+    pub fn front(&self) -> Option<S::Handle<T>> { self.head }
+
+    /// Returns the handle of the back element, if any.
+    pub fn back(&self) -> Option<S::Handle<T>> { self.tail }
+
+    /// Links the element of `handle` at the front.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes `handle` is valid, and was issued by `storage`.
+    /// -   Assumes the element is not linked in any list of tag `Tag`.
+    /// -   Assumes all the elements of `self` were issued by `storage`.
+    pub unsafe fn push_front(&mut self, storage: &mut S, handle: S::Handle<T>) {
+        *link_mut(storage, handle) = RawLink { prev: None, next: self.head };
+
+        match self.head {
+            Some(head) => link_mut(storage, head).prev = Some(handle),
+            None => self.tail = Some(handle),
+        }
+
+        self.head = Some(handle);
+        self.len += 1;
+    }
+
+    /// Links the element of `handle` at the back.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes `handle` is valid, and was issued by `storage`.
+    /// -   Assumes the element is not linked in any list of tag `Tag`.
+    /// -   Assumes all the elements of `self` were issued by `storage`.
+    pub unsafe fn push_back(&mut self, storage: &mut S, handle: S::Handle<T>) {
+        *link_mut(storage, handle) = RawLink { prev: self.tail, next: None };
+
+        match self.tail {
+            Some(tail) => link_mut(storage, tail).next = Some(handle),
+            None => self.head = Some(handle),
+        }
+
+        self.tail = Some(handle);
+        self.len += 1;
+    }
+
+    /// Unlinks the front element, if any, and returns its handle.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes all the elements of `self` were issued by `storage`, and are still valid.
+    pub unsafe fn pop_front(&mut self, storage: &mut S) -> Option<S::Handle<T>> {
+        let head = self.head?;
+
+        self.unlink(storage, head);
+
+        Some(head)
+    }
+
+    /// Unlinks the back element, if any, and returns its handle.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes all the elements of `self` were issued by `storage`, and are still valid.
+    pub unsafe fn pop_back(&mut self, storage: &mut S) -> Option<S::Handle<T>> {
+        let tail = self.tail?;
+
+        self.unlink(storage, tail);
+
+        Some(tail)
+    }
+
+    /// Unlinks the element of `handle`, in O(1).
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes `handle` is linked in `self`.
+    /// -   Assumes all the elements of `self` were issued by `storage`, and are still valid.
+    pub unsafe fn unlink(&mut self, storage: &mut S, handle: S::Handle<T>) {
+        let RawLink { prev, next } = *link(storage, handle);
+
+        match prev {
+            Some(prev) => link_mut(storage, prev).next = next,
+            None => self.head = next,
+        }
+
+        match next {
+            Some(next) => link_mut(storage, next).prev = prev,
+            None => self.tail = prev,
+        }
+
+        *link_mut(storage, handle) = RawLink::new();
+        self.len -= 1;
+    }
+
+    /// Returns an iterator over the elements, from front to back.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes all the elements of `self` were issued by `storage`, and are still valid.
+    pub unsafe fn iter<'a>(&self, storage: &'a S) -> RawIntrusiveListIter<'a, T, S, Tag> {
+        RawIntrusiveListIter { next: self.head, storage, _marker: PhantomData }
+    }
+}
+
+impl<T: RawLinked<S, Tag>, S: MultiElementStorage, Tag> Debug for RawIntrusiveList<T, S, Tag> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "RawIntrusiveList{{ len: {} }}", self.len)
+    }
+}
+
+impl<T: RawLinked<S, Tag>, S: MultiElementStorage, Tag> Default for RawIntrusiveList<T, S, Tag> {
+    fn default() -> Self { Self::new() }
+}
+
+/// Iterator over the elements of a RawIntrusiveList.
+pub struct RawIntrusiveListIter<'a, T: RawLinked<S, Tag>, S: MultiElementStorage, Tag = ()> {
+    next: Option<S::Handle<T>>,
+    storage: &'a S,
+    _marker: PhantomData<fn(Tag)>,
+}
+
+impl<'a, T: RawLinked<S, Tag> + 'a, S: MultiElementStorage, Tag> Iterator for RawIntrusiveListIter<'a, T, S, Tag> {
+    type Item = (S::Handle<T>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let handle = self.next?;
+
+        //  Safety:
+        //  -   `handle` is valid, as per the pre-conditions of `RawIntrusiveList::iter`.
+        //  -   The lifetime of the element is actually that of `self.storage`.
+        let element: &'a T = unsafe { &*self.storage.resolve(handle).as_ptr() };
+
+        self.next = element.link().next;
+
+        Some((handle, element))
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<T, S: MultiElementStorage> Clone for RawLink<T, S> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T, S: MultiElementStorage> Copy for RawLink<T, S> {}
+
+//  #   Safety
+//
+//  -   Assumes `handle` is valid, and was issued by `storage`.
+unsafe fn link<T: RawLinked<S, Tag>, S: MultiElementStorage, Tag>(storage: &S, handle: S::Handle<T>)
+    -> &RawLink<T, S>
+    {
+    (*storage.resolve(handle).as_ptr()).link()
+}
+
+//  #   Safety
+//
+//  -   Assumes `handle` is valid, and was issued by `storage`.
+unsafe fn link_mut<T: RawLinked<S, Tag>, S: MultiElementStorage, Tag>(storage: &mut S, handle: S::Handle<T>)
+    -> &mut RawLink<T, S>
+    {
+    (*storage.resolve_mut(handle).as_ptr()).link_mut()
+}
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::MultiElement;
+use crate::traits::ElementStorage;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+type Storage = MultiElement<SpyAllocator>;
+
+struct Ready;
+struct Timer;
+
+#[derive(Debug)]
+struct Task {
+    id: u32,
+    ready: RawLink<Task, Storage>,
+    timer: RawLink<Task, Storage>,
+}
+
+impl Task {
+    fn new(id: u32) -> Self { Self { id, ready: RawLink::new(), timer: RawLink::new() } }
+}
+
+impl RawLinked<Storage, Ready> for Task {
+    fn link(&self) -> &RawLink<Self, Storage> { &self.ready }
+    fn link_mut(&mut self) -> &mut RawLink<Self, Storage> { &mut self.ready }
+}
+
+impl RawLinked<Storage, Timer> for Task {
+    fn link(&self) -> &RawLink<Self, Storage> { &self.timer }
+    fn link_mut(&mut self) -> &mut RawLink<Self, Storage> { &mut self.timer }
+}
+
+fn ids<Tag>(list: &RawIntrusiveList<Task, Storage, Tag>, storage: &Storage) -> Vec<u32>
+    where
+        Task: RawLinked<Storage, Tag>,
+{
+    unsafe { list.iter(storage).map(|(_, task)| task.id).collect() }
+}
+
+#[test]
+fn push_pop() {
+    let mut storage = Storage::new(SpyAllocator::default());
+    let mut list = RawIntrusiveList::<Task, Storage, Ready>::new();
+
+    let handles: Vec<_> = (0..4).map(|i| storage.create(Task::new(i)).unwrap()).collect();
+
+    unsafe {
+        list.push_back(&mut storage, handles[1]);
+        list.push_back(&mut storage, handles[2]);
+        list.push_front(&mut storage, handles[0]);
+        list.push_back(&mut storage, handles[3]);
+    }
+
+    assert_eq!(4, list.len());
+    assert_eq!(vec![0, 1, 2, 3], ids(&list, &storage));
+
+    unsafe {
+        assert_eq!(Some(0), list.pop_front(&mut storage).map(|h| storage.resolve(h).as_ref().id));
+        assert_eq!(Some(3), list.pop_back(&mut storage).map(|h| storage.resolve(h).as_ref().id));
+    }
+
+    assert_eq!(vec![1, 2], ids(&list, &storage));
+
+    for handle in handles {
+        unsafe { storage.destroy(handle) };
+    }
+}
+
+#[test]
+fn multiple_lists() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = Storage::new(allocator.clone());
+    let mut ready = RawIntrusiveList::<Task, Storage, Ready>::new();
+    let mut timer = RawIntrusiveList::<Task, Storage, Timer>::new();
+
+    let handles: Vec<_> = (0..4).map(|i| storage.create(Task::new(i)).unwrap()).collect();
+
+    unsafe {
+        for handle in &handles {
+            ready.push_back(&mut storage, *handle);
+            timer.push_front(&mut storage, *handle);
+        }
+
+        ready.unlink(&mut storage, handles[2]);
+        timer.unlink(&mut storage, handles[1]);
+    }
+
+    assert_eq!(vec![0, 1, 3], ids(&ready, &storage));
+    assert_eq!(vec![3, 2, 0], ids(&timer, &storage));
+
+    for handle in handles {
+        unsafe { storage.destroy(handle) };
+    }
+
+    assert_eq!(4, allocator.deallocated());
+}
+
+} // mod test_allocator
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::MultiElement;
+use crate::traits::ElementStorage;
+
+use super::*;
+
+type Storage = MultiElement<[usize; 4], 4>;
+
+#[derive(Debug)]
+struct Node {
+    value: u8,
+    link: RawLink<Node, Storage>,
+}
+
+impl RawLinked<Storage> for Node {
+    fn link(&self) -> &RawLink<Self, Storage> { &self.link }
+    fn link_mut(&mut self) -> &mut RawLink<Self, Storage> { &mut self.link }
+}
+
+#[test]
+fn pooled() {
+    let mut storage = Storage::new();
+    let mut list = RawIntrusiveList::<Node, Storage>::new();
+
+    let handles: Vec<_> = (0..3).map(|value| storage.create(Node { value, link: RawLink::new() }).unwrap()).collect();
+
+    unsafe {
+        for handle in &handles {
+            list.push_front(&mut storage, *handle);
+        }
+
+        list.unlink(&mut storage, handles[1]);
+
+        let values: Vec<_> = list.iter(&storage).map(|(_, node)| node.value).collect();
+        assert_eq!(vec![2, 0], values);
+    }
+
+    for handle in handles {
+        unsafe { storage.destroy(handle) };
+    }
+}
+
+} // mod test_inline
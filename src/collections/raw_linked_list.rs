@@ -1,19 +1,35 @@
 //! Proof-of-Concept implementation of a LinkedList parameterized by a Storage.
 
-use core::{fmt::{self, Debug}, marker::PhantomData, mem::MaybeUninit, ptr::{self, Pointee}};
+use core::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::{self, Pointee},
+};
 
-use crate::traits::MultiElementStorage;
+use crate::{global, traits::MultiElementStorage};
 
 /// A PoC LinkedList.
-pub struct RawLinkedList<T: Pointee, S: MultiElementStorage> {
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawLinkedList<T: Pointee, S: MultiElementStorage = global::MultiElement> {
     next: Option<S::Handle<RawLinkedListNode<T, S>>>,
+    len: usize,
     storage: S,
     _marker: PhantomData<T>,
 }
 
 impl<T: Pointee, S: MultiElementStorage> RawLinkedList<T, S> {
     /// Creates a new instance from `storage`.
-    pub fn new(storage: S) -> Self { Self { next: None, storage, _marker: PhantomData } }
+    pub fn new(storage: S) -> Self { Self { next: None, len: 0, storage, _marker: PhantomData } }
+
+    /// Returns whether the list is empty, or not.
+    pub fn is_empty(&self) -> bool { self.next.is_none() }
+
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize { self.len }
 
     /// Clears all the elements from the list, leading to an empty list.
     pub fn clear(&mut self) {
@@ -45,6 +61,7 @@ impl<T: Pointee, S: MultiElementStorage> RawLinkedList<T, S> {
         let handle = self.storage.create(node).map_err(|node| node.element)?;
 
         self.next = Some(handle);
+        self.len += 1;
 
         Ok(())
     }
@@ -59,9 +76,28 @@ impl<T: Pointee, S: MultiElementStorage> RawLinkedList<T, S> {
             self.storage.deallocate(handle);
 
             self.next = node.next;
+            self.len -= 1;
+
             node.element
         })
     }
+
+    /// Returns an iterator over the elements of the list, front to back.
+    pub fn iter(&self) -> RawLinkedListIter<'_, T, S> {
+        RawLinkedListIter { storage: &self.storage, next: self.next, _marker: PhantomData }
+    }
+
+    /// Retains only the elements for which `fun` returns `true`, in a single pass.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut fun: F) { self.extract_if(|e| !fun(e)).for_each(drop) }
+
+    /// Returns an iterator removing the elements for which `fun` returns `true`, lazily, front to back.
+    ///
+    /// The elements not yet visited when the iterator is dropped are retained.
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, fun: F) -> RawLinkedListExtractIf<'_, T, S, F> {
+        let current = self.next;
+
+        RawLinkedListExtractIf { list: self, previous: None, current, fun }
+    }
 }
 
 impl<T: Debug + Pointee, S: MultiElementStorage> Debug for RawLinkedList<T, S> {
@@ -93,6 +129,38 @@ impl<T: Debug + Pointee, S: MultiElementStorage> Debug for RawLinkedList<T, S> {
     }
 }
 
+impl<T, U, S, OS> PartialEq<RawLinkedList<U, OS>> for RawLinkedList<T, S>
+    where
+        T: Pointee + PartialEq<U>,
+        U: Pointee,
+        S: MultiElementStorage,
+        OS: MultiElementStorage,
+{
+    fn eq(&self, other: &RawLinkedList<U, OS>) -> bool { self.len() == other.len() && self.iter().eq(other.iter()) }
+}
+
+impl<T: Pointee + Eq, S: MultiElementStorage> Eq for RawLinkedList<T, S> {}
+
+impl<T: Pointee + PartialOrd, S: MultiElementStorage, OS: MultiElementStorage> PartialOrd<RawLinkedList<T, OS>>
+    for RawLinkedList<T, S>
+{
+    fn partial_cmp(&self, other: &RawLinkedList<T, OS>) -> Option<Ordering> { self.iter().partial_cmp(other.iter()) }
+}
+
+impl<T: Pointee + Ord, S: MultiElementStorage> Ord for RawLinkedList<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering { self.iter().cmp(other.iter()) }
+}
+
+impl<T: Pointee + Hash, S: MultiElementStorage> Hash for RawLinkedList<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+
+        for e in self.iter() {
+            e.hash(state);
+        }
+    }
+}
+
 impl<T: Pointee, S: Default + MultiElementStorage> Default for RawLinkedList<T, S> {
     fn default() -> Self { Self::new(S::default()) }
 }
@@ -101,6 +169,83 @@ impl<T: Pointee, S: MultiElementStorage> Drop for RawLinkedList<T, S> {
     fn drop(&mut self) { self.clear(); }
 }
 
+/// An iterator over the elements of a RawLinkedList, front to back.
+pub struct RawLinkedListIter<'a, T: Pointee, S: MultiElementStorage> {
+    storage: &'a S,
+    next: Option<S::Handle<RawLinkedListNode<T, S>>>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: Pointee, S: MultiElementStorage> Iterator for RawLinkedListIter<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|handle| {
+            //  Safety:
+            //  -   `handle` is valid, as it is linked in the list, which is borrowed for `'a`.
+            let node = unsafe { &*self.storage.resolve(handle).as_ptr() };
+
+            self.next = node.next;
+
+            &node.element
+        })
+    }
+}
+
+/// An iterator removing the elements of a RawLinkedList matching a predicate, see `RawLinkedList::extract_if`.
+pub struct RawLinkedListExtractIf<'a, T: Pointee, S: MultiElementStorage, F> {
+    list: &'a mut RawLinkedList<T, S>,
+    previous: Option<S::Handle<RawLinkedListNode<T, S>>>,
+    current: Option<S::Handle<RawLinkedListNode<T, S>>>,
+    fun: F,
+}
+
+impl<'a, T, S, F> Iterator for RawLinkedListExtractIf<'a, T, S, F>
+    where
+        T: Pointee,
+        S: MultiElementStorage,
+        F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(handle) = self.current {
+            //  Safety:
+            //  -   `handle` is valid, as it is linked in the list.
+            let node = unsafe { self.list.storage.resolve_mut(handle).as_ptr() };
+
+            //  Safety:
+            //  -   `node` points to a valid node, which is not otherwise borrowed.
+            let (next, extracted) = unsafe { ((*node).next, (self.fun)(&mut (*node).element)) };
+
+            self.current = next;
+
+            if !extracted {
+                self.previous = Some(handle);
+                continue;
+            }
+
+            match self.previous {
+                None => self.list.next = next,
+                //  Safety:
+                //  -   `previous` is valid, as it is linked in the list.
+                Some(previous) => unsafe { (*self.list.storage.resolve_mut(previous).as_ptr()).next = next },
+            }
+
+            self.list.len -= 1;
+
+            //  Safety:
+            //  -   The node is unlinked, hence its element is moved out exactly once, and its block released.
+            let element = unsafe { ptr::read(&(*node).element) };
+            unsafe { self.list.storage.deallocate(handle) };
+
+            return Some(element);
+        }
+
+        None
+    }
+}
+
 /// A PoC LinkedList storage helper.
 ///
 /// Reserves enough space for storing a list node containing `T`, for a handle of size similar to `H`.
@@ -130,15 +275,73 @@ fn smoke_test() {
 
     let mut list = List::default();
 
+    assert!(list.is_empty());
+
     list.push(1).unwrap();
     list.push(2).unwrap();
 
+    assert_eq!(2, list.len());
     assert_eq!(Some(&2), list.front());
 
     *list.front_mut().unwrap() = 3;
 
     assert_eq!(Some(3), list.pop());
     assert_eq!(Some(&1), list.front());
+    assert_eq!(1, list.len());
+}
+
+#[test]
+fn compare() {
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    type NodeStorage = RawLinkedListNodeStorage<u8, usize>;
+
+    let mut small = RawLinkedList::<u8, MultiElement<NodeStorage, 4>>::default();
+    let mut large = RawLinkedList::<u8, MultiElement<NodeStorage, 8>>::default();
+
+    for i in 0..3 {
+        small.push(i).unwrap();
+        large.push(i).unwrap();
+    }
+
+    assert_eq!(small, large);
+    assert_eq!(Some(Ordering::Equal), small.partial_cmp(&large));
+    assert_eq!(hash_of(&small), hash_of(&large));
+
+    large.pop();
+
+    assert_ne!(small, large);
+    assert!(large < small);
+    assert_eq!(vec![&2, &1, &0], small.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn retain() {
+    type NodeStorage = RawLinkedListNodeStorage<u8, usize>;
+    type List = RawLinkedList<u8, MultiElement<NodeStorage, 6>>;
+
+    let mut list = List::default();
+
+    for i in 0..6 {
+        list.push(i).unwrap();
+    }
+
+    list.retain(|e| e % 2 == 0);
+
+    assert_eq!(3, list.len());
+    assert_eq!("[4, 2, 0]", format!("{:?}", list));
+
+    list.push(6).unwrap();
+    list.push(7).unwrap();
+    list.push(8).unwrap();
+
+    assert_eq!(Err(9), list.push(9));
 }
 
 } // mod test_inline
@@ -174,6 +377,34 @@ fn smoke_test() {
     assert_eq!(1, allocator.deallocated());
 }
 
+#[test]
+fn extract_if() {
+    type List = RawLinkedList<String, MultiElement<SpyAllocator>>;
+
+    let allocator = SpyAllocator::default();
+    let mut list = List::new(MultiElement::new(allocator.clone()));
+
+    for i in 0..5 {
+        list.push(i.to_string()).unwrap();
+    }
+
+    {
+        let mut extracted = list.extract_if(|e| e != "2");
+
+        assert_eq!(Some("4".to_string()), extracted.next());
+        assert_eq!(Some("3".to_string()), extracted.next());
+        assert_eq!(Some("1".to_string()), extracted.next());
+    }
+
+    assert_eq!(2, list.len());
+    assert_eq!(r#"["2", "0"]"#, format!("{:?}", list));
+    assert_eq!(3, allocator.deallocated());
+
+    drop(list);
+
+    assert_eq!(5, allocator.deallocated());
+}
+
 #[test]
 fn allocation_failure() {
     type List = RawLinkedList<&'static str, MultiElement<NonAllocator>>;
@@ -181,6 +412,8 @@ fn allocation_failure() {
     let mut list = List::default();
 
     list.push("Caramba").unwrap_err();
+
+    assert_eq!(0, list.len());
 }
 
 } // mod test_allocator
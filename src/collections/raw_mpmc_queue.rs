@@ -0,0 +1,405 @@
+//! Proof-of-Concept implementation of a bounded lock-free multi-producer multi-consumer queue parameterized by a
+//! Storage.
+//!
+//! The queue follows the design of crossbeam's `ArrayQueue`: each slot carries a sequence number, indicating whether it
+//! is ready to be written to, or read from, for a given lap of the ring. The head and tail pack a lap and an index
+//! within the ring, a lap being the smallest power of 2 greater than the capacity, so that the index of a slot is
+//! stable as the laps wrap around `usize::MAX`.
+//!
+//! The queue is shared between threads by reference, hence the storage is required to be `Sync`.
+
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug},
+    mem::MaybeUninit,
+    hint,
+    sync::atomic::{self, AtomicUsize, Ordering},
+};
+
+use crate::{global, traits::{Capacity, SingleRangeStorage}};
+
+/// A PoC bounded MPMC queue.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawMpmcQueue<T, S: SingleRangeStorage = global::SingleRange> {
+    //  Lap and index of the next value to pop.
+    head: AtomicUsize,
+    //  Lap and index of the next value to push.
+    tail: AtomicUsize,
+    capacity: usize,
+    //  Smallest power of 2 greater than `capacity`, the indices being the lower bits of the head and tail.
+    one_lap: usize,
+    slots: S::Handle<RawMpmcSlot<T>>,
+    storage: S,
+}
+
+impl<T, S: SingleRangeStorage> RawMpmcQueue<T, S> {
+    /// Creates a new instance, holding up to `capacity` values.
+    ///
+    /// Returns `storage` if it cannot accomodate `capacity` slots, or if `capacity` is 0 or exceeds `usize::MAX / 2`.
+    pub fn new(mut storage: S, capacity: usize) -> Result<Self, S> {
+        if capacity == 0 || capacity > usize::MAX / 2 {
+            return Err(storage);
+        }
+
+        let one_lap = (capacity + 1).next_power_of_two();
+
+        let slots = match S::Capacity::from_usize(capacity).map(|c| storage.allocate::<RawMpmcSlot<T>>(c)) {
+            Some(Ok(slots)) => slots,
+            _ => return Err(storage),
+        };
+
+        //  Safety:
+        //  -   `slots` is valid, as it was just allocated, and `storage` is borrowed mutably.
+        let range = unsafe { &mut *storage.resolve_mut(slots).as_ptr() };
+
+        for (index, slot) in range.iter_mut().enumerate() {
+            let value = UnsafeCell::new(MaybeUninit::uninit());
+
+            slot.write(RawMpmcSlot { sequence: AtomicUsize::new(index), value });
+        }
+
+        Ok(Self { head: AtomicUsize::new(0), tail: AtomicUsize::new(0), capacity, one_lap, slots, storage, })
+    }
+
+    /// Returns whether `self` is empty, or not.
+    ///
+    /// The queue may no longer be empty by the time this returns, should a producer push concurrently.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns whether `self` is full, or not.
+    ///
+    /// The queue may no longer be full by the time this returns, should a consumer pop concurrently.
+    pub fn is_full(&self) -> bool { self.len() == self.capacity }
+
+    /// Returns the number of elements in `self`.
+    ///
+    /// The result is only a snapshot, should producers or consumers operate concurrently.
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+
+            //  Only a consistent snapshot of both indices is meaningful.
+            if self.tail.load(Ordering::SeqCst) != tail {
+                continue;
+            }
+
+            let (head_index, tail_index) = (self.index_of(head), self.index_of(tail));
+
+            return if head_index < tail_index {
+                tail_index - head_index
+            } else if head_index > tail_index {
+                self.capacity - head_index + tail_index
+            } else if head == tail {
+                0
+            } else {
+                self.capacity
+            };
+        }
+    }
+
+    /// Returns the maximum number of elements in `self`.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Attempts to push `value` at the back of the queue, returning it if the queue is full.
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            let slot = self.slot(self.index_of(tail));
+            let sequence = slot.sequence.load(Ordering::Acquire);
+
+            //  The slot is ready to be written to, for this lap.
+            if sequence == tail {
+                let next = self.next(tail);
+
+                if let Err(current) = self.tail.compare_exchange_weak(tail, next, Ordering::SeqCst, Ordering::Relaxed) {
+                    tail = current;
+                    continue;
+                }
+
+                //  Safety:
+                //  -   The slot was claimed by this thread, no other thread accesses it until its sequence number is
+                //      published.
+                unsafe { (*slot.value.get()).write(value) };
+
+                slot.sequence.store(tail + 1, Ordering::Release);
+
+                return Ok(());
+            }
+
+            //  The slot still holds the value of the previous lap: the queue may be full.
+            if sequence.wrapping_add(self.one_lap) == tail + 1 {
+                atomic::fence(Ordering::SeqCst);
+
+                if self.head.load(Ordering::Relaxed).wrapping_add(self.one_lap) == tail {
+                    return Err(value);
+                }
+            } else {
+                //  Another producer claimed the slot already.
+                hint::spin_loop();
+            }
+
+            tail = self.tail.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Pops the front of the queue, if any.
+    pub fn pop(&self) -> Option<T> {
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            let slot = self.slot(self.index_of(head));
+            let sequence = slot.sequence.load(Ordering::Acquire);
+
+            //  The slot holds the value of this lap.
+            if sequence == head + 1 {
+                let next = self.next(head);
+
+                if let Err(current) = self.head.compare_exchange_weak(head, next, Ordering::SeqCst, Ordering::Relaxed) {
+                    head = current;
+                    continue;
+                }
+
+                //  Safety:
+                //  -   The slot was claimed by this thread, and its value was published by its producer.
+                let value = unsafe { (*slot.value.get()).assume_init_read() };
+
+                slot.sequence.store(head.wrapping_add(self.one_lap), Ordering::Release);
+
+                return Some(value);
+            }
+
+            //  The slot has not been written to, for this lap: the queue may be empty.
+            if sequence == head {
+                atomic::fence(Ordering::SeqCst);
+
+                if self.tail.load(Ordering::Relaxed) == head {
+                    return None;
+                }
+            } else {
+                //  Another consumer claimed the slot already.
+                hint::spin_loop();
+            }
+
+            head = self.head.load(Ordering::Relaxed);
+        }
+    }
+}
+
+//  Safety:
+//  -   The values may be dropped by another thread, if the queue is sent.
+unsafe impl<T: Send, S: SingleRangeStorage + Send> Send for RawMpmcQueue<T, S> {}
+
+//  Safety:
+//  -   The values are moved in and out of the queue by any thread, but never shared.
+//  -   The storage is only ever accessed immutably, to resolve the slots.
+unsafe impl<T: Send, S: SingleRangeStorage + Sync> Sync for RawMpmcQueue<T, S> {}
+
+impl<T, S: SingleRangeStorage> Debug for RawMpmcQueue<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "RawMpmcQueue{{ len: {}, capacity: {} }}", self.len(), self.capacity)
+    }
+}
+
+impl<T, S: SingleRangeStorage> Drop for RawMpmcQueue<T, S> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+
+        //  Safety:
+        //  -   `self.slots` is valid, and all its values were dropped.
+        unsafe { self.storage.deallocate(self.slots) };
+    }
+}
+
+/// A slot of a RawMpmcQueue.
+///
+/// This type is only public so that the storage of a queue may be sized appropriately.
+pub struct RawMpmcSlot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+
+//
+//  Implementation
+//
+
+impl<T, S: SingleRangeStorage> RawMpmcQueue<T, S> {
+    //  Returns the index within the ring of `stamp`, a head or tail.
+    fn index_of(&self, stamp: usize) -> usize { stamp & (self.one_lap - 1) }
+
+    //  Returns the stamp following `stamp`, moving to the start of the next lap past the last slot.
+    fn next(&self, stamp: usize) -> usize {
+        if self.index_of(stamp) + 1 < self.capacity {
+            stamp + 1
+        } else {
+            (stamp & !(self.one_lap - 1)).wrapping_add(self.one_lap)
+        }
+    }
+
+    //  Returns the slot at `index`.
+    fn slot(&self, index: usize) -> &RawMpmcSlot<T> {
+        debug_assert!(index < self.capacity);
+
+        //  Safety:
+        //  -   `self.slots` is valid, as it is owned by `self`.
+        let range = unsafe { self.storage.resolve(self.slots) };
+
+        //  Safety:
+        //  -   `index` is within the range, and all slots were initialized on construction.
+        unsafe { (*range.as_mut_ptr().add(index)).assume_init_ref() }
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Queue = RawMpmcQueue<u8, SingleRange<u8, [usize; 2], 4>>;
+
+#[test]
+fn new_failure() {
+    Queue::new(SingleRange::new(), 5).unwrap_err();
+    Queue::new(SingleRange::new(), 0).unwrap_err();
+}
+
+#[test]
+fn smoke_test() {
+    let queue = Queue::new(SingleRange::new(), 3).unwrap();
+
+    assert_eq!(None, queue.pop());
+
+    for i in 0..3 {
+        queue.try_push(i).unwrap();
+    }
+
+    assert!(queue.is_full());
+    assert_eq!(Err(3), queue.try_push(3));
+
+    assert_eq!(Some(0), queue.pop());
+
+    queue.try_push(3).unwrap();
+
+    assert_eq!(Some(1), queue.pop());
+    assert_eq!(2, queue.len());
+    assert_eq!("RawMpmcQueue{ len: 2, capacity: 3 }", format!("{:?}", queue));
+}
+
+#[test]
+fn wrap_around() {
+    let queue = Queue::new(SingleRange::new(), 4).unwrap();
+
+    for i in 0..20 {
+        queue.try_push(i).unwrap();
+        queue.try_push(i + 1).unwrap();
+
+        assert_eq!(Some(i), queue.pop());
+        assert_eq!(Some(i + 1), queue.pop());
+        assert!(queue.is_empty());
+    }
+}
+
+#[test]
+fn wrap_around_usize() {
+    let mut queue = Queue::new(SingleRange::new(), 3).unwrap();
+
+    //  Start on the last lap before the stamps wrap around `usize::MAX`.
+    let lap = usize::MAX & !(queue.one_lap - 1);
+
+    *queue.head.get_mut() = lap;
+    *queue.tail.get_mut() = lap;
+
+    for index in 0..3 {
+        queue.slot(index).sequence.store(lap + index, Ordering::Relaxed);
+    }
+
+    for i in 0..20 {
+        queue.try_push(i).unwrap();
+        queue.try_push(i + 1).unwrap();
+        queue.try_push(i + 2).unwrap();
+
+        assert!(queue.is_full());
+        assert_eq!(Err(3), queue.try_push(3));
+
+        assert_eq!(Some(i), queue.pop());
+        assert_eq!(Some(i + 1), queue.pop());
+        assert_eq!(Some(i + 2), queue.pop());
+
+        assert!(queue.is_empty());
+        assert_eq!(None, queue.pop());
+    }
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use std::{alloc::Global, sync::atomic::AtomicU64, thread};
+
+use crate::allocator::SingleRange;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn drop_remaining() {
+    let allocator = SpyAllocator::default();
+
+    let queue = RawMpmcQueue::new(SingleRange::new(allocator.clone()), 4).unwrap();
+
+    queue.try_push("Hello".to_string()).unwrap();
+    queue.try_push("World".to_string()).unwrap();
+
+    drop(queue);
+
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn threads() {
+    const PRODUCERS: u64 = 4;
+    const COUNT: u64 = 5_000;
+
+    let queue = RawMpmcQueue::new(SingleRange::new(Global), 16).unwrap();
+    let sum = AtomicU64::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..PRODUCERS {
+            scope.spawn(|| {
+                for i in 1..=COUNT {
+                    let mut value = i;
+
+                    while let Err(v) = queue.try_push(value) {
+                        value = v;
+                        thread::yield_now();
+                    }
+                }
+            });
+
+            scope.spawn(|| {
+                for _ in 0..COUNT {
+                    loop {
+                        if let Some(value) = queue.pop() {
+                            sum.fetch_add(value, Ordering::Relaxed);
+                            break;
+                        }
+
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+    });
+
+    assert!(queue.is_empty());
+    assert_eq!(PRODUCERS * COUNT * (COUNT + 1) / 2, sum.load(Ordering::Relaxed));
+}
+
+} // mod test_allocator
@@ -0,0 +1,345 @@
+//! Proof-of-Concept implementation of a OnceCell, and a Lazy, parameterized by a Storage.
+//!
+//! The value is constructed directly within the storage, on first access. The state of the cell is atomic, so that a
+//! cell may be shared between threads, and placed in a `static`.
+
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug},
+    hint,
+    mem::{self, MaybeUninit},
+    ptr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use crate::{global, traits::SingleElementStorage};
+
+/// A PoC OnceCell.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawOnceCell<T, S: SingleElementStorage = global::SingleElement> {
+    state: AtomicU8,
+    handle: UnsafeCell<MaybeUninit<S::Handle<T>>>,
+    storage: UnsafeCell<S>,
+}
+
+impl<T, S: SingleElementStorage> RawOnceCell<T, S> {
+    /// Creates a new, uninitialized, instance from `storage`.
+    pub const fn new(storage: S) -> Self {
+        Self {
+            state: AtomicU8::new(UNINITIALIZED),
+            handle: UnsafeCell::new(MaybeUninit::uninit()),
+            storage: UnsafeCell::new(storage),
+        }
+    }
+
+    /// Returns a reference to the value, if initialized.
+    pub fn get(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) != INITIALIZED {
+            return None;
+        }
+
+        //  Safety:
+        //  -   The cell is initialized, hence `handle` is valid, and neither it nor the storage is ever mutated again
+        //      until `self` is borrowed mutably.
+        Some(unsafe { &*(*self.storage.get()).resolve((*self.handle.get()).assume_init()).as_ptr() })
+    }
+
+    /// Returns a mutable reference to the value, if initialized.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if *self.state.get_mut() != INITIALIZED {
+            return None;
+        }
+
+        //  Safety:
+        //  -   The cell is initialized, hence `handle` is valid.
+        Some(unsafe { &mut *self.storage.get_mut().resolve_mut(self.handle.get_mut().assume_init()).as_ptr() })
+    }
+
+    /// Attempts to initialize the cell with `value`, returning it if the cell is already initialized, or if the
+    /// storage cannot accomodate it.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+
+        //  The value is left untouched if the cell is already initialized, or cannot be initialized.
+        let _ = self.try_get_or_init(|| value.take().expect("Not initialized"));
+
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Returns a reference to the value, initializing it with `fun` if not already initialized.
+    ///
+    /// If the cell is concurrently initialized, waits for the initialization to complete. Initializing the cell from
+    /// within `fun` deadlocks.
+    ///
+    /// Fails if the storage cannot accomodate the value, in which case `fun` is returned, uninvoked, and the cell
+    /// remains uninitialized.
+    pub fn try_get_or_init<F: FnOnce() -> T>(&self, fun: F) -> Result<&T, F> {
+        loop {
+            match self.state.compare_exchange_weak(UNINITIALIZED, INITIALIZING, Ordering::Acquire, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(INITIALIZED) => return Ok(self.get().expect("Initialized")),
+                Err(_) => hint::spin_loop(),
+            }
+        }
+
+        //  Should `fun` panic, the cell is reverted to uninitialized.
+        let guard = StateGuard(&self.state);
+
+        //  Safety:
+        //  -   The cell is INITIALIZING, hence this thread has exclusive access to the storage and handle.
+        let handle = unsafe { (*self.storage.get()).create_with(fun) }?;
+
+        //  Safety:
+        //  -   The cell is INITIALIZING, hence this thread has exclusive access to the handle.
+        unsafe { (*self.handle.get()).write(handle) };
+
+        mem::forget(guard);
+
+        self.state.store(INITIALIZED, Ordering::Release);
+
+        Ok(self.get().expect("Initialized"))
+    }
+
+    /// Returns a reference to the value, initializing it with `fun` if not already initialized.
+    ///
+    /// #   Panics
+    ///
+    /// If the storage cannot accomodate the value.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn get_or_init<F: FnOnce() -> T>(&self, fun: F) -> &T {
+        self.try_get_or_init(fun)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Takes the value out of the cell, if initialized, leaving it uninitialized.
+    pub fn take(&mut self) -> Option<T> {
+        if *self.state.get_mut() != INITIALIZED {
+            return None;
+        }
+
+        *self.state.get_mut() = UNINITIALIZED;
+
+        //  Safety:
+        //  -   The cell was initialized, hence `handle` is valid, and it is no longer considered so.
+        unsafe {
+            let handle = self.handle.get_mut().assume_init();
+            let storage = self.storage.get_mut();
+
+            let value = ptr::read(storage.resolve(handle).as_ptr());
+            storage.deallocate(handle);
+
+            Some(value)
+        }
+    }
+}
+
+//  Safety:
+//  -   The value may be dropped by another thread, if the cell is sent.
+unsafe impl<T: Send, S: SingleElementStorage + Send> Send for RawOnceCell<T, S> {}
+
+//  Safety:
+//  -   The value may be initialized by any thread, and is then shared between all threads.
+//  -   The storage is only ever accessed mutably by the initializing thread, then shared.
+unsafe impl<T: Send + Sync, S: SingleElementStorage + Send + Sync> Sync for RawOnceCell<T, S> {}
+
+impl<T: Debug, S: SingleElementStorage> Debug for RawOnceCell<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.get() {
+            Some(value) => write!(f, "RawOnceCell({:?})", value),
+            None => write!(f, "RawOnceCell(<uninit>)"),
+        }
+    }
+}
+
+impl<T, S: Default + SingleElementStorage> Default for RawOnceCell<T, S> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<T, S: SingleElementStorage> Drop for RawOnceCell<T, S> {
+    fn drop(&mut self) { self.take(); }
+}
+
+/// A PoC Lazy, initialized on first access by a function.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawLazy<T, S: SingleElementStorage = global::SingleElement, F = fn() -> T> {
+    cell: RawOnceCell<T, S>,
+    fun: UnsafeCell<Option<F>>,
+}
+
+impl<T, S: SingleElementStorage, F: FnOnce() -> T> RawLazy<T, S, F> {
+    /// Creates a new instance from `storage`, to be initialized by `fun`.
+    pub const fn new(storage: S, fun: F) -> Self {
+        Self { cell: RawOnceCell::new(storage), fun: UnsafeCell::new(Some(fun)) }
+    }
+
+    /// Returns a reference to the value, if initialized.
+    pub fn get(this: &Self) -> Option<&T> { this.cell.get() }
+
+    /// Returns a reference to the value, initializing it if need be.
+    ///
+    /// Returns `None` if the storage cannot accomodate the value, in which case initialization may be attempted again.
+    pub fn try_force(this: &Self) -> Option<&T> {
+        let init = || {
+            //  Safety:
+            //  -   Only the initializing thread may invoke this closure, and it does so at most once.
+            let fun = unsafe { (*this.fun.get()).take() };
+
+            fun.expect("Not initialized")()
+        };
+
+        this.cell.try_get_or_init(init).ok()
+    }
+
+    /// Returns a reference to the value, initializing it if need be.
+    ///
+    /// #   Panics
+    ///
+    /// If the storage cannot accomodate the value.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn force(this: &Self) -> &T { Self::try_force(this).expect("Sufficient capacity") }
+}
+
+//  Safety:
+//  -   `fun` is only ever accessed by the initializing thread, hence only needs to be `Send`.
+unsafe impl<T, S, F> Sync for RawLazy<T, S, F>
+    where
+        T: Send + Sync,
+        S: SingleElementStorage + Send + Sync,
+        F: Send,
+{}
+
+#[cfg(any(not(feature = "no-panic"), test))]
+impl<T, S: SingleElementStorage, F: FnOnce() -> T> core::ops::Deref for RawLazy<T, S, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T { Self::force(self) }
+}
+
+impl<T: Debug, S: SingleElementStorage, F> Debug for RawLazy<T, S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.cell.get() {
+            Some(value) => write!(f, "RawLazy({:?})", value),
+            None => write!(f, "RawLazy(<uninit>)"),
+        }
+    }
+}
+
+
+//
+//  Implementation
+//
+
+const UNINITIALIZED: u8 = 0;
+const INITIALIZING: u8 = 1;
+const INITIALIZED: u8 = 2;
+
+//  Reverts the state to UNINITIALIZED on drop.
+struct StateGuard<'a>(&'a AtomicU8);
+
+impl<'a> Drop for StateGuard<'a> {
+    fn drop(&mut self) { self.0.store(UNINITIALIZED, Ordering::Release); }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleElement;
+
+use super::*;
+
+#[test]
+fn get_or_init() {
+    let cell = RawOnceCell::<u32, SingleElement<u32>>::default();
+
+    assert_eq!(None, cell.get());
+    assert_eq!("RawOnceCell(<uninit>)", format!("{:?}", cell));
+
+    assert_eq!(&42, cell.get_or_init(|| 42));
+    assert_eq!(&42, cell.get_or_init(|| unreachable!()));
+    assert_eq!(Err(7), cell.set(7));
+    assert_eq!("RawOnceCell(42)", format!("{:?}", cell));
+}
+
+#[test]
+fn try_get_or_init_failure() {
+    let cell = RawOnceCell::<u32, SingleElement<u8>>::default();
+
+    assert!(cell.try_get_or_init(|| 42).is_err());
+    assert_eq!(None, cell.get());
+}
+
+#[test]
+fn take_set() {
+    let mut cell = RawOnceCell::<u32, SingleElement<u32>>::default();
+
+    assert_eq!(Ok(()), cell.set(1));
+
+    *cell.get_mut().unwrap() += 1;
+
+    assert_eq!(Some(2), cell.take());
+    assert_eq!(None, cell.get());
+    assert_eq!(&3, cell.get_or_init(|| 3));
+}
+
+#[test]
+fn lazy() {
+    let table = RawLazy::new(SingleElement::<[u8; 16]>::new(), || {
+        let mut table = [0u8; 16];
+        table.iter_mut().enumerate().for_each(|(i, e)| *e = (i * i) as u8);
+        table
+    });
+
+    assert_eq!(None, RawLazy::get(&table));
+    assert_eq!(49, table[7]);
+    assert_eq!(Some(&225), RawLazy::get(&table).map(|table| &table[15]));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use std::{alloc::Global, sync::Arc, thread};
+
+use crate::allocator::SingleElement;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn drop_deallocates() {
+    let allocator = SpyAllocator::default();
+
+    let cell = RawOnceCell::new(SingleElement::new(allocator.clone()));
+
+    assert_eq!("Hello", cell.get_or_init(|| "Hello".to_string()));
+    assert_eq!(1, allocator.allocated());
+
+    drop(cell);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn threads() {
+    let cell = Arc::new(RawOnceCell::new(SingleElement::new(Global)));
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let cell = cell.clone();
+            thread::spawn(move || *cell.get_or_init(|| i))
+        })
+        .collect();
+
+    let results: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+
+    assert!(results.iter().all(|result| *result == results[0]));
+}
+
+} // mod test_allocator
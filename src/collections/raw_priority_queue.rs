@@ -0,0 +1,360 @@
+//! Proof-of-Concept implementation of an indexed PriorityQueue parameterized by a Storage.
+//!
+//! The queue is built atop 2 ranges: the binary heap of elements, and the index of the position of each element within
+//! the heap, which allows changing the priority of any element in O(log N).
+
+use core::fmt::{self, Debug};
+
+use crate::{global, traits::SingleRangeStorage};
+
+use super::RawVec;
+
+/// A PoC indexed PriorityQueue.
+///
+/// Like `BinaryHeap`, this is a max-heap: `pop` returns the greatest element. Use `core::cmp::Reverse` for a min-heap.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawPriorityQueue<T, S: SingleRangeStorage = global::SingleRange> {
+    free: usize,
+    heap: RawVec<HeapEntry<T>, S>,
+    slots: RawVec<Slot, S>,
+}
+
+/// A handle to an element of a RawPriorityQueue.
+///
+/// The handle is valid until the element is popped or removed, after which it may be reused for another element.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RawPriorityQueueHandle(usize);
+
+impl<T, S: SingleRangeStorage> RawPriorityQueue<T, S> {
+    /// Creates a new instance, with the elements stored in `heap` and their index in `index`.
+    pub fn new(heap: S, index: S) -> Self {
+        Self { free: INVALID_FREE, heap: RawVec::new(heap), slots: RawVec::new(index), }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.heap.is_empty() }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.heap.len() }
+
+    /// Clears `self`, destroying all elements, and invalidating all handles.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.slots.clear();
+        self.free = INVALID_FREE;
+    }
+
+    /// Returns whether `handle` refers to an element of `self`.
+    pub fn contains(&self, handle: RawPriorityQueueHandle) -> bool { self.position(handle).is_some() }
+
+    /// Returns a reference to the greatest element, if any.
+    pub fn peek(&self) -> Option<&T> { self.heap.first().map(|entry| &entry.value) }
+
+    /// Returns a reference to the element referred to by `handle`, if any.
+    pub fn get(&self, handle: RawPriorityQueueHandle) -> Option<&T> {
+        self.position(handle).map(|position| &self.heap[position].value)
+    }
+}
+
+impl<T: Ord, S: SingleRangeStorage> RawPriorityQueue<T, S> {
+    /// Attempts to push a new element, returning its handle.
+    pub fn try_push(&mut self, value: T) -> Result<RawPriorityQueueHandle, T> {
+        if self.heap.try_grow_for_push().is_err() {
+            return Err(value);
+        }
+
+        if self.free == INVALID_FREE && self.slots.try_grow_for_push().is_err() {
+            return Err(value);
+        }
+
+        let position = self.heap.len();
+
+        let slot = if self.free != INVALID_FREE {
+            let slot = self.free;
+            self.free = match self.slots[slot] {
+                Slot::Vacant(next) => next,
+                Slot::Occupied(_) => unreachable!("Free list to only contain vacant slots"),
+            };
+            self.slots[slot] = Slot::Occupied(position);
+            slot
+        } else {
+            //  Safety:
+            //  -   `self.slots` has room for one more element, as per `try_grow_for_push`.
+            unsafe { self.slots.push_unchecked(Slot::Occupied(position)) };
+            self.slots.len() - 1
+        };
+
+        //  Safety:
+        //  -   `self.heap` has room for one more element, as per `try_grow_for_push`.
+        unsafe { self.heap.push_unchecked(HeapEntry { value, slot }) };
+        self.sift_up(position);
+
+        Ok(RawPriorityQueueHandle(slot))
+    }
+
+    /// Pushes a new element, returning its handle.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn push(&mut self, value: T) -> RawPriorityQueueHandle {
+        self.try_push(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Pops the greatest element, if any.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        Some(self.remove_at(0))
+    }
+
+    /// Removes the element referred to by `handle`, if any.
+    pub fn remove(&mut self, handle: RawPriorityQueueHandle) -> Option<T> {
+        self.position(handle).map(|position| self.remove_at(position))
+    }
+
+    /// Changes the priority of the element referred to by `handle`, by applying `fun` to it.
+    ///
+    /// Returns whether the element was found.
+    pub fn change_priority<F: FnOnce(&mut T)>(&mut self, handle: RawPriorityQueueHandle, fun: F) -> bool {
+        let position = match self.position(handle) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        fun(&mut self.heap[position].value);
+
+        let position = self.sift_up(position);
+        self.sift_down(position);
+
+        true
+    }
+}
+
+impl<T: Debug, S: SingleRangeStorage> Debug for RawPriorityQueue<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.heap.iter().map(|entry| &entry.value)).finish()
+    }
+}
+
+impl<T, S: Default + SingleRangeStorage> Default for RawPriorityQueue<T, S> {
+    fn default() -> Self { Self::new(S::default(), S::default()) }
+}
+
+//
+//  Implementation
+//
+
+const INVALID_FREE: usize = usize::MAX;
+
+struct HeapEntry<T> {
+    value: T,
+    slot: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Occupied(usize),
+    Vacant(usize),
+}
+
+impl<T, S: SingleRangeStorage> RawPriorityQueue<T, S> {
+    fn position(&self, handle: RawPriorityQueueHandle) -> Option<usize> {
+        match self.slots.get(handle.0) {
+            Some(Slot::Occupied(position)) => Some(*position),
+            _ => None,
+        }
+    }
+
+    //  Swaps the elements at positions `a` and `b`, updating the index.
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+
+        let (slot_a, slot_b) = (self.heap[a].slot, self.heap[b].slot);
+
+        self.slots[slot_a] = Slot::Occupied(a);
+        self.slots[slot_b] = Slot::Occupied(b);
+    }
+}
+
+impl<T: Ord, S: SingleRangeStorage> RawPriorityQueue<T, S> {
+    fn remove_at(&mut self, position: usize) -> T {
+        let last = self.heap.len() - 1;
+
+        if position != last {
+            self.swap(position, last);
+        }
+
+        let entry = self.heap.pop().expect("Non-empty");
+
+        self.slots[entry.slot] = Slot::Vacant(self.free);
+        self.free = entry.slot;
+
+        if position < self.heap.len() {
+            let position = self.sift_up(position);
+            self.sift_down(position);
+        }
+
+        entry.value
+    }
+
+    //  Returns the final position of the element.
+    fn sift_up(&mut self, mut position: usize) -> usize {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+
+            if self.heap[position].value <= self.heap[parent].value {
+                break;
+            }
+
+            self.swap(position, parent);
+            position = parent;
+        }
+
+        position
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        let len = self.heap.len();
+
+        loop {
+            let left = 2 * position + 1;
+            let right = left + 1;
+
+            let mut greatest = position;
+
+            if left < len && self.heap[left].value > self.heap[greatest].value {
+                greatest = left;
+            }
+
+            if right < len && self.heap[right].value > self.heap[greatest].value {
+                greatest = right;
+            }
+
+            if greatest == position {
+                break;
+            }
+
+            self.swap(position, greatest);
+            position = greatest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use core::cmp::Reverse;
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Queue<T> = RawPriorityQueue<T, SingleRange<u8, [usize; 2], 8>>;
+
+#[test]
+fn smoke_test() {
+    let mut queue = Queue::<u32>::default();
+
+    for i in [3, 1, 4, 1, 5, 9, 2, 6] {
+        queue.push(i);
+    }
+
+    assert_eq!(8, queue.len());
+    assert_eq!(Some(&9), queue.peek());
+
+    let mut popped = Vec::new();
+    while let Some(e) = queue.pop() {
+        popped.push(e);
+    }
+
+    assert_eq!(vec![9, 6, 5, 4, 3, 2, 1, 1], popped);
+}
+
+#[test]
+fn change_priority() {
+    let mut queue = Queue::<Reverse<u32>>::default();
+
+    let a = queue.push(Reverse(10));
+    let b = queue.push(Reverse(20));
+    let c = queue.push(Reverse(30));
+
+    assert!(queue.change_priority(c, |p| p.0 = 5));
+    assert_eq!(Some(&Reverse(5)), queue.peek());
+
+    assert!(queue.change_priority(c, |p| p.0 = 25));
+    assert_eq!(Some(&Reverse(10)), queue.peek());
+
+    assert_eq!(Some(Reverse(20)), queue.remove(b));
+    assert!(!queue.contains(b));
+    assert!(!queue.change_priority(b, |p| p.0 = 0));
+
+    assert_eq!(Some(Reverse(10)), queue.pop());
+    assert_eq!(Some(&Reverse(25)), queue.get(c));
+    assert_eq!(None, queue.get(a));
+}
+
+#[test]
+fn handles_reused() {
+    let mut queue = Queue::<u32>::default();
+
+    for i in 0..8 {
+        queue.push(i);
+    }
+
+    assert_eq!(Err(8), queue.try_push(8));
+
+    assert_eq!(Some(7), queue.pop());
+
+    let handle = queue.push(8);
+
+    assert_eq!(Some(&8), queue.get(handle));
+    assert_eq!(Some(8), queue.pop());
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn smoke_test() {
+    let allocator = SpyAllocator::default();
+
+    let mut queue = RawPriorityQueue::new(SingleRange::new(allocator.clone()), SingleRange::new(allocator.clone()));
+
+    let handles: Vec<_> = (0..100).map(|i| (i, queue.push(i.to_string()))).collect();
+
+    for (i, handle) in &handles {
+        if i % 2 == 0 {
+            assert_eq!(Some(i.to_string()), queue.remove(*handle));
+        }
+    }
+
+    assert_eq!(50, queue.len());
+    assert_eq!(Some("99".to_string()), queue.pop());
+
+    drop(queue);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_push_failure() {
+    let mut queue = RawPriorityQueue::new(SingleRange::new(NonAllocator), SingleRange::new(NonAllocator));
+
+    assert_eq!(Err(1), queue.try_push(1));
+}
+
+} // mod test_allocator
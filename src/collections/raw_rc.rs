@@ -0,0 +1,381 @@
+//! Proof-of-Concept implementation of a Rc, and its Weak counterpart, parameterized by a Storage.
+//!
+//! The reference counts, the value, and the storage itself, are stored together in a single element: once the element
+//! is allocated, the storage is moved within it, so that all clones may share it, and the last of them may deallocate
+//! the element.
+//!
+//! This requires the element not to move when the storage is moved, hence the storage must be a `PinningStorage`.
+//! Inline storages, which store the element within themselves, are therefore excluded.
+//!
+//! Placing a Rc entirely inline, or in an arena, is not supported: the storage would be moved within the very block
+//! it manages, and deallocate it through a self-referential borrow.
+
+use core::{
+    cell::Cell,
+    cmp::Ordering,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::Deref,
+    ptr::{self, NonNull},
+};
+
+use crate::{global, traits::{ElementStorage, PinningStorage, SingleElementStorage}};
+
+/// A PoC Rc.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawRc<T, S: SingleElementStorage + PinningStorage = global::SingleElement> {
+    inner: NonNull<RcInner<T, S>>,
+    _marker: PhantomData<RcInner<T, S>>,
+}
+
+/// A PoC Weak, a non-owning reference to the value of a RawRc.
+pub struct RawWeak<T, S: SingleElementStorage + PinningStorage = global::SingleElement> {
+    inner: NonNull<RcInner<T, S>>,
+    _marker: PhantomData<RcInner<T, S>>,
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> RawRc<T, S> {
+    /// Creates an instance of Self, containing `value` stored in `storage`, which is moved alongside it.
+    pub fn new(value: T, mut storage: S) -> Result<Self, (T, S)> {
+        let handle = match storage.allocate::<RcInner<T, S>>(()) {
+            Ok(handle) => handle,
+            Err(_) => return Err((value, storage)),
+        };
+
+        //  Safety:
+        //  -   `handle` is valid, having just been allocated.
+        let inner = unsafe { storage.resolve_mut(handle) };
+
+        let value = RcInner {
+            strong: Cell::new(1),
+            weak: Cell::new(1),
+            handle,
+            storage: ManuallyDrop::new(storage),
+            value: ManuallyDrop::new(value),
+        };
+
+        //  Safety:
+        //  -   `inner` points to a memory area suitable for `RcInner<T, S>`.
+        //  -   `inner` remains valid as `storage` is moved, as `S` is a `PinningStorage`.
+        unsafe { ptr::write(inner.as_ptr(), value) };
+
+        Ok(RawRc { inner, _marker: PhantomData })
+    }
+
+    /// Creates an instance of Self, containing `value` stored in a default-constructed storage.
+    pub fn try_new(value: T) -> Result<Self, T>
+        where
+            S: Default,
+    {
+        Self::new(value, S::default()).map_err(|(value, _)| value)
+    }
+
+    /// Creates a RawWeak pointing to the same value.
+    pub fn downgrade(this: &Self) -> RawWeak<T, S> {
+        this.inner().increment_weak();
+
+        RawWeak { inner: this.inner, _marker: PhantomData }
+    }
+
+    /// Returns the number of RawRc pointing to the same value.
+    pub fn strong_count(this: &Self) -> usize { this.inner().strong.get() }
+
+    /// Returns the number of RawWeak pointing to the same value.
+    pub fn weak_count(this: &Self) -> usize { this.inner().weak.get() - 1 }
+
+    /// Returns whether `this` and `other` point to the same value, or not.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool { this.inner == other.inner }
+
+    /// Returns a mutable reference to the value, if no other RawRc or RawWeak point to it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if Self::strong_count(this) != 1 || Self::weak_count(this) != 0 {
+            return None;
+        }
+
+        //  Safety:
+        //  -   `this` is the unique reference to the value.
+        Some(unsafe { &mut (*this.inner.as_ptr()).value })
+    }
+
+    /// Returns the value, if `this` is the only RawRc pointing to it, or `this` otherwise.
+    ///
+    /// The remaining RawWeak, if any, can no longer be upgraded.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if Self::strong_count(&this) != 1 {
+            return Err(this);
+        }
+
+        let this = ManuallyDrop::new(this);
+        let inner = this.inner();
+
+        inner.strong.set(0);
+
+        //  Safety:
+        //  -   The value is no longer reachable, as the strong count reached 0, hence is moved out exactly once.
+        let value = unsafe { ManuallyDrop::take(&mut (*this.inner.as_ptr()).value) };
+
+        //  Safety:
+        //  -   The implicit weak reference held by the strong references is released.
+        unsafe { RcInner::release_weak(this.inner) };
+
+        Ok(value)
+    }
+
+    fn inner(&self) -> &RcInner<T, S> {
+        //  Safety:
+        //  -   `self.inner` is valid, as long as `self` holds a strong reference.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> RawWeak<T, S> {
+    /// Attempts to obtain a RawRc pointing to the same value, if it is still alive.
+    pub fn upgrade(&self) -> Option<RawRc<T, S>> {
+        let inner = self.inner();
+        let strong = inner.strong.get();
+
+        if strong == 0 {
+            return None;
+        }
+
+        inner.strong.set(strong + 1);
+
+        Some(RawRc { inner: self.inner, _marker: PhantomData })
+    }
+
+    /// Returns the number of RawRc pointing to the same value.
+    pub fn strong_count(&self) -> usize { self.inner().strong.get() }
+
+    /// Returns the number of RawWeak pointing to the same value, or 0 if no RawRc does.
+    pub fn weak_count(&self) -> usize {
+        let inner = self.inner();
+
+        if inner.strong.get() == 0 { 0 } else { inner.weak.get() - 1 }
+    }
+
+    /// Returns whether `self` and `other` point to the same value, or not.
+    pub fn ptr_eq(&self, other: &Self) -> bool { self.inner == other.inner }
+
+    fn inner(&self) -> &RcInner<T, S> {
+        //  Safety:
+        //  -   `self.inner` is valid, as long as `self` holds a weak reference.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Clone for RawRc<T, S> {
+    fn clone(&self) -> Self {
+        let inner = self.inner();
+
+        inner.strong.set(inner.strong.get() + 1);
+
+        RawRc { inner: self.inner, _marker: PhantomData }
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Clone for RawWeak<T, S> {
+    fn clone(&self) -> Self {
+        self.inner().increment_weak();
+
+        RawWeak { inner: self.inner, _marker: PhantomData }
+    }
+}
+
+impl<T: Debug, S: SingleElementStorage + PinningStorage> Debug for RawRc<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "{:?}", **self) }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Debug for RawWeak<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "(Weak)") }
+}
+
+impl<T: Display, S: SingleElementStorage + PinningStorage> Display for RawRc<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "{}", **self) }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Deref for RawRc<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T { &self.inner().value }
+}
+
+impl<T: PartialEq, S: SingleElementStorage + PinningStorage> PartialEq for RawRc<T, S> {
+    fn eq(&self, other: &Self) -> bool { **self == **other }
+}
+
+impl<T: Eq, S: SingleElementStorage + PinningStorage> Eq for RawRc<T, S> {}
+
+impl<T: PartialOrd, S: SingleElementStorage + PinningStorage> PartialOrd for RawRc<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { (**self).partial_cmp(&**other) }
+}
+
+impl<T: Ord, S: SingleElementStorage + PinningStorage> Ord for RawRc<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering { (**self).cmp(&**other) }
+}
+
+impl<T: Hash, S: SingleElementStorage + PinningStorage> Hash for RawRc<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) { (**self).hash(state) }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Drop for RawRc<T, S> {
+    fn drop(&mut self) {
+        let inner = self.inner();
+        let strong = inner.strong.get() - 1;
+
+        inner.strong.set(strong);
+
+        if strong != 0 {
+            return;
+        }
+
+        //  Safety:
+        //  -   The value is no longer reachable, as the strong count reached 0, hence is dropped exactly once.
+        unsafe { ManuallyDrop::drop(&mut (*self.inner.as_ptr()).value) };
+
+        //  Safety:
+        //  -   The implicit weak reference held by the strong references is released.
+        unsafe { RcInner::release_weak(self.inner) };
+    }
+}
+
+impl<T, S: SingleElementStorage + PinningStorage> Drop for RawWeak<T, S> {
+    fn drop(&mut self) {
+        //  Safety:
+        //  -   `self` holds a weak reference, which is released.
+        unsafe { RcInner::release_weak(self.inner) };
+    }
+}
+
+
+//
+//  Implementation
+//
+
+struct RcInner<T, S: ElementStorage> {
+    strong: Cell<usize>,
+    //  The strong references collectively hold one weak reference.
+    weak: Cell<usize>,
+    handle: S::Handle<Self>,
+    storage: ManuallyDrop<S>,
+    value: ManuallyDrop<T>,
+}
+
+impl<T, S: SingleElementStorage> RcInner<T, S> {
+    fn increment_weak(&self) { self.weak.set(self.weak.get() + 1); }
+
+    //  Releases a weak reference, deallocating the element if it was the last one.
+    //
+    //  #   Safety
+    //
+    //  -   Assumes that `this` is valid, and that the caller holds a weak reference.
+    unsafe fn release_weak(this: NonNull<Self>) {
+        let weak = this.as_ref().weak.get() - 1;
+
+        this.as_ref().weak.set(weak);
+
+        if weak != 0 {
+            return;
+        }
+
+        //  The storage is moved out of the element, prior to deallocating it.
+        let handle = this.as_ref().handle;
+        let mut storage = ManuallyDrop::take(&mut (*this.as_ptr()).storage);
+
+        storage.deallocate(handle);
+
+        mem::drop(storage);
+    }
+}
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+type Rc<T> = RawRc<T, SingleElement<SpyAllocator>>;
+
+#[test]
+fn smoke_test() {
+    let allocator = SpyAllocator::default();
+
+    let rc = Rc::new("Hello".to_string(), SingleElement::new(allocator.clone())).unwrap();
+    let other = rc.clone();
+
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(2, RawRc::strong_count(&rc));
+    assert!(RawRc::ptr_eq(&rc, &other));
+    assert_eq!("Hello", &*other);
+    assert_eq!(r#""Hello""#, format!("{:?}", rc));
+
+    drop(rc);
+
+    assert_eq!(1, RawRc::strong_count(&other));
+    assert_eq!(0, allocator.deallocated());
+
+    drop(other);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn weak() {
+    let allocator = SpyAllocator::default();
+
+    let mut rc = Rc::new(vec![1, 2, 3], SingleElement::new(allocator.clone())).unwrap();
+
+    RawRc::get_mut(&mut rc).unwrap().push(4);
+
+    let weak = RawRc::downgrade(&rc);
+
+    assert_eq!(1, RawRc::weak_count(&rc));
+    assert_eq!(None, RawRc::get_mut(&mut rc));
+    assert_eq!(Some(vec![1, 2, 3, 4]), weak.upgrade().map(|rc| (*rc).clone()));
+
+    drop(rc);
+
+    //  The value is dropped, but the element is retained for the weak reference.
+    assert_eq!(0, weak.strong_count());
+    assert_eq!(0, weak.weak_count());
+    assert!(weak.upgrade().is_none());
+    assert_eq!(0, allocator.deallocated());
+
+    drop(weak);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn try_unwrap() {
+    let allocator = SpyAllocator::default();
+
+    let rc = Rc::new("Hello".to_string(), SingleElement::new(allocator.clone())).unwrap();
+    let other = rc.clone();
+    let weak = RawRc::downgrade(&rc);
+
+    let rc = RawRc::try_unwrap(rc).unwrap_err();
+
+    drop(other);
+
+    assert_eq!(Ok("Hello".to_string()), RawRc::try_unwrap(rc));
+    assert!(weak.upgrade().is_none());
+    assert_eq!(0, allocator.deallocated());
+
+    drop(weak);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn allocation_failure() {
+    let result = RawRc::new(1u32, SingleElement::new(NonAllocator));
+
+    assert_eq!(1, result.map(|_| ()).unwrap_err().0);
+}
+
+} // mod test_allocator
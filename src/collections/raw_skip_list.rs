@@ -0,0 +1,463 @@
+//! Proof-of-Concept implementation of a SkipList parameterized by a Storage.
+//!
+//! Each node of the list is an element of a MultiElementStorage, whose tower of links to the following nodes is of a
+//! height drawn at random on insertion: the nodes are thus unsized, their height being the meta-data of their handle.
+//!
+//! The maximum height of the towers, `H`, is a const generic parameter, so that the size of the largest nodes can be
+//! bounded to match the size of the slots of the storage, see `RawSkipList::max_node_size`.
+
+use core::{
+    borrow::Borrow,
+    cmp::{self, Ordering},
+    fmt::{self, Debug},
+    marker::PhantomData,
+    ptr::{self, NonNull},
+};
+
+use crate::{global, traits::{ElementStorage, MultiElementStorage}, utils};
+
+/// A PoC SkipList, storing its elements in order, without duplicates.
+///
+/// `H` is the maximum height of a tower, it must be at least 1.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawSkipList<T, S: MultiElementStorage = global::MultiElement, const H: usize = 16> {
+    head: [Option<NodeHandle<T, S>>; H],
+    height: usize,
+    len: usize,
+    seed: u64,
+    storage: S,
+    _marker: PhantomData<T>,
+}
+
+impl<T, S: MultiElementStorage, const H: usize> RawSkipList<T, S, H> {
+    //  Compile-time check of the validity of `H`.
+    const VALID_HEIGHT: () = assert!(H >= 1, "H must be at least 1");
+
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self { Self::with_seed(DEFAULT_SEED, storage) }
+
+    /// Creates a new instance from `storage`, seeding the generator of the heights of the towers with `seed`.
+    ///
+    /// A `seed` of 0 is replaced by the default seed.
+    pub fn with_seed(seed: u64, storage: S) -> Self {
+        let () = Self::VALID_HEIGHT;
+
+        let seed = if seed == 0 { DEFAULT_SEED } else { seed };
+
+        Self { head: [None; H], height: 0, len: 0, seed, storage, _marker: PhantomData }
+    }
+
+    /// Returns the size of the largest node, in bytes, that is of a node whose tower is `H` high.
+    pub fn max_node_size() -> usize { utils::layout_of::<RawSkipListNode<T, S>>(H).size() }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Clears `self`, destroying all elements and deallocating all nodes.
+    pub fn clear(&mut self) {
+        let mut next = self.head[0];
+
+        while let Some(handle) = next {
+            next = self.node(handle).tower[0];
+
+            //  Safety:
+            //  -   `handle` is valid, and no longer linked once the head is reset.
+            unsafe { self.storage.destroy(handle) };
+        }
+
+        self.head = [None; H];
+        self.height = 0;
+        self.len = 0;
+    }
+
+    /// Returns a reference to the smallest element, if any.
+    pub fn first(&self) -> Option<&T> { self.head[0].map(|handle| &self.node(handle).element) }
+
+    /// Removes and returns the smallest element, if any.
+    pub fn pop_first(&mut self) -> Option<T> {
+        let handle = self.head[0]?;
+
+        let height = self.node(handle).tower.len();
+
+        for level in 0..height {
+            self.head[level] = self.node(handle).tower[level];
+        }
+
+        Some(self.unlink(handle))
+    }
+
+    /// Returns an iterator over the elements, in order.
+    pub fn iter(&self) -> RawSkipListIter<'_, T, S> {
+        RawSkipListIter { storage: &self.storage, next: self.head[0], remaining: self.len, _marker: PhantomData }
+    }
+}
+
+impl<T: Ord, S: MultiElementStorage, const H: usize> RawSkipList<T, S, H> {
+    /// Returns whether `value` is contained in `self`, or not.
+    pub fn contains<Q: ?Sized + Ord>(&self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+    {
+        self.get(value).is_some()
+    }
+
+    /// Returns a reference to the element equal to `value`, if any.
+    pub fn get<Q: ?Sized + Ord>(&self, value: &Q) -> Option<&T>
+        where
+            T: Borrow<Q>,
+    {
+        let mut previous = None;
+
+        for level in (0..self.height).rev() {
+            previous = self.skip_before(previous, level, value);
+        }
+
+        let candidate = self.next_of(previous, 0)?;
+        let element = &self.node(candidate).element;
+
+        (element.borrow() == value).then_some(element)
+    }
+
+    /// Attempts to insert `value`, returning whether it was newly inserted.
+    ///
+    /// If an equal element is already present, it is left untouched and `value` is dropped.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, T> {
+        let previous = self.predecessors(&value);
+
+        if let Some(candidate) = self.next_of(previous[0], 0) {
+            if self.node(candidate).element == value {
+                return Ok(false);
+            }
+        }
+
+        let height = self.draw_height();
+
+        let handle = match self.storage.allocate::<RawSkipListNode<T, S>>(height) {
+            Ok(handle) => handle,
+            Err(_) => return Err(value),
+        };
+
+        //  Safety:
+        //  -   `handle` is valid, having just been allocated.
+        let node = unsafe { self.storage.resolve_mut(handle) }.as_ptr();
+
+        //  Safety:
+        //  -   `node` points to a memory area suitable for a node of `height`, whose fields are written in place.
+        unsafe {
+            ptr::write(ptr::addr_of_mut!((*node).element), value);
+
+            let tower = ptr::addr_of_mut!((*node).tower) as *mut Option<NodeHandle<T, S>>;
+
+            for level in 0..height {
+                ptr::write(tower.add(level), None);
+            }
+        }
+
+        for (level, previous) in previous.iter().enumerate().take(height) {
+            let next = self.next_of(*previous, level);
+
+            self.node_mut(handle).tower[level] = next;
+            self.set_next_of(*previous, level, Some(handle));
+        }
+
+        self.height = cmp::max(self.height, height);
+        self.len += 1;
+
+        Ok(true)
+    }
+
+    /// Inserts `value`, returning whether it was newly inserted.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+
+    /// Removes and returns the element equal to `value`, if any.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, value: &Q) -> Option<T>
+        where
+            T: Borrow<Q>,
+    {
+        let previous = self.predecessors(value);
+
+        let candidate = self.next_of(previous[0], 0)?;
+
+        if self.node(candidate).element.borrow() != value {
+            return None;
+        }
+
+        //  All predecessors of the candidate, up to its height, link to it, as it is the first element not less than
+        //  `value` on each level.
+        let height = self.node(candidate).tower.len();
+
+        for (level, previous) in previous.iter().enumerate().take(height) {
+            let next = self.node(candidate).tower[level];
+
+            self.set_next_of(*previous, level, next);
+        }
+
+        Some(self.unlink(candidate))
+    }
+}
+
+impl<T: Debug, S: MultiElementStorage, const H: usize> Debug for RawSkipList<T, S, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: Default + MultiElementStorage, const H: usize> Default for RawSkipList<T, S, H> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<T, S: MultiElementStorage, const H: usize> Drop for RawSkipList<T, S, H> {
+    fn drop(&mut self) { self.clear(); }
+}
+
+/// Iterator over the elements of a RawSkipList, in order.
+pub struct RawSkipListIter<'a, T, S: MultiElementStorage> {
+    storage: &'a S,
+    next: Option<NodeHandle<T, S>>,
+    remaining: usize,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T, S: MultiElementStorage> Iterator for RawSkipListIter<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|handle| {
+            //  Safety:
+            //  -   `handle` is valid, as it is linked in the list, which is borrowed for `'a`.
+            let node = unsafe { &*self.storage.resolve(handle).as_ptr() };
+
+            self.next = node.tower[0];
+            self.remaining -= 1;
+
+            &node.element
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining, Some(self.remaining)) }
+}
+
+/// A PoC SkipList node, of unknown height.
+///
+/// Exposed so that the layout of the nodes may be computed, to size the slots of a storage, see `utils::layout_of`.
+pub struct RawSkipListNode<T, S: MultiElementStorage> {
+    element: T,
+    tower: [Option<S::Handle<Self>>],
+}
+
+
+//
+//  Implementation
+//
+
+const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+type NodeHandle<T, S> = <S as ElementStorage>::Handle<RawSkipListNode<T, S>>;
+
+impl<T, S: MultiElementStorage, const H: usize> RawSkipList<T, S, H> {
+    //  Draws the height of a new tower, following a geometric distribution of parameter 1/2.
+    fn draw_height(&mut self) -> usize {
+        //  xorshift64.
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+
+        cmp::min(self.seed.trailing_ones() as usize + 1, H)
+    }
+
+    fn node(&self, handle: NodeHandle<T, S>) -> &RawSkipListNode<T, S> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid, as all handles are linked in the list.
+        unsafe { &*self.storage.resolve(handle).as_ptr() }
+    }
+
+    fn node_mut(&mut self, handle: NodeHandle<T, S>) -> &mut RawSkipListNode<T, S> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid, as all handles are linked in the list.
+        unsafe { &mut *self.storage.resolve_mut(handle).as_ptr() }
+    }
+
+    //  Returns the node following `previous` on `level`, where `None` designates the head.
+    fn next_of(&self, previous: Option<NodeHandle<T, S>>, level: usize) -> Option<NodeHandle<T, S>> {
+        match previous {
+            None => self.head[level],
+            Some(handle) => self.node(handle).tower[level],
+        }
+    }
+
+    //  Sets the node following `previous` on `level`, where `None` designates the head.
+    fn set_next_of(&mut self, previous: Option<NodeHandle<T, S>>, level: usize, next: Option<NodeHandle<T, S>>) {
+        match previous {
+            None => self.head[level] = next,
+            Some(handle) => self.node_mut(handle).tower[level] = next,
+        }
+    }
+
+    //  Unlinks `handle`, already bypassed on all levels, and returns its element.
+    fn unlink(&mut self, handle: NodeHandle<T, S>) -> T {
+        while self.height > 0 && self.head[self.height - 1].is_none() {
+            self.height -= 1;
+        }
+
+        self.len -= 1;
+
+        //  Safety:
+        //  -   `handle` is valid, being linked until now.
+        let node: NonNull<RawSkipListNode<T, S>> = unsafe { self.storage.resolve_mut(handle) };
+
+        //  Safety:
+        //  -   `node` is valid, and no longer linked, hence its element is moved out exactly once.
+        let element = unsafe { ptr::read(ptr::addr_of!((*node.as_ptr()).element)) };
+
+        //  Safety:
+        //  -   `handle` is valid, and no longer linked.
+        unsafe { self.storage.deallocate(handle) };
+
+        element
+    }
+}
+
+impl<T: Ord, S: MultiElementStorage, const H: usize> RawSkipList<T, S, H> {
+    //  Returns the last node strictly less than `value` on `level`, starting from `previous`.
+    fn skip_before<Q: ?Sized + Ord>(&self, mut previous: Option<NodeHandle<T, S>>, level: usize, value: &Q)
+        -> Option<NodeHandle<T, S>>
+        where
+            T: Borrow<Q>,
+    {
+        while let Some(next) = self.next_of(previous, level) {
+            if self.node(next).element.borrow().cmp(value) != Ordering::Less {
+                break;
+            }
+
+            previous = Some(next);
+        }
+
+        previous
+    }
+
+    //  Returns the last node strictly less than `value` on each level, where `None` designates the head.
+    fn predecessors<Q: ?Sized + Ord>(&self, value: &Q) -> [Option<NodeHandle<T, S>>; H]
+        where
+            T: Borrow<Q>,
+    {
+        let mut result = [None; H];
+        let mut previous = None;
+
+        for level in (0..self.height).rev() {
+            previous = self.skip_before(previous, level, value);
+            result[level] = previous;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::MultiElement;
+
+use super::*;
+
+type List = RawSkipList<u8, MultiElement<[usize; 9], 12>, 4>;
+
+#[test]
+fn smoke_test() {
+    assert!(List::max_node_size() <= 72);
+
+    let mut list = List::default();
+
+    assert!(list.is_empty());
+    assert_eq!(None, list.first());
+
+    for i in [5, 3, 8, 1, 4, 7, 9, 2, 6, 0] {
+        assert!(list.insert(i));
+    }
+
+    assert!(!list.insert(4));
+    assert_eq!(10, list.len());
+    assert!(list.contains(&4));
+    assert!(!list.contains(&10));
+    assert_eq!(Some(&0), list.first());
+
+    assert_eq!(Some(4), list.remove(&4));
+    assert_eq!(None, list.remove(&4));
+    assert_eq!(Some(0), list.pop_first());
+
+    assert_eq!(vec![1, 2, 3, 5, 6, 7, 8, 9], list.iter().copied().collect::<Vec<_>>());
+    assert_eq!("{1, 2, 3, 5, 6, 7, 8, 9}", format!("{:?}", list));
+}
+
+#[test]
+fn capacity() {
+    let mut list = List::default();
+
+    for i in 0..12 {
+        assert_eq!(Ok(true), list.try_insert(i));
+    }
+
+    assert_eq!(Err(12), list.try_insert(12));
+    assert_eq!(Ok(false), list.try_insert(11));
+
+    list.clear();
+
+    assert!(list.is_empty());
+    assert_eq!(Ok(true), list.try_insert(12));
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::MultiElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn drop_deallocates() {
+    let allocator = SpyAllocator::default();
+
+    let mut list = RawSkipList::<String, _>::with_seed(42, MultiElement::new(allocator.clone()));
+
+    for i in (0..100).rev() {
+        assert!(list.insert(format!("{:02}", i)));
+    }
+
+    assert_eq!(Some("42".to_string()), list.remove("42"));
+    assert_eq!(99, list.len());
+    assert_eq!(100, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+
+    let expected: Vec<_> = (0..100).filter(|i| *i != 42).map(|i| format!("{:02}", i)).collect();
+
+    assert_eq!(expected, list.iter().cloned().collect::<Vec<_>>());
+
+    drop(list);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_insert_failure() {
+    let mut list = RawSkipList::<u8, _>::new(MultiElement::new(NonAllocator));
+
+    assert_eq!(Err(1), list.try_insert(1));
+    assert!(list.is_empty());
+}
+
+} // mod test_allocator
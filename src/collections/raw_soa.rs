@@ -0,0 +1,341 @@
+//! Proof-of-Concept implementation of a Struct-of-Arrays parameterized by a Storage.
+//!
+//! Each field of the tuple is stored in its own range, from its own storage, so that iterating over a subset of the
+//! fields only touches the memory of those fields.
+
+use core::{fmt::{self, Debug}, marker::PhantomData, mem};
+
+use crate::{error::StorageError, global, traits::SingleRangeStorage};
+
+use super::RawVec;
+
+/// A PoC Struct-of-Arrays.
+///
+/// `T` is a tuple of fields, such as `(A, B, C)`, each field being stored in its own range.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawSoa<T: SoaFields<S>, S: SingleRangeStorage = global::SingleRange> {
+    columns: T::Columns,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SoaFields<S>, S: SingleRangeStorage> RawSoa<T, S> {
+    /// Creates a new instance from `storages`, one per field.
+    pub fn new(storages: T::Storages) -> Self { Self { columns: T::columns(storages), _marker: PhantomData } }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { T::len(&self.columns) }
+
+    /// Clears `self`, destroying all elements and resetting its length to 0.
+    pub fn clear(&mut self) { T::clear(&mut self.columns) }
+
+    /// Attempts to push a new element at the back.
+    ///
+    /// Either all fields are pushed, or none is.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if T::try_grow_for_push(&mut self.columns).is_err() {
+            return Err(value);
+        }
+
+        //  Safety:
+        //  -   All columns have room for one more element, as per `try_grow_for_push`.
+        unsafe { T::push(&mut self.columns, value) };
+
+        Ok(())
+    }
+
+    /// Pushes an element at the back.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn push(&mut self, value: T) {
+        self.try_push(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity");
+    }
+
+    /// Pops the back element, if any.
+    pub fn pop(&mut self) -> Option<T> { T::pop(&mut self.columns) }
+
+    /// Returns the slices of each field.
+    pub fn slices(&self) -> T::Slices<'_> { T::slices(&self.columns) }
+
+    /// Returns the mutable slices of each field.
+    pub fn slices_mut(&mut self) -> T::SlicesMut<'_> { T::slices_mut(&mut self.columns) }
+
+    /// Returns an iterator over the elements, zipping the fields together.
+    pub fn iter(&self) -> RawSoaIter<'_, T, S> { RawSoaIter(self.slices()) }
+
+    /// Returns an iterator over the elements, zipping the fields together.
+    pub fn iter_mut(&mut self) -> RawSoaIterMut<'_, T, S> { RawSoaIterMut(self.slices_mut()) }
+}
+
+impl<T: SoaFields<S>, S: SingleRangeStorage> Debug for RawSoa<T, S>
+    where
+        for<'a> T::Refs<'a>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: SoaFields<S>, S: SingleRangeStorage> Default for RawSoa<T, S>
+    where
+        T::Storages: Default,
+{
+    fn default() -> Self { Self::new(T::Storages::default()) }
+}
+
+/// Iterator over the elements of a RawSoa.
+pub struct RawSoaIter<'a, T: SoaFields<S> + 'a, S: SingleRangeStorage>(T::Slices<'a>);
+
+impl<'a, T: SoaFields<S> + 'a, S: SingleRangeStorage> Iterator for RawSoaIter<'a, T, S> {
+    type Item = T::Refs<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> { T::split_first(&mut self.0) }
+}
+
+/// Iterator over the elements of a RawSoa.
+pub struct RawSoaIterMut<'a, T: SoaFields<S> + 'a, S: SingleRangeStorage>(T::SlicesMut<'a>);
+
+impl<'a, T: SoaFields<S> + 'a, S: SingleRangeStorage> Iterator for RawSoaIterMut<'a, T, S> {
+    type Item = T::RefsMut<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> { T::split_first_mut(&mut self.0) }
+}
+
+/// The fields of a RawSoa, implemented for tuples of up to 6 fields.
+pub trait SoaFields<S: SingleRangeStorage>: Sized {
+    /// The storages, one per field.
+    type Storages;
+
+    /// The columns, one per field.
+    type Columns;
+
+    /// The references to the fields of an element.
+    type Refs<'a> where Self: 'a;
+
+    /// The mutable references to the fields of an element.
+    type RefsMut<'a> where Self: 'a;
+
+    /// The slices of the fields.
+    type Slices<'a> where Self: 'a;
+
+    /// The mutable slices of the fields.
+    type SlicesMut<'a> where Self: 'a;
+
+    /// Creates the columns.
+    fn columns(storages: Self::Storages) -> Self::Columns;
+
+    /// Returns the number of elements.
+    fn len(columns: &Self::Columns) -> usize;
+
+    /// Clears all columns.
+    fn clear(columns: &mut Self::Columns);
+
+    /// Ensures that all columns have room for one more element.
+    fn try_grow_for_push(columns: &mut Self::Columns) -> Result<(), StorageError>;
+
+    /// Pushes the fields into their columns.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes all columns have room for one more element, for example following a successful `try_grow_for_push`.
+    unsafe fn push(columns: &mut Self::Columns, value: Self);
+
+    /// Pops the fields from their columns, if any.
+    fn pop(columns: &mut Self::Columns) -> Option<Self>;
+
+    /// Returns the slices of the columns.
+    fn slices(columns: &Self::Columns) -> Self::Slices<'_>;
+
+    /// Returns the mutable slices of the columns.
+    fn slices_mut(columns: &mut Self::Columns) -> Self::SlicesMut<'_>;
+
+    /// Splits the first element off the slices, if any.
+    fn split_first<'a>(slices: &mut Self::Slices<'a>) -> Option<Self::Refs<'a>>;
+
+    /// Splits the first element off the mutable slices, if any.
+    fn split_first_mut<'a>(slices: &mut Self::SlicesMut<'a>) -> Option<Self::RefsMut<'a>>;
+}
+
+//
+//  Implementation
+//
+
+macro_rules! replace {
+    ($_t:tt, $sub:ty) => { $sub };
+}
+
+macro_rules! soa_fields {
+    ($($name:ident $index:tt),+) => {
+        impl<S: SingleRangeStorage, $($name),+> SoaFields<S> for ($($name,)+) {
+            type Storages = ($(replace!($name, S),)+);
+
+            type Columns = ($(RawVec<$name, S>,)+);
+
+            type Refs<'a> = ($(&'a $name,)+) where Self: 'a;
+
+            type RefsMut<'a> = ($(&'a mut $name,)+) where Self: 'a;
+
+            type Slices<'a> = ($(&'a [$name],)+) where Self: 'a;
+
+            type SlicesMut<'a> = ($(&'a mut [$name],)+) where Self: 'a;
+
+            fn columns(storages: Self::Storages) -> Self::Columns {
+                ($(RawVec::new(storages.$index),)+)
+            }
+
+            fn len(columns: &Self::Columns) -> usize { columns.0.len() }
+
+            fn clear(columns: &mut Self::Columns) {
+                $(columns.$index.clear();)+
+            }
+
+            fn try_grow_for_push(columns: &mut Self::Columns) -> Result<(), StorageError> {
+                $(columns.$index.try_grow_for_push()?;)+
+                Ok(())
+            }
+
+            unsafe fn push(columns: &mut Self::Columns, value: Self) {
+                $(columns.$index.push_unchecked(value.$index);)+
+            }
+
+            fn pop(columns: &mut Self::Columns) -> Option<Self> {
+                if columns.0.is_empty() {
+                    return None;
+                }
+
+                Some(($(columns.$index.pop()?,)+))
+            }
+
+            fn slices(columns: &Self::Columns) -> Self::Slices<'_> {
+                ($(&*columns.$index,)+)
+            }
+
+            fn slices_mut(columns: &mut Self::Columns) -> Self::SlicesMut<'_> {
+                ($(&mut *columns.$index,)+)
+            }
+
+            fn split_first<'a>(slices: &mut Self::Slices<'a>) -> Option<Self::Refs<'a>> {
+                if slices.0.is_empty() {
+                    return None;
+                }
+
+                Some(($({
+                    let (first, rest) = slices.$index.split_first()?;
+                    slices.$index = rest;
+                    first
+                },)+))
+            }
+
+            fn split_first_mut<'a>(slices: &mut Self::SlicesMut<'a>) -> Option<Self::RefsMut<'a>> {
+                if slices.0.is_empty() {
+                    return None;
+                }
+
+                Some(($({
+                    let (first, rest) = mem::take(&mut slices.$index).split_first_mut()?;
+                    slices.$index = rest;
+                    first
+                },)+))
+            }
+        }
+    };
+}
+
+soa_fields!(A 0, B 1);
+soa_fields!(A 0, B 1, C 2);
+soa_fields!(A 0, B 1, C 2, D 3);
+soa_fields!(A 0, B 1, C 2, D 3, E 4);
+soa_fields!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+#[test]
+fn smoke_test() {
+    type Soa = RawSoa<(u8, u32), SingleRange<u8, u32, 4>>;
+
+    let mut soa = Soa::default();
+
+    soa.push((1, 10));
+    soa.push((2, 20));
+    soa.push((3, 30));
+
+    assert_eq!(3, soa.len());
+    assert_eq!(([1u8, 2, 3].as_slice(), [10u32, 20, 30].as_slice()), soa.slices());
+    assert_eq!("[(1, 10), (2, 20), (3, 30)]", format!("{:?}", soa));
+
+    for (a, b) in soa.iter_mut() {
+        *b += *a as u32;
+    }
+
+    assert_eq!(Some((3, 33)), soa.pop());
+    assert_eq!(vec![(&1, &11), (&2, &22)], soa.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn try_push_failure() {
+    type Soa = RawSoa<(u8, u32, u8), SingleRange<u8, u32, 2>>;
+
+    let mut soa = Soa::default();
+
+    soa.push((1, 10, 100));
+    soa.push((2, 20, 200));
+
+    assert_eq!(Err((3, 30, 255)), soa.try_push((3, 30, 255)));
+    assert_eq!(2, soa.len());
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn smoke_test() {
+    type Storage = SingleRange<SpyAllocator>;
+
+    let allocator = SpyAllocator::default();
+    let storages = (Storage::new(allocator.clone()), Storage::new(allocator.clone()));
+
+    let mut soa: RawSoa<(String, u64), _> = RawSoa::new(storages);
+
+    for i in 0..10 {
+        soa.push((i.to_string(), i));
+    }
+
+    assert_eq!(10, soa.len());
+    assert_eq!(Some(("9".to_string(), 9)), soa.pop());
+    assert!(soa.iter().all(|(s, i)| *s == i.to_string()));
+
+    drop(soa);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn try_push_failure() {
+    type Storage = SingleRange<NonAllocator>;
+
+    let mut soa: RawSoa<(u8, u16), Storage> = RawSoa::default();
+
+    assert_eq!(Err((1, 2)), soa.try_push((1, 2)));
+}
+
+} // mod test_allocator
@@ -0,0 +1,344 @@
+//! Proof-of-Concept implementation of a lock-free single-producer single-consumer queue parameterized by a Storage.
+//!
+//! The queue is split into a producer and a consumer, which may be used from different threads, or from an interrupt
+//! handler and a thread, as neither ever blocks nor allocates.
+
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{global, traits::{Capacity, SingleRangeStorage}};
+
+/// A PoC SPSC queue.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawSpscQueue<T, S: SingleRangeStorage = global::SingleRange> {
+    //  Index of the next value to pop, modulo `2 * capacity`.
+    head: AtomicUsize,
+    //  Index of the next value to push, modulo `2 * capacity`.
+    tail: AtomicUsize,
+    capacity: usize,
+    //  The slots are written through a shared reference to the queue, by the producer, hence the `UnsafeCell`.
+    data: S::Handle<UnsafeCell<MaybeUninit<T>>>,
+    storage: S,
+}
+
+impl<T, S: SingleRangeStorage> RawSpscQueue<T, S> {
+    /// Creates a new instance, holding up to `capacity` values.
+    ///
+    /// Returns `storage` if it cannot accomodate `capacity` values, or if `capacity` is 0 or exceeds `usize::MAX / 2`.
+    pub fn new(mut storage: S, capacity: usize) -> Result<Self, S> {
+        if capacity == 0 || capacity > usize::MAX / 2 {
+            return Err(storage);
+        }
+
+        let data = match S::Capacity::from_usize(capacity).map(|c| storage.allocate::<UnsafeCell<MaybeUninit<T>>>(c)) {
+            Some(Ok(data)) => data,
+            _ => return Err(storage),
+        };
+
+        Ok(Self { head: AtomicUsize::new(0), tail: AtomicUsize::new(0), capacity, data, storage, })
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+
+        self.distance(head, self.tail.load(Ordering::Acquire))
+    }
+
+    /// Returns the maximum number of elements in `self`.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Splits `self` into its producer and consumer halves.
+    pub fn split(&mut self) -> (RawSpscProducer<'_, T, S>, RawSpscConsumer<'_, T, S>) {
+        let queue: &Self = self;
+
+        (RawSpscProducer { queue, _marker: PhantomData }, RawSpscConsumer { queue, _marker: PhantomData })
+    }
+}
+
+impl<T, S: SingleRangeStorage> Debug for RawSpscQueue<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "RawSpscQueue{{ len: {}, capacity: {} }}", self.len(), self.capacity)
+    }
+}
+
+impl<T, S: SingleRangeStorage> Drop for RawSpscQueue<T, S> {
+    fn drop(&mut self) {
+        let (_, mut consumer) = self.split();
+
+        while consumer.pop().is_some() {}
+
+        //  Safety:
+        //  -   `self.data` is valid, and all its elements were dropped.
+        unsafe { self.storage.deallocate(self.data) };
+    }
+}
+
+/// The producing half of a RawSpscQueue.
+pub struct RawSpscProducer<'a, T, S: SingleRangeStorage> {
+    queue: &'a RawSpscQueue<T, S>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, S: SingleRangeStorage> RawSpscProducer<'a, T, S> {
+    /// Returns whether the queue is full, or not.
+    ///
+    /// The queue may no longer be full by the time this returns, should the consumer pop concurrently.
+    pub fn is_full(&self) -> bool { self.queue.len() == self.queue.capacity }
+
+    /// Attempts to push `value` at the back of the queue, returning it if the queue is full.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let tail = self.queue.tail.load(Ordering::Relaxed);
+        let head = self.queue.head.load(Ordering::Acquire);
+
+        if self.queue.distance(head, tail) == self.queue.capacity {
+            return Err(value);
+        }
+
+        //  Safety:
+        //  -   The slot is within the range, and not in use, as the queue is not full.
+        //  -   The consumer does not access the slot until the new tail is published.
+        unsafe { ptr::write(self.queue.slot(tail), MaybeUninit::new(value)) };
+
+        self.queue.tail.store(self.queue.next(tail), Ordering::Release);
+
+        Ok(())
+    }
+}
+
+//  Safety:
+//  -   The producer only moves values into the queue, and only accesses the slots not in use by the consumer.
+unsafe impl<'a, T: Send, S: SingleRangeStorage + Sync> Send for RawSpscProducer<'a, T, S> {}
+
+/// The consuming half of a RawSpscQueue.
+pub struct RawSpscConsumer<'a, T, S: SingleRangeStorage> {
+    queue: &'a RawSpscQueue<T, S>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, S: SingleRangeStorage> RawSpscConsumer<'a, T, S> {
+    /// Returns whether the queue is empty, or not.
+    ///
+    /// The queue may no longer be empty by the time this returns, should the producer push concurrently.
+    pub fn is_empty(&self) -> bool { self.queue.is_empty() }
+
+    /// Returns a reference to the front of the queue, if any.
+    pub fn peek(&self) -> Option<&T> {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        let tail = self.queue.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        //  Safety:
+        //  -   The slot is within the range, and initialized, as the queue is not empty.
+        //  -   The producer does not access the slot until the consumer pops it, which requires borrowing `self`.
+        Some(unsafe { (*self.queue.slot(head)).assume_init_ref() })
+    }
+
+    /// Pops the front of the queue, if any.
+    pub fn pop(&mut self) -> Option<T> {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        let tail = self.queue.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        //  Safety:
+        //  -   The slot is within the range, and initialized, as the queue is not empty.
+        //  -   The producer does not access the slot until the new head is published.
+        let value = unsafe { (*self.queue.slot(head)).assume_init_read() };
+
+        self.queue.head.store(self.queue.next(head), Ordering::Release);
+
+        Some(value)
+    }
+}
+
+//  Safety:
+//  -   The consumer only moves values out of the queue, and only accesses the slots not in use by the producer.
+unsafe impl<'a, T: Send, S: SingleRangeStorage + Sync> Send for RawSpscConsumer<'a, T, S> {}
+
+
+//
+//  Implementation
+//
+
+//  The indices run modulo `2 * capacity`, rather than wrapping around `usize::MAX`, so that the slot of an index is
+//  stable across the wrap, whether `capacity` is a power of 2 or not, and a full queue is distinguished from an empty
+//  one.
+impl<T, S: SingleRangeStorage> RawSpscQueue<T, S> {
+    //  Returns the number of values from `head` to `tail`.
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        if tail >= head { tail - head } else { 2 * self.capacity - head + tail }
+    }
+
+    //  Returns the index following `index`.
+    fn next(&self, index: usize) -> usize {
+        if index + 1 == 2 * self.capacity { 0 } else { index + 1 }
+    }
+
+    //  Returns a pointer to the slot of `index`.
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        let index = if index >= self.capacity { index - self.capacity } else { index };
+
+        debug_assert!(index < self.capacity);
+
+        //  Safety:
+        //  -   `self.data` is valid, as it is owned by `self`.
+        let range = unsafe { self.storage.resolve(self.data) };
+
+        //  Safety:
+        //  -   `index` is within the range.
+        //  -   The slot is only accessed through its `UnsafeCell`, hence may be written through `&self`.
+        unsafe { UnsafeCell::raw_get(range.as_mut_ptr().add(index) as *const UnsafeCell<MaybeUninit<T>>) }
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Queue = RawSpscQueue<u8, SingleRange<u8, u8, 4>>;
+
+#[test]
+fn new_failure() {
+    Queue::new(SingleRange::new(), 5).unwrap_err();
+    Queue::new(SingleRange::new(), 0).unwrap_err();
+}
+
+#[test]
+fn smoke_test() {
+    let mut queue = Queue::new(SingleRange::new(), 3).unwrap();
+
+    {
+        let (mut producer, mut consumer) = queue.split();
+
+        assert_eq!(None, consumer.pop());
+
+        for i in 0..3 {
+            producer.try_push(i).unwrap();
+        }
+
+        assert!(producer.is_full());
+        assert_eq!(Err(3), producer.try_push(3));
+
+        assert_eq!(Some(&0), consumer.peek());
+        assert_eq!(Some(0), consumer.pop());
+
+        producer.try_push(3).unwrap();
+
+        assert_eq!(Some(1), consumer.pop());
+    }
+
+    assert_eq!(2, queue.len());
+    assert_eq!(3, queue.capacity());
+}
+
+#[test]
+fn wrap_around() {
+    let mut queue = Queue::new(SingleRange::new(), 3).unwrap();
+
+    //  Start right before the indices wrap.
+    *queue.head.get_mut() = 5;
+    *queue.tail.get_mut() = 5;
+
+    let (mut producer, mut consumer) = queue.split();
+
+    for i in 0..20 {
+        producer.try_push(i).unwrap();
+        producer.try_push(i + 1).unwrap();
+        producer.try_push(i + 2).unwrap();
+
+        assert!(producer.is_full());
+
+        assert_eq!(Some(i), consumer.pop());
+        assert_eq!(Some(i + 1), consumer.pop());
+        assert_eq!(Some(i + 2), consumer.pop());
+        assert!(consumer.is_empty());
+    }
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use std::{alloc::Global, thread};
+
+use crate::allocator::SingleRange;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn drop_remaining() {
+    let allocator = SpyAllocator::default();
+
+    let mut queue = RawSpscQueue::new(SingleRange::new(allocator.clone()), 4).unwrap();
+
+    {
+        let (mut producer, _) = queue.split();
+
+        producer.try_push("Hello".to_string()).unwrap();
+        producer.try_push("World".to_string()).unwrap();
+    }
+
+    drop(queue);
+
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn threads() {
+    const COUNT: usize = 10_000;
+
+    let mut queue = RawSpscQueue::new(SingleRange::new(Global), 16).unwrap();
+    let (mut producer, mut consumer) = queue.split();
+
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for i in 0..COUNT {
+                let mut value = i;
+
+                while let Err(v) = producer.try_push(value) {
+                    value = v;
+                    thread::yield_now();
+                }
+            }
+        });
+
+        scope.spawn(move || {
+            for i in 0..COUNT {
+                loop {
+                    if let Some(value) = consumer.pop() {
+                        assert_eq!(i, value);
+                        break;
+                    }
+
+                    thread::yield_now();
+                }
+            }
+        });
+    });
+
+    assert!(queue.is_empty());
+}
+
+} // mod test_allocator
@@ -0,0 +1,330 @@
+//! Proof-of-Concept implementation of a Vec whose elements never move, parameterized by a Storage.
+//!
+//! The elements are stored in segments, each a range of a MultiRangeStorage, of progressively larger capacities: the
+//! first segment holds 4 elements, and each further segment holds twice as many as the previous one. Growing thus
+//! allocates a new segment, rather than relocating the existing elements.
+//!
+//! The maximum number of segments, `N`, is a const generic parameter, bounding the capacity to `4 * (2^N - 1)`.
+
+use core::{
+    cell::{Cell, UnsafeCell},
+    convert::TryFrom,
+    fmt::{self, Debug},
+    mem::MaybeUninit,
+    ops::{Index, IndexMut},
+    ptr::{self, NonNull},
+};
+
+use crate::{global, traits::{Capacity, MultiRangeStorage, PinningStorage}};
+
+/// A PoC StableVec.
+///
+/// Pushing only requires a shared reference, and returns a reference to the pushed element, which remains valid across
+/// further pushes; this requires the storage not to relocate its ranges, hence to be a `PinningStorage`.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawStableVec<T, S: MultiRangeStorage = global::MultiRange, const N: usize = 32> {
+    len: Cell<usize>,
+    segments: UnsafeCell<[Option<S::Handle<T>>; N]>,
+    storage: UnsafeCell<S>,
+}
+
+impl<T, S: MultiRangeStorage, const N: usize> RawStableVec<T, S, N> {
+    /// Creates a new instance from `storage`.
+    pub fn new(storage: S) -> Self {
+        Self { len: Cell::new(0), segments: UnsafeCell::new([None; N]), storage: UnsafeCell::new(storage) }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the number of elements in `self`.
+    pub fn len(&self) -> usize { self.len.get() }
+
+    /// Returns a reference to the element at `index`, if any.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        //  Safety:
+        //  -   `index` is within bounds, hence its element is initialized.
+        Some(unsafe { &*self.element(index).as_ptr() })
+    }
+
+    /// Returns a mutable reference to the element at `index`, if any.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        //  Safety:
+        //  -   `index` is within bounds, hence its element is initialized, and `self` is borrowed mutably.
+        Some(unsafe { &mut *self.element(index).as_ptr() })
+    }
+
+    /// Pops the last element, if any.
+    ///
+    /// The segments are retained, for further pushes.
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.len().checked_sub(1)?;
+
+        self.len.set(len);
+
+        //  Safety:
+        //  -   `len` was within bounds, hence its element is initialized, and it is no longer considered so.
+        Some(unsafe { ptr::read(self.element(len).as_ptr()) })
+    }
+
+    /// Clears `self`, destroying all elements.
+    ///
+    /// The segments are retained, for further pushes.
+    pub fn clear(&mut self) {
+        while let Some(element) = self.pop() {
+            drop(element);
+        }
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> RawStableVecIter<'_, T, S, N> { RawStableVecIter { vec: self, index: 0 } }
+}
+
+impl<T, S: MultiRangeStorage + PinningStorage, const N: usize> RawStableVec<T, S, N> {
+    /// Attempts to push `value` at the back, returning a reference to it.
+    ///
+    /// Fails if a new segment is required, and cannot be allocated, or if all `N` segments are in use.
+    pub fn try_push(&self, value: T) -> Result<&T, T> {
+        let index = self.len();
+        let (segment, _) = locate(index);
+
+        if segment >= N {
+            return Err(value);
+        }
+
+        //  Safety:
+        //  -   `self` is not `Sync`, and no reference to the segments outlives any method.
+        let segments = unsafe { &mut *self.segments.get() };
+
+        if segments[segment].is_none() {
+            let capacity = match segment_capacity(segment).and_then(S::Capacity::from_usize) {
+                Some(capacity) => capacity,
+                None => return Err(value),
+            };
+
+            //  Safety:
+            //  -   `self` is not `Sync`, and no reference to the storage outlives any method; the references to the
+            //      elements handed out remain valid, as `S` is a `PinningStorage`.
+            let storage = unsafe { &mut *self.storage.get() };
+
+            match storage.allocate::<T>(capacity) {
+                Ok(handle) => segments[segment] = Some(handle),
+                Err(_) => return Err(value),
+            }
+        }
+
+        let element = self.element(index);
+
+        //  Safety:
+        //  -   `element` points to the uninitialized slot of `index`, within an allocated segment.
+        unsafe { ptr::write(element.as_ptr(), value) };
+
+        self.len.set(index + 1);
+
+        //  Safety:
+        //  -   `element` was just initialized, and will not move until `self` is borrowed mutably.
+        Ok(unsafe { &*element.as_ptr() })
+    }
+
+    /// Pushes `value` at the back, returning a reference to it.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn push(&self, value: T) -> &T {
+        self.try_push(value)
+            .map_err(|_| ())
+            .expect("Sufficient capacity")
+    }
+}
+
+impl<T: Debug, S: MultiRangeStorage, const N: usize> Debug for RawStableVec<T, S, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S: Default + MultiRangeStorage, const N: usize> Default for RawStableVec<T, S, N> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<T, S: MultiRangeStorage, const N: usize> Index<usize> for RawStableVec<T, S, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T { self.get(index).expect("Index within bounds") }
+}
+
+impl<T, S: MultiRangeStorage, const N: usize> IndexMut<usize> for RawStableVec<T, S, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T { self.get_mut(index).expect("Index within bounds") }
+}
+
+impl<T, S: MultiRangeStorage, const N: usize> Drop for RawStableVec<T, S, N> {
+    fn drop(&mut self) {
+        self.clear();
+
+        let storage = self.storage.get_mut();
+
+        for handle in self.segments.get_mut().iter_mut().filter_map(Option::take) {
+            //  Safety:
+            //  -   `handle` is valid, as it is owned by `self`, and its elements were destroyed.
+            unsafe { storage.deallocate(handle) };
+        }
+    }
+}
+
+/// Iterator over the elements of a RawStableVec.
+pub struct RawStableVecIter<'a, T, S: MultiRangeStorage, const N: usize> {
+    vec: &'a RawStableVec<T, S, N>,
+    index: usize,
+}
+
+impl<'a, T, S: MultiRangeStorage, const N: usize> Iterator for RawStableVecIter<'a, T, S, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let result = self.vec.get(self.index)?;
+
+        self.index += 1;
+
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vec.len() - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+
+//
+//  Implementation
+//
+
+//  The capacity of the first segment is `1 << FIRST_SEGMENT_SHIFT`.
+const FIRST_SEGMENT_SHIFT: u32 = 2;
+
+//  Returns the capacity of `segment`, if representable.
+fn segment_capacity(segment: usize) -> Option<usize> {
+    let shift = u32::try_from(segment).ok()?.checked_add(FIRST_SEGMENT_SHIFT)?;
+
+    1usize.checked_shl(shift)
+}
+
+//  Returns the segment of `index`, and its offset within the segment.
+fn locate(index: usize) -> (usize, usize) {
+    let shifted = index + (1 << FIRST_SEGMENT_SHIFT);
+    let log = usize::BITS - 1 - shifted.leading_zeros();
+
+    ((log - FIRST_SEGMENT_SHIFT) as usize, shifted - (1 << log))
+}
+
+impl<T, S: MultiRangeStorage, const N: usize> RawStableVec<T, S, N> {
+    //  Returns a pointer to the slot of `index`.
+    //
+    //  The segment of `index` is assumed to be allocated.
+    fn element(&self, index: usize) -> NonNull<T> {
+        let (segment, offset) = locate(index);
+
+        //  Safety:
+        //  -   `self` is not `Sync`, and no mutable reference to the segments outlives any method.
+        let handle = unsafe { (*self.segments.get())[segment] }.expect("Allocated segment");
+
+        //  Safety:
+        //  -   `handle` is valid, as it is owned by `self`.
+        let range: NonNull<[MaybeUninit<T>]> = unsafe { (*self.storage.get()).resolve(handle) };
+
+        debug_assert!(offset < range.len());
+
+        //  Safety:
+        //  -   `offset` is within the capacity of the segment.
+        unsafe { NonNull::new_unchecked(range.as_mut_ptr().add(offset) as *mut T) }
+    }
+}
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::adapter::Typed;
+use crate::allocator::MultiElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+type StableVec = RawStableVec<String, Typed<MultiElement<SpyAllocator>>>;
+
+#[test]
+fn locate_segments() {
+    assert_eq!((0, 0), locate(0));
+    assert_eq!((0, 3), locate(3));
+    assert_eq!((1, 0), locate(4));
+    assert_eq!((1, 7), locate(11));
+    assert_eq!((2, 0), locate(12));
+}
+
+#[test]
+fn stable_references() {
+    let allocator = SpyAllocator::default();
+    let vec = StableVec::new(Typed::new(MultiElement::new(allocator.clone())));
+
+    let first = vec.push("Hello".to_string());
+
+    for i in 1..20 {
+        vec.push(i.to_string());
+    }
+
+    //  The first element did not move, despite the pushes.
+    assert_eq!("Hello", first);
+    assert!(ptr::eq(first, &vec[0]));
+
+    assert_eq!(20, vec.len());
+    assert_eq!(Some(&"19".to_string()), vec.get(19));
+    assert_eq!(None, vec.get(20));
+
+    //  Segments of 4, 8, and 16 elements.
+    assert_eq!(3, allocator.allocated());
+}
+
+#[test]
+fn pop_clear_drop() {
+    let allocator = SpyAllocator::default();
+    let mut vec = StableVec::new(Typed::new(MultiElement::new(allocator.clone())));
+
+    for i in 0..5 {
+        vec.push(i.to_string());
+    }
+
+    vec[0] = "Zero".to_string();
+
+    assert_eq!(Some("4".to_string()), vec.pop());
+    assert_eq!(r#"["Zero", "1", "2", "3"]"#, format!("{:?}", vec));
+
+    vec.clear();
+
+    assert!(vec.is_empty());
+    assert_eq!(0, vec.iter().count());
+    assert_eq!(0, allocator.deallocated());
+
+    drop(vec);
+
+    assert_eq!(2, allocator.deallocated());
+}
+
+#[test]
+fn try_push_failure() {
+    let vec = RawStableVec::<u8, Typed<MultiElement<NonAllocator>>>::default();
+
+    assert_eq!(Err(1), vec.try_push(1));
+}
+
+} // mod test_allocator
@@ -0,0 +1,260 @@
+//! Proof-of-Concept implementation of a String parameterized by a Storage.
+
+use core::{
+    fmt::{self, Debug, Display, Write},
+    ops::{Deref, DerefMut},
+    str::{self, Utf8Error},
+};
+
+use crate::{error::StorageError, global, traits::SingleRangeStorage};
+
+use super::{RawVec, ToOwnedIn};
+
+/// A PoC String.
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+pub struct RawString<S: SingleRangeStorage = global::SingleRange> {
+    bytes: RawVec<u8, S>,
+}
+
+impl<S: SingleRangeStorage> RawString<S> {
+    /// Creates a new, empty, instance.
+    pub fn new(storage: S) -> Self { Self { bytes: RawVec::new(storage) } }
+
+    /// Converts a vector of bytes to a string, validating that the bytes are UTF-8.
+    ///
+    /// The storage, and its allocation, is reused as is.
+    pub fn from_utf8(bytes: RawVec<u8, S>) -> Result<Self, FromUtf8Error<S>> {
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(Self { bytes }),
+            Err(error) => Err(FromUtf8Error { bytes, error }),
+        }
+    }
+
+    /// Converts a vector of bytes to a string, without validating that the bytes are UTF-8.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `bytes` are valid UTF-8.
+    pub unsafe fn from_utf8_unchecked(bytes: RawVec<u8, S>) -> Self { Self { bytes } }
+
+    /// Converts the string into a vector of bytes.
+    ///
+    /// The storage, and its allocation, is reused as is.
+    pub fn into_bytes(self) -> RawVec<u8, S> { self.bytes }
+
+    /// Returns the bytes of the string.
+    pub fn as_bytes(&self) -> &[u8] { &self.bytes }
+
+    /// Returns the string slice.
+    pub fn as_str(&self) -> &str {
+        //  Safety:
+        //  -   Invariant: `self.bytes` are valid UTF-8.
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Returns the mutable string slice.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        //  Safety:
+        //  -   Invariant: `self.bytes` are valid UTF-8.
+        unsafe { str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Returns whether `self` is empty, or not.
+    pub fn is_empty(&self) -> bool { self.bytes.is_empty() }
+
+    /// Returns the length of `self`, in bytes.
+    pub fn len(&self) -> usize { self.bytes.len() }
+
+    /// Returns the capacity of `self`, in bytes.
+    pub fn capacity(&self) -> usize { self.bytes.capacity() }
+
+    /// Attempts to append `c` at the back.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_push(&mut self, c: char) -> Result<(), StorageError> { self.try_push_str(c.encode_utf8(&mut [0; 4])) }
+
+    /// Appends `c` at the back.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn push(&mut self, c: char) { self.try_push(c).expect("Sufficient capacity") }
+
+    /// Attempts to append `string` at the back, growing at most once.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), StorageError> {
+        self.bytes.try_extend_from_slice_copied(string.as_bytes())
+    }
+
+    /// Appends `string` at the back, growing at most once.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn push_str(&mut self, string: &str) { self.try_push_str(string).expect("Sufficient capacity") }
+}
+
+impl<S: SingleRangeStorage> Deref for RawString<S> {
+    type Target = str;
+
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl<S: SingleRangeStorage> DerefMut for RawString<S> {
+    fn deref_mut(&mut self) -> &mut str { self.as_mut_str() }
+}
+
+impl<S: SingleRangeStorage> Display for RawString<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { f.write_str(self.as_str()) }
+}
+
+impl<S: SingleRangeStorage> Write for RawString<S> {
+    fn write_str(&mut self, string: &str) -> Result<(), fmt::Error> { self.try_push_str(string).map_err(|_| fmt::Error) }
+}
+
+impl<S: SingleRangeStorage> Debug for RawString<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl<S: Default + SingleRangeStorage> Default for RawString<S> {
+    fn default() -> Self { Self::new(S::default()) }
+}
+
+impl<S: SingleRangeStorage> ToOwnedIn<S> for str {
+    type Owned = RawString<S>;
+
+    fn try_to_owned_in(&self, storage: S) -> Result<Self::Owned, (S, StorageError)> {
+        let bytes = self.as_bytes().try_to_owned_in(storage)?;
+
+        //  Safety:
+        //  -   `bytes` is a copy of a `str`, hence is valid UTF-8.
+        Ok(unsafe { RawString::from_utf8_unchecked(bytes) })
+    }
+}
+
+/// The error returned when converting bytes which are not UTF-8.
+pub struct FromUtf8Error<S: SingleRangeStorage = global::SingleRange> {
+    bytes: RawVec<u8, S>,
+    error: Utf8Error,
+}
+
+impl<S: SingleRangeStorage> FromUtf8Error<S> {
+    /// Returns the bytes which failed the conversion.
+    pub fn as_bytes(&self) -> &[u8] { &self.bytes }
+
+    /// Returns the bytes which failed the conversion.
+    pub fn into_bytes(self) -> RawVec<u8, S> { self.bytes }
+
+    /// Returns the details of the conversion failure.
+    pub fn utf8_error(&self) -> Utf8Error { self.error }
+}
+
+impl<S: SingleRangeStorage> Debug for FromUtf8Error<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "FromUtf8Error{{ bytes: {:?}, error: {:?} }}", self.as_bytes(), self.error)
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use crate::collections::ToRawVecIn;
+use crate::inline::SingleRange;
+
+use super::*;
+
+type Storage = SingleRange<u8, u8, 8>;
+
+#[test]
+fn from_utf8_success() {
+    let bytes = b"Hello".to_raw_vec_in(Storage::new());
+    let string = RawString::from_utf8(bytes).unwrap();
+
+    assert_eq!("Hello", string.as_str());
+    assert_eq!(5, string.len());
+    assert_eq!("\"Hello\"", format!("{:?}", string));
+}
+
+#[test]
+fn from_utf8_failure() {
+    let bytes = [b'a', 0xFF].to_raw_vec_in(Storage::new());
+    let error = RawString::from_utf8(bytes).unwrap_err();
+
+    assert_eq!(1, error.utf8_error().valid_up_to());
+    assert_eq!([b'a', 0xFF], &*error.into_bytes());
+}
+
+#[test]
+fn push() {
+    let mut string = RawString::new(Storage::new());
+
+    string.push_str("Hell");
+    string.push('ø');
+
+    assert_eq!("Hellø", &*string);
+    assert_eq!(6, string.len());
+    assert_eq!("Hellø", string.to_string());
+
+    string.try_push_str("!!!").unwrap_err();
+    string.try_push('€').unwrap_err();
+
+    assert_eq!("Hellø", &*string);
+
+    string.make_ascii_uppercase();
+
+    assert_eq!("HELLø", string.as_str());
+}
+
+#[test]
+fn write() {
+    let mut string = RawString::new(Storage::new());
+
+    write!(string, "{}-{}", 1, 2).unwrap();
+    assert_eq!("1-2", &*string);
+
+    write!(string, "{}", 123456).unwrap_err();
+}
+
+#[test]
+fn into_bytes() {
+    let bytes = b"Hello".to_raw_vec_in(Storage::new());
+    let bytes = RawString::from_utf8(bytes).unwrap().into_bytes();
+
+    assert_eq!(b"Hello", &*bytes);
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use crate::allocator::SingleRange;
+use crate::collections::ToRawVecIn;
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn round_trip_no_copy() {
+    let allocator = SpyAllocator::default();
+
+    let bytes = "Hello, World!".as_bytes().to_raw_vec_in(SingleRange::new(allocator.clone()));
+    let pointer = bytes.as_ptr();
+
+    let string = RawString::from_utf8(bytes).unwrap();
+
+    assert_eq!(pointer, string.as_bytes().as_ptr());
+
+    let bytes = string.into_bytes();
+
+    assert_eq!(pointer, bytes.as_ptr());
+    assert_eq!(1, allocator.allocated());
+}
+
+} // mod test_allocator
@@ -1,25 +1,107 @@
 //! Proof-of-Concept implementation of a Vec parameterized by a Storage.
 
-use core::{cmp, fmt::{self, Debug}, mem::MaybeUninit, ops::{Deref, DerefMut}, ptr};
+use core::{
+    cmp::{self, Ordering},
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr,
+};
 
-use crate::traits::{Capacity, SingleRangeStorage};
+#[cfg(feature = "alloc")]
+use alloc::{alloc::Global, vec::Vec};
+
+#[cfg(feature = "alloc")]
+use core::ptr::NonNull;
+
+#[cfg(feature = "serde")]
+use serde::{de::{self, Deserialize, Deserializer, SeqAccess, Visitor}, ser::{Serialize, Serializer}};
+
+#[cfg(feature = "alloc")]
+use crate::allocator;
+
+use crate::{
+    error::StorageError,
+    global,
+    traits::{Capacity, CloneStorage, PinningStorage, SingleRangeStorage, SpillingRangeStorage},
+};
+
+use super::{FromIteratorIn, ToOwnedIn, ToRawVecIn};
 
 /// A PoC Vec.
-pub struct RawVec<T, S: SingleRangeStorage> {
+///
+/// Unless specified, the storage is the process-wide default storage, see `global`.
+///
+/// Like `Vec`, the elements may dangle when the vector is dropped. Unlike `Vec`, it is invariant in `T`, as the handle
+/// of the storage is an associated type of `S`.
+pub struct RawVec<T, S: SingleRangeStorage = global::SingleRange> {
     len: S::Capacity,
     data: S::Handle<T>,
     storage: S,
+    //  Owns `T`s, for the purpose of drop check.
+    _marker: PhantomData<T>,
 }
 
 impl<T, S: SingleRangeStorage> RawVec<T, S> {
     /// Creates a new instance.
+    ///
+    /// No element is allocated, yet with an inline storage the range spans the whole storage from the start, hence
+    /// pushing never grows until it is full.
     pub fn new(mut storage: S) -> Self {
         let zero = Self::into_capacity(0);
 
         let len = zero;
         let data = storage.allocate(zero).expect("Zero-capacity allocation should always succeed");
 
-        Self { len, data, storage, }
+        Self { len, data, storage, _marker: PhantomData }
+    }
+
+    /// Attempts to create a new instance, with room for at least `capacity` elements.
+    pub fn try_with_capacity_in(capacity: usize, mut storage: S) -> Result<Self, StorageError> {
+        let capacity = S::Capacity::from_usize(capacity).ok_or_else(StorageError::capacity_overflow)?;
+
+        let len = Self::into_capacity(0);
+        let data = storage.allocate(capacity)?;
+
+        Ok(Self { len, data, storage, _marker: PhantomData })
+    }
+
+    /// Creates a new instance, with room for at least `capacity` elements.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot allocate.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn with_capacity_in(capacity: usize, storage: S) -> Self {
+        Self::try_with_capacity_in(capacity, storage).expect("Sufficient capacity")
+    }
+
+    /// Attempts to create a new instance, moving the elements of `array` in all at once.
+    pub fn try_from_array_in<const N: usize>(array: [T; N], storage: S) -> Result<Self, StorageError> {
+        let mut result = Self::try_with_capacity_in(N, storage)?;
+
+        let array = ManuallyDrop::new(array);
+
+        //  Safety:
+        //  -   `result` is valid for writes of `N` elements, as per `try_with_capacity_in`.
+        //  -   The elements of `array` are moved, and never dropped from `array`.
+        unsafe { ptr::copy_nonoverlapping(array.as_ptr(), result.raw_slice_mut().as_mut_ptr() as *mut T, N) };
+
+        result.len = Self::into_capacity(N);
+
+        Ok(result)
+    }
+
+    /// Creates a new instance, moving the elements of `array` in all at once.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot allocate.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn from_array_in<const N: usize>(array: [T; N], storage: S) -> Self {
+        Self::try_from_array_in(array, storage).expect("Sufficient capacity")
     }
 
     /// Returns whether `self` is empty, or not.
@@ -28,19 +110,52 @@ impl<T, S: SingleRangeStorage> RawVec<T, S> {
     /// Returns the number of elements in `self`.
     pub fn len(&self) -> usize { self.len.into_usize() }
 
+    /// Returns the number of elements `self` can hold without growing.
+    pub fn capacity(&self) -> usize { self.raw_slice().len() }
+
     /// Clears `self`, destroying all elements and resetting its length to 0.
-    pub fn clear(&mut self) {
-        while let Some(_) = self.pop() {}
+    pub fn clear(&mut self) { self.truncate(0) }
+
+    /// Shortens `self` to `len` elements, destroying the remaining ones.
+    ///
+    /// Has no effect if `len >= self.len()`; the capacity is left unchanged.
+    pub fn truncate(&mut self, len: usize) {
+        let old_len = self.len();
+
+        if len >= old_len {
+            return;
+        }
+
+        //  Shorten first, so that should a destructor panic, the remaining elements are leaked rather than dropped
+        //  twice.
+        self.len = Self::into_capacity(len);
+
+        if !mem::needs_drop::<T>() {
+            return;
+        }
+
+        let tail = &mut self.raw_slice_mut()[len..old_len];
+
+        //  Safety:
+        //  -   The elements in `len..old_len` are initialized, and no longer reachable.
+        unsafe { ptr::drop_in_place(tail as *mut [MaybeUninit<T>] as *mut [T]) };
     }
 
     /// Attempts to push a new element at the back.
     pub fn try_push(&mut self, e: T) -> Result<(), T> {
+        self.push_within_capacity(e).or_else(|e| self.try_push_grow(e))
+    }
+
+    /// Attempts to push a new element at the back, without growing.
+    ///
+    /// Fails if `self` is full. As it never grows, the elements are never relocated by this call.
+    pub fn push_within_capacity(&mut self, e: T) -> Result<(), T> {
         let len = self.len();
 
         let slice = self.raw_slice_mut();
 
         if len >= slice.len() {
-            return self.try_push_grow(e);
+            return Err(e);
         }
 
         //  Safety:
@@ -59,6 +174,7 @@ impl<T, S: SingleRangeStorage> RawVec<T, S> {
     /// #   Panics
     ///
     /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
     pub fn push(&mut self, e: T) {
         self.try_push(e)
             .map_err(|_| ())
@@ -89,208 +205,1356 @@ impl<T, S: SingleRangeStorage> RawVec<T, S> {
 
         Some(result)
     }
-}
 
-impl<T: Debug, S: SingleRangeStorage> Debug for RawVec<T, S> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        let slice: &[T] = &*self;
-        write!(f, "{:?}", slice)
-    }
-}
+    /// Attempts to insert an element at `index`, shifting all elements after it to the right.
+    ///
+    /// #   Panics
+    ///
+    /// If `index > self.len()`.
+    pub fn try_insert(&mut self, index: usize, e: T) -> Result<(), T> {
+        let len = self.len();
 
-impl<T, S: Default + SingleRangeStorage> Default for RawVec<T, S> {
-    fn default() -> Self { RawVec::new(S::default()) }
-}
+        assert!(index <= len, "Index {} out of bounds, length is {}", index, len);
 
-impl<T, S: SingleRangeStorage> Deref for RawVec<T, S> {
-    type Target = [T];
+        if self.try_grow_for_push().is_err() {
+            return Err(e);
+        }
 
-    fn deref(&self) -> &Self::Target {
-        let len = self.len();
-        let slice = self.raw_slice();
+        let slice = self.raw_slice_mut();
 
         //  Safety:
-        //  -   Invariant: `slice.len() >= self.len()`.
-        let slice = unsafe { slice.get_unchecked(0..len) };
+        //  -   `index <= len < slice.len()`, as per `try_grow_for_push`.
+        //  -   Neither the shift nor the write may panic, hence no element may be dropped twice.
+        unsafe {
+            let slot = slice.as_mut_ptr().add(index);
 
-        //  Safety:
-        //  -   Invariant, `self.raw_slice()[0..len]` are initialized.
-        unsafe { MaybeUninit::slice_assume_init_ref(slice) }
+            ptr::copy(slot, slot.add(1), len - index);
+            (*slot).write(e);
+        }
+
+        self.len = Self::into_capacity(len + 1);
+
+        Ok(())
     }
-}
 
-impl<T, S: SingleRangeStorage> DerefMut for RawVec<T, S> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
+    /// Inserts an element at `index`, shifting all elements after it to the right.
+    ///
+    /// #   Panics
+    ///
+    /// If `index > self.len()`, or if cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn insert(&mut self, index: usize, e: T) {
+        self.try_insert(index, e)
+            .map_err(|_| ())
+            .expect("Sufficient capacity");
+    }
+
+    /// Removes the element at `index`, shifting all elements after it to the left.
+    ///
+    /// #   Panics
+    ///
+    /// If `index >= self.len()`.
+    pub fn remove(&mut self, index: usize) -> T {
         let len = self.len();
-        let slice = self.raw_slice_mut();
 
-        //  Safety:
-        //  -   Invariant: `slice.len() >= self.len()`.
-        let slice = unsafe { slice.get_unchecked_mut(0..len) };
+        assert!(index < len, "Index {} out of bounds, length is {}", index, len);
+
+        let slice = self.raw_slice_mut();
 
         //  Safety:
-        //  -   Invariant, `self.raw_slice()[0..len]` are initialized.
-        unsafe { MaybeUninit::slice_assume_init_mut(slice) }
-    }
-}
+        //  -   `index < len <= slice.len()`.
+        //  -   `slot` contains an initialized value of `T`, which is moved out prior to being overwritten.
+        //  -   Neither the read nor the shift may panic, hence no element may be dropped twice.
+        let result = unsafe {
+            let slot = slice.as_mut_ptr().add(index);
 
-impl<T, S: SingleRangeStorage> Drop for RawVec<T, S> {
-    fn drop(&mut self) {
-        self.clear();
+            let result = ptr::read(slot).assume_init();
+            ptr::copy(slot.add(1), slot, len - index - 1);
 
-        //  Safety:
-        //  -   `self.data` is valid.
-        unsafe { self.storage.deallocate(self.data) };
-    }
-}
+            result
+        };
 
-//
-//  Implementation
-//
+        self.len = Self::into_capacity(len - 1);
 
-impl<T, S: SingleRangeStorage> RawVec<T, S> {
-    fn into_capacity(n: usize) -> S::Capacity {
-        S::Capacity::from_usize(n).expect("n <= S::maximum_capacity()")
+        result
     }
 
-    fn raw_slice(&self) -> &[MaybeUninit<T>] {
-        //  Safety:
-        //  -   `self.data` is valid and points to valid data.
-        let range = unsafe { self.storage.resolve(self.data) };
+    /// Removes the element at `index`, replacing it by the back element.
+    ///
+    /// This does not preserve ordering, but is O(1).
+    ///
+    /// #   Panics
+    ///
+    /// If `index >= self.len()`.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len();
 
-        //  Safety:
-        //  -   `range` points to valid data.
-        //  -   The lifetime of the slice is actually that of `self.storage`.
-        unsafe { &*range.as_ptr() }
-    }
+        assert!(index < len, "Index {} out of bounds, length is {}", index, len);
 
-    fn raw_slice_mut(&mut self) -> &mut [MaybeUninit<T>] {
-        //  Safety:
-        //  -   `self.data` is valid and points to valid data.
-        let range = unsafe { self.storage.resolve_mut(self.data) };
+        let slice = self.raw_slice_mut();
 
         //  Safety:
-        //  -   `range` points to valid data.
-        //  -   The lifetime of the slice is actually that of `self.storage`.
-        unsafe { &mut *range.as_ptr() }
-    }
+        //  -   `index < len <= slice.len()`.
+        //  -   `slot` contains an initialized value of `T`, which is moved out prior to being overwritten.
+        //  -   `index` and `len - 1` may be equal, hence the use of `ptr::copy`.
+        let result = unsafe {
+            let base = slice.as_mut_ptr();
 
-    #[inline(never)]
-    fn try_push_grow(&mut self, e: T) -> Result<(), T> {
-        let len = self.len.into_usize();
-        let new_cap = cmp::max(1, len * 2);
+            let result = ptr::read(base.add(index)).assume_init();
+            ptr::copy(base.add(len - 1), base.add(index), 1);
 
-        //  Safety:
-        //  -   `self.data` is a valid handle pointing to valid data.
-        self.data = match unsafe { self.storage.try_grow(self.data, Self::into_capacity(new_cap)) } {
-            Ok(handle) => handle,
-            Err(_) => return Err(e),
+            result
         };
 
-        let slice = self.raw_slice_mut();
+        self.len = Self::into_capacity(len - 1);
 
-        //  Safety:
-        //  -   `len < slice.len()`.
-        let slot = unsafe { slice.get_unchecked_mut(len) };
+        result
+    }
 
-        slot.write(e);
+    /// Retains only the elements for which `fun` returns `true`, in a single pass, preserving their order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut fun: F) { self.retain_mut(|e| fun(e)) }
 
-        self.len = Self::into_capacity(len + 1);
+    /// Retains only the elements for which `fun` returns `true`, in a single pass, preserving their order.
+    ///
+    /// Should `fun`, or the destructor of an element, panic, the elements not yet processed are retained.
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut fun: F) { self.retain_impl(|e, _| fun(e)) }
 
-        Ok(())
+    /// Removes the consecutive elements for which `same_bucket` returns `true`, in a single pass.
+    ///
+    /// `same_bucket` is passed the element under consideration, and the last retained element before it.
+    ///
+    /// Should `same_bucket`, or the destructor of an element, panic, the elements not yet processed are retained.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        self.retain_impl(|e, previous| previous.is_none_or(|previous| !same_bucket(e, previous)))
     }
-}
-
-#[cfg(test)]
-mod test_inline {
 
-use core::mem;
+    /// Removes the consecutive elements which resolve to the same key, in a single pass.
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
 
-use crate::inline::SingleRange;
+    /// Attempts to ensure there is room for at least `additional` more elements.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), StorageError> {
+        let required = self.len().checked_add(additional).ok_or_else(StorageError::capacity_overflow)?;
 
-use super::*;
+        if required <= self.raw_slice().len() {
+            return Ok(());
+        }
 
-#[test]
-fn size() {
-    type Storage = SingleRange<u8, u8, 31>;
-    type Vec = RawVec<u8, Storage>;
+        let amortized = cmp::min(self.len().saturating_mul(2), self.addressable_capacity());
 
-    assert_eq!(32, mem::size_of::<Vec>());
-}
+        self.try_grow_to(cmp::max(required, amortized))
+            .or_else(|_| self.try_grow_to(required))
+    }
 
-#[test]
-fn smoke_test() {
-    type Storage = SingleRange<u8, u8, 31>;
-    type Vec = RawVec<u8, Storage>;
+    /// Ensures there is room for at least `additional` more elements.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).expect("Sufficient capacity");
+    }
 
-    let mut vec = Vec::default();
+    /// Attempts to ensure there is room for at least `additional` more elements, without over-allocating.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), StorageError> {
+        let required = self.len().checked_add(additional).ok_or_else(StorageError::capacity_overflow)?;
 
-    for i in 0..31 {
-        vec.push(i);
+        self.try_grow_to(required)
     }
 
-    assert_eq!(Some(&2), vec.get(2));
+    /// Ensures there is room for at least `additional` more elements, without over-allocating.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.try_reserve_exact(additional).expect("Sufficient capacity");
+    }
 
-    assert_eq!(
-        "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30]",
-        format!("{:?}", vec)
-    );
-}
+    /// Attempts to resize `self` to `new_len`, truncating or appending the results of `fun` as necessary.
+    ///
+    /// On failure, `self` is left unmodified. Should `fun` panic, the elements appended so far are kept.
+    pub fn try_resize_with<F: FnMut() -> T>(&mut self, new_len: usize, mut fun: F) -> Result<(), StorageError> {
+        let len = self.len();
 
-#[test]
-fn try_push_failure() {
-    type Storage = SingleRange<u8, u8, 1>;
-    type Vec = RawVec<u8, Storage>;
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
 
-    let mut vec = Vec::default();
-    vec.push(0);
+        self.try_reserve(new_len - len)?;
 
-    assert_eq!(Err(42), vec.try_push(42));
-}
+        for _ in len..new_len {
+            //  Safety:
+            //  -   There is room for `new_len - len` more elements, as per `try_reserve`.
+            unsafe { self.push_unchecked(fun()) };
+        }
 
-} // mod test_inline
+        Ok(())
+    }
 
-#[cfg(test)]
-mod test_allocator {
+    /// Resizes `self` to `new_len`, truncating or appending the results of `fun` as necessary.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, fun: F) {
+        self.try_resize_with(new_len, fun).expect("Sufficient capacity");
+    }
 
-use core::mem;
+    /// Returns the initialized elements, and the spare capacity past them.
+    pub fn split_at_spare(&self) -> (&[T], &[MaybeUninit<T>]) {
+        let len = self.len();
+        let (initialized, spare) = self.raw_slice().split_at(len);
 
-use crate::allocator::SingleRange;
-use crate::utils::{NonAllocator, SpyAllocator};
+        //  Safety:
+        //  -   Invariant, `self.raw_slice()[0..len]` are initialized.
+        (unsafe { MaybeUninit::slice_assume_init_ref(initialized) }, spare)
+    }
 
-use super::*;
+    /// Returns the initialized elements, and the spare capacity past them.
+    ///
+    /// Once the spare capacity has been written to, for example by an I/O operation, `assume_init_grow` is used to
+    /// commit it.
+    pub fn split_at_spare_mut(&mut self) -> (&mut [T], &mut [MaybeUninit<T>]) {
+        let len = self.len();
+        let (initialized, spare) = self.raw_slice_mut().split_at_mut(len);
 
-#[test]
-fn size() {
-    type Storage = SingleRange<NonAllocator>;
-    type Vec = RawVec<u8, Storage>;
+        //  Safety:
+        //  -   Invariant, `self.raw_slice()[0..len]` are initialized.
+        (unsafe { MaybeUninit::slice_assume_init_mut(initialized) }, spare)
+    }
 
-    assert_eq!(mem::size_of::<usize>() * 3, mem::size_of::<Vec>());
-}
+    /// Returns the spare capacity past the initialized elements.
+    ///
+    /// Once filled, the elements may be committed with `set_len`, or `assume_init_grow`.
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] { self.split_at_spare_mut().1 }
 
-#[test]
-fn smoke_test() {
-    type Storage = SingleRange<SpyAllocator>;
-    type Vec = RawVec<u8, Storage>;
+    /// Sets the length of `self` to `new_len`.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes `new_len <= self.capacity()`.
+    /// -   Assumes the first `new_len` elements are initialized.
+    /// -   Any element past `new_len` is forgotten, rather than dropped.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
 
-    let allocator = SpyAllocator::default();
+        self.len = Self::into_capacity(new_len);
+    }
 
-    let storage = SingleRange::new(allocator.clone());
-    let mut vec = Vec::new(storage);
+    /// Returns mutable references to the elements at `indices`, all at once.
+    ///
+    /// Returns None if any index is out of bounds, or if any 2 indices are equal.
+    pub fn get_many_mut<const N: usize>(&mut self, indices: [usize; N]) -> Option<[&mut T; N]> {
+        let len = self.len();
 
-    assert_eq!(0, allocator.allocated());
-    assert_eq!(0, allocator.deallocated());
+        for (i, index) in indices.iter().enumerate() {
+            if *index >= len || indices[..i].contains(index) {
+                return None;
+            }
+        }
 
-    for i in 0..31 {
-        vec.push(i);
+        let base = self.as_mut_ptr();
 
-        assert_eq!(allocator.allocated() - 1, allocator.deallocated());
+        //  Safety:
+        //  -   All indices are in bounds, hence point to initialized elements.
+        //  -   All indices are distinct, hence the references do not alias.
+        Some(indices.map(|index| unsafe { &mut *base.add(index) }))
     }
 
-    assert_eq!(Some(&2), vec.get(2));
+    /// Attempts to convert `self` into an array, moving the elements out and releasing the range.
+    ///
+    /// Fails, returning `self`, unless `self.len() == N`.
+    pub fn try_into_array<const N: usize>(mut self) -> Result<[T; N], Self> {
+        if self.len() != N {
+            return Err(self);
+        }
 
-    assert_eq!(
-        "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30]",
+        let mut array = MaybeUninit::<[T; N]>::uninit();
+
+        //  Safety:
+        //  -   The first `N` elements of the range are initialized, as `self.len() == N`.
+        //  -   `array` is valid for writes of `N` elements.
+        unsafe { ptr::copy_nonoverlapping(self.raw_slice().as_ptr() as *const T, array.as_mut_ptr() as *mut T, N) };
+
+        //  The elements have been moved out, and must not be dropped along with `self`.
+        self.len = Self::into_capacity(0);
+
+        //  Safety:
+        //  -   All `N` elements of `array` were initialized above.
+        Ok(unsafe { array.assume_init() })
+    }
+
+    /// Attempts to move the elements of `self` into `new_storage`, releasing the current range.
+    ///
+    /// Fails, returning `self` unmodified, if `new_storage` cannot hold `self.len()` elements.
+    pub fn try_in<NS: SingleRangeStorage>(mut self, new_storage: NS) -> Result<RawVec<T, NS>, Self> {
+        let len = self.len();
+
+        let mut result = match RawVec::try_with_capacity_in(len, new_storage) {
+            Ok(result) => result,
+            Err(_) => return Err(self),
+        };
+
+        //  Safety:
+        //  -   The first `len` elements of the range are initialized.
+        //  -   `result` is valid for writes of `len` elements, as per `try_with_capacity_in`.
+        unsafe {
+            let (source, destination) = (self.raw_slice().as_ptr() as *const T, result.raw_slice_mut().as_mut_ptr());
+            ptr::copy_nonoverlapping(source, destination as *mut T, len);
+        }
+
+        //  The elements have been moved out, and must not be dropped along with `self`.
+        self.len = Self::into_capacity(0);
+        result.len = RawVec::<T, NS>::into_capacity(len);
+
+        Ok(result)
+    }
+
+    /// Leaks `self`, returning a mutable reference to its elements, valid for as long as the storage could be.
+    ///
+    /// Neither the elements nor the storage are ever dropped; the storage is pinning, hence its blocks remain valid.
+    pub fn leak<'a>(self) -> &'a mut [T]
+        where
+            S: PinningStorage + 'a,
+    {
+        let mut this = ManuallyDrop::new(self);
+
+        let len = this.len();
+        let pointer = this.raw_slice_mut().as_mut_ptr() as *mut T;
+
+        //  Safety:
+        //  -   The first `len` elements of the range are initialized.
+        //  -   The range is never deallocated, as the storage is never dropped, and does not move as it is pinning.
+        unsafe { &mut *ptr::slice_from_raw_parts_mut(pointer, len) }
+    }
+
+    /// Commits the first `n` elements of the spare capacity, increasing the length of `self` by `n`.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes `n <= self.capacity() - self.len()`.
+    /// -   Assumes the first `n` elements of the spare capacity, see `split_at_spare_mut`, are initialized.
+    pub unsafe fn assume_init_grow(&mut self, n: usize) {
+        self.set_len(self.len() + n);
+    }
+}
+
+impl<T: Clone, S: SingleRangeStorage> RawVec<T, S> {
+    /// Attempts to clone the elements of `slice` at the back, growing at most once.
+    ///
+    /// On failure, `self` is left unmodified. Should a clone panic, the elements cloned so far are kept.
+    pub fn try_extend_from_slice(&mut self, slice: &[T]) -> Result<(), StorageError> {
+        self.try_reserve(slice.len())?;
+
+        for e in slice {
+            //  Safety:
+            //  -   There is room for `slice.len()` more elements, as per `try_reserve`.
+            unsafe { self.push_unchecked(e.clone()) };
+        }
+
+        Ok(())
+    }
+
+    /// Clones the elements of `slice` at the back, growing at most once.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn extend_from_slice(&mut self, slice: &[T]) {
+        self.try_extend_from_slice(slice).expect("Sufficient capacity");
+    }
+
+    /// Attempts to resize `self` to `new_len`, truncating or appending clones of `value` as necessary.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), StorageError> {
+        self.try_resize_with(new_len, || value.clone())
+    }
+
+    /// Resizes `self` to `new_len`, truncating or appending clones of `value` as necessary.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        self.try_resize(new_len, value).expect("Sufficient capacity");
+    }
+}
+
+impl<T: PartialEq, S: SingleRangeStorage> RawVec<T, S> {
+    /// Removes the consecutive equal elements, in a single pass.
+    pub fn dedup(&mut self) { self.dedup_by(|a, b| a == b) }
+}
+
+impl<T: Copy, S: SingleRangeStorage> RawVec<T, S> {
+    /// Attempts to copy the elements of `slice` at the back, all at once.
+    ///
+    /// This is the fast path of `try_extend_from_slice`, performing a single `memcpy`.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_extend_from_slice_copied(&mut self, slice: &[T]) -> Result<(), StorageError> {
+        self.try_reserve(slice.len())?;
+
+        let (_, spare) = self.split_at_spare_mut();
+
+        //  Safety:
+        //  -   `spare` is valid for writes of `slice.len()` elements, as per `try_reserve`.
+        //  -   `spare` and `slice` do not overlap, as `self` is borrowed mutably.
+        unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), spare.as_mut_ptr() as *mut T, slice.len()) };
+
+        let len = self.len() + slice.len();
+        self.len = Self::into_capacity(len);
+
+        Ok(())
+    }
+
+    /// Copies the elements of `slice` at the back, all at once.
+    ///
+    /// This is the fast path of `extend_from_slice`, performing a single `memcpy`.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn extend_from_slice_copied(&mut self, slice: &[T]) {
+        self.try_extend_from_slice_copied(slice).expect("Sufficient capacity");
+    }
+
+    /// Sets all the elements to `value`, including those of the spare capacity, so that the length of `self` becomes
+    /// its capacity.
+    ///
+    /// Unlike `<[T]>::fill`, reachable through `DerefMut`, this also initializes the spare capacity.
+    pub fn fill_capacity(&mut self, value: T) {
+        let (initialized, spare) = self.split_at_spare_mut();
+
+        initialized.fill(value);
+
+        for slot in spare.iter_mut() {
+            slot.write(value);
+        }
+
+        self.len = Self::into_capacity(self.capacity());
+    }
+}
+
+impl<S: SingleRangeStorage> RawVec<u8, S> {
+    /// Attempts to resize `self` to `new_len`, truncating or appending zeroes as necessary.
+    ///
+    /// On failure, `self` is left unmodified.
+    pub fn try_resize_zeroed(&mut self, new_len: usize) -> Result<(), StorageError> {
+        let len = self.len();
+
+        if new_len > len {
+            self.try_reserve(new_len - len)?;
+
+            let (_, spare) = self.split_at_spare_mut();
+
+            //  Safety:
+            //  -   `spare` is valid for writes of `new_len - len` bytes, as per `try_reserve`.
+            unsafe { ptr::write_bytes(spare.as_mut_ptr(), 0, new_len - len) };
+        }
+
+        self.len = Self::into_capacity(new_len);
+
+        Ok(())
+    }
+
+    /// Resizes `self` to `new_len`, truncating or appending zeroes as necessary.
+    ///
+    /// #   Panics
+    ///
+    /// If cannot grow.
+    #[cfg(any(not(feature = "no-panic"), test))]
+    pub fn resize_zeroed(&mut self, new_len: usize) {
+        self.try_resize_zeroed(new_len).expect("Sufficient capacity");
+    }
+}
+
+impl<T, S: SingleRangeStorage + SpillingRangeStorage> RawVec<T, S> {
+    /// Returns whether the elements were spilled into the second storage, typically the allocator, rather than
+    /// living in the first, typically inline.
+    pub fn spilled(&self) -> bool {
+        //  Safety:
+        //  -   `self.data` is valid, and was issued by `self.storage`.
+        unsafe { self.storage.is_spilled(self.data) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> RawVec<T, allocator::SingleRange<Global>> {
+    /// Converts `self` into a `Vec`, reusing its memory block.
+    pub fn into_vec(self) -> Vec<T> {
+        let this = ManuallyDrop::new(self);
+
+        let (pointer, len, capacity) = (this.data.as_mut_ptr() as *mut T, this.len(), this.data.len());
+
+        //  Safety:
+        //  -   `pointer` was allocated by `Global`, for `capacity` elements, of which the first `len` are initialized.
+        //  -   `this` is never dropped, hence ownership of the block and its elements is transferred.
+        unsafe { Vec::from_raw_parts(pointer, len, capacity) }
+    }
+}
+
+impl<T: Clone, S: CloneStorage + SingleRangeStorage> RawVec<T, S> {
+    /// Attempts to clone `self`, within a clone of its storage.
+    pub fn try_clone(&self) -> Result<Self, StorageError> { self.try_to_raw_vec_in(self.storage.clone_storage()) }
+}
+
+#[cfg(any(not(feature = "no-panic"), test))]
+impl<T: Clone, S: CloneStorage + SingleRangeStorage> Clone for RawVec<T, S> {
+    fn clone(&self) -> Self { self.try_clone().expect("Sufficient capacity") }
+}
+
+impl<T: Debug, S: SingleRangeStorage> Debug for RawVec<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let slice: &[T] = &*self;
+        write!(f, "{:?}", slice)
+    }
+}
+
+impl<T: PartialEq<U>, U, S: SingleRangeStorage, OS: SingleRangeStorage> PartialEq<RawVec<U, OS>> for RawVec<T, S> {
+    fn eq(&self, other: &RawVec<U, OS>) -> bool { **self == **other }
+}
+
+impl<T: Eq, S: SingleRangeStorage> Eq for RawVec<T, S> {}
+
+impl<T: PartialOrd, S: SingleRangeStorage, OS: SingleRangeStorage> PartialOrd<RawVec<T, OS>> for RawVec<T, S> {
+    fn partial_cmp(&self, other: &RawVec<T, OS>) -> Option<Ordering> { (**self).partial_cmp(&**other) }
+}
+
+impl<T: Ord, S: SingleRangeStorage> Ord for RawVec<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering { (**self).cmp(&**other) }
+}
+
+impl<T: Hash, S: SingleRangeStorage> Hash for RawVec<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) { (**self).hash(state) }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize, S: SingleRangeStorage> Serialize for RawVec<T, S> {
+    fn serialize<SE: Serializer>(&self, serializer: SE) -> Result<SE::Ok, SE::Error> { serializer.collect_seq(&**self) }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>, S: Default + SingleRangeStorage> Deserialize<'de> for RawVec<T, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(RawVecVisitor(PhantomData))
+    }
+}
+
+impl<T, S: Default + SingleRangeStorage> Default for RawVec<T, S> {
+    fn default() -> Self { RawVec::new(S::default()) }
+}
+
+impl<T, S: SingleRangeStorage> Deref for RawVec<T, S> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        let len = self.len();
+        let slice = self.raw_slice();
+
+        //  Safety:
+        //  -   Invariant: `slice.len() >= self.len()`.
+        let slice = unsafe { slice.get_unchecked(0..len) };
+
+        //  Safety:
+        //  -   Invariant, `self.raw_slice()[0..len]` are initialized.
+        unsafe { MaybeUninit::slice_assume_init_ref(slice) }
+    }
+}
+
+impl<T, S: SingleRangeStorage> DerefMut for RawVec<T, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let len = self.len();
+        let slice = self.raw_slice_mut();
+
+        //  Safety:
+        //  -   Invariant: `slice.len() >= self.len()`.
+        let slice = unsafe { slice.get_unchecked_mut(0..len) };
+
+        //  Safety:
+        //  -   Invariant, `self.raw_slice()[0..len]` are initialized.
+        unsafe { MaybeUninit::slice_assume_init_mut(slice) }
+    }
+}
+
+impl<T, S: SingleRangeStorage> FromIteratorIn<T> for RawVec<T, S> {
+    type Storage = S;
+
+    #[cfg(any(not(feature = "no-panic"), test))]
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, storage: S) -> Self {
+        let mut result = Self::new(storage);
+        result.extend(iter);
+
+        result
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, storage: S) -> Result<Self, StorageError> {
+        let iter = iter.into_iter();
+
+        let mut result = Self::new(storage);
+
+        //  Failure to reserve is not fatal, as the lower bound may be overestimated.
+        let _ = result.try_grow_to(iter.size_hint().0);
+
+        for e in iter {
+            result.try_grow_for_push()?;
+
+            //  Safety:
+            //  -   `result` has room for one more element, as per `try_grow_for_push`.
+            unsafe { result.push_unchecked(e) };
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(any(not(feature = "no-panic"), test))]
+impl<T, S: SingleRangeStorage> Extend<T> for RawVec<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+
+        //  Failure to reserve is not fatal, as the lower bound may be overestimated.
+        let _ = self.try_grow_to(self.len().saturating_add(iter.size_hint().0));
+
+        for e in iter {
+            self.push(e);
+        }
+    }
+}
+
+#[cfg(any(not(feature = "no-panic"), test))]
+impl<T, S: Default + SingleRangeStorage, const N: usize> From<[T; N]> for RawVec<T, S> {
+    fn from(array: [T; N]) -> Self { Self::from_array_in(array, S::default()) }
+}
+
+#[cfg(any(not(feature = "no-panic"), test))]
+impl<T, S: Default + SingleRangeStorage> core::iter::FromIterator<T> for RawVec<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { Self::from_iter_in(iter, S::default()) }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<Vec<T>> for RawVec<T, allocator::SingleRange<Global>> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+
+        let (pointer, len, capacity) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+
+        //  Safety:
+        //  -   The pointer of a `Vec` is never null, even when dangling.
+        let pointer = unsafe { NonNull::new_unchecked(pointer as *mut MaybeUninit<T>) };
+
+        //  The handle of `allocator::SingleRange` is the range itself, matching the block allocated by `vec`; a
+        //  dangling range for an empty `Vec`, or for zero-sized elements, is never deallocated either.
+        let data = NonNull::slice_from_raw_parts(pointer, capacity);
+
+        Self { len, data, storage: allocator::SingleRange::new(Global), _marker: PhantomData }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<RawVec<T, allocator::SingleRange<Global>>> for Vec<T> {
+    fn from(vec: RawVec<T, allocator::SingleRange<Global>>) -> Self { vec.into_vec() }
+}
+
+impl<T: Clone, S: SingleRangeStorage> ToOwnedIn<S> for [T] {
+    type Owned = RawVec<T, S>;
+
+    fn try_to_owned_in(&self, storage: S) -> Result<Self::Owned, (S, StorageError)> {
+        let mut vec = RawVec::new(storage);
+
+        match vec.try_extend_from_slice(self) {
+            Ok(()) => Ok(vec),
+            Err(error) => Err((vec.into_storage(), error)),
+        }
+    }
+}
+
+//  Safety:
+//  -   `T` is not accessed on drop, other than being dropped itself.
+unsafe impl<#[may_dangle] T, S: SingleRangeStorage> Drop for RawVec<T, S> {
+    fn drop(&mut self) {
+        self.clear();
+
+        //  Safety:
+        //  -   `self.data` is valid.
+        unsafe { self.storage.deallocate(self.data) };
+    }
+}
+
+//
+//  Implementation
+//
+
+impl<T, S: SingleRangeStorage> RawVec<T, S> {
+    fn into_capacity(n: usize) -> S::Capacity {
+        S::Capacity::from_usize(n).expect("n <= S::maximum_capacity()")
+    }
+
+    //  Destroys the elements, releases the range, and returns the storage.
+    fn into_storage(self) -> S {
+        let mut this = ManuallyDrop::new(self);
+
+        this.clear();
+
+        let data = this.data;
+
+        //  Safety:
+        //  -   `data` is valid, and never used again.
+        //  -   `this.storage` is never used again, as `this` is never dropped.
+        unsafe {
+            this.storage.deallocate(data);
+            ptr::read(&this.storage)
+        }
+    }
+
+    //  Returns the maximum number of elements the storage may hold, within `isize::MAX` bytes.
+    fn addressable_capacity(&self) -> usize {
+        let bytes = (isize::MAX as usize).checked_div(mem::size_of::<T>()).unwrap_or(usize::MAX);
+
+        cmp::min(self.storage.maximum_capacity::<T>().into_usize(), bytes)
+    }
+
+    fn raw_slice(&self) -> &[MaybeUninit<T>] {
+        //  Safety:
+        //  -   `self.data` is valid and points to valid data.
+        let range = unsafe { self.storage.resolve(self.data) };
+
+        //  Safety:
+        //  -   `range` points to valid data.
+        //  -   The lifetime of the slice is actually that of `self.storage`.
+        unsafe { &*range.as_ptr() }
+    }
+
+    fn raw_slice_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        //  Safety:
+        //  -   `self.data` is valid and points to valid data.
+        let range = unsafe { self.storage.resolve_mut(self.data) };
+
+        //  Safety:
+        //  -   `range` points to valid data.
+        //  -   The lifetime of the slice is actually that of `self.storage`.
+        unsafe { &mut *range.as_ptr() }
+    }
+
+    //  Ensures the capacity is at least `capacity`, growing if necessary.
+    fn try_grow_to(&mut self, capacity: usize) -> Result<(), StorageError> {
+        if capacity <= self.raw_slice().len() {
+            return Ok(());
+        }
+
+        let new_cap = S::Capacity::from_usize(capacity).ok_or_else(StorageError::capacity_overflow)?;
+
+        //  Safety:
+        //  -   `self.data` is a valid handle pointing to valid data.
+        self.data = unsafe { self.storage.try_grow(self.data, new_cap)? };
+
+        Ok(())
+    }
+
+    //  Ensures there is room for at least one more element, growing geometrically if necessary.
+    pub(crate) fn try_grow_for_push(&mut self) -> Result<(), StorageError> { self.try_reserve(1) }
+
+    //  Pushes `e` at the back, without checking the capacity.
+    //
+    //  #   Safety
+    //
+    //  -   Assumes `self.len() < self.capacity()`, for example following a successful `try_grow_for_push`.
+    pub(crate) unsafe fn push_unchecked(&mut self, e: T) {
+        let len = self.len();
+
+        debug_assert!(len < self.capacity());
+
+        self.raw_slice_mut().get_unchecked_mut(len).write(e);
+
+        self.len = Self::into_capacity(len + 1);
+    }
+
+    //  Retains only the elements for which `fun` returns `true`, in a single pass, preserving their order.
+    //
+    //  `fun` is passed the element under consideration, and the last retained element before it, if any.
+    fn retain_impl<F: FnMut(&mut T, Option<&mut T>) -> bool>(&mut self, mut fun: F) {
+        let original = self.len();
+
+        //  Should anything panic, the guard restores the length, hence no element may be dropped twice.
+        self.len = Self::into_capacity(0);
+
+        let mut guard = RetainGuard { vec: self, processed: 0, deleted: 0, original };
+
+        while guard.processed < original {
+            let base = guard.vec.raw_slice_mut().as_mut_ptr() as *mut T;
+
+            //  Safety:
+            //  -   `processed < original`, and the first `original` elements are initialized, or moved.
+            //  -   The element at `processed` has not been processed yet, hence is initialized.
+            let current = unsafe { base.add(guard.processed) };
+
+            //  Safety:
+            //  -   The last retained element, if any, is at `processed - deleted - 1`, and is initialized.
+            let previous = (guard.processed > guard.deleted).then(|| unsafe { &mut *current.sub(guard.deleted + 1) });
+
+            //  Safety:
+            //  -   `current` is initialized, as per above, and distinct from `previous`.
+            let retained = fun(unsafe { &mut *current }, previous);
+
+            guard.processed += 1;
+
+            if !retained {
+                guard.deleted += 1;
+
+                //  Safety:
+                //  -   `current` is initialized, and accounted for as processed in case `drop` panics.
+                unsafe { ptr::drop_in_place(current) };
+
+                continue;
+            }
+
+            if guard.deleted > 0 {
+                //  Safety:
+                //  -   The slot `deleted` positions to the left was deleted, or moved from.
+                unsafe { ptr::copy_nonoverlapping(current, current.sub(guard.deleted), 1) };
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn try_push_grow(&mut self, e: T) -> Result<(), T> {
+        let len = self.len();
+
+        if self.try_grow_for_push().is_err() {
+            return Err(e);
+        }
+
+        let slice = self.raw_slice_mut();
+
+        //  Safety:
+        //  -   `len < slice.len()`.
+        let slot = unsafe { slice.get_unchecked_mut(len) };
+
+        slot.write(e);
+
+        self.len = Self::into_capacity(len + 1);
+
+        Ok(())
+    }
+}
+
+//  Compacts the elements not yet processed by `retain_mut` and restores the length, even on panic.
+struct RetainGuard<'a, T, S: SingleRangeStorage> {
+    vec: &'a mut RawVec<T, S>,
+    processed: usize,
+    deleted: usize,
+    original: usize,
+}
+
+impl<'a, T, S: SingleRangeStorage> Drop for RetainGuard<'a, T, S> {
+    fn drop(&mut self) {
+        let base = self.vec.raw_slice_mut().as_mut_ptr() as *mut T;
+
+        if self.deleted > 0 && self.processed < self.original {
+            //  Safety:
+            //  -   The elements in `processed..original` are initialized, and not yet processed.
+            //  -   The `deleted` slots preceding them were deleted, or moved from.
+            unsafe {
+                let unprocessed = base.add(self.processed);
+                ptr::copy(unprocessed, unprocessed.sub(self.deleted), self.original - self.processed);
+            }
+        }
+
+        self.vec.len = RawVec::<T, S>::into_capacity(self.original - self.deleted);
+    }
+}
+
+//  Deserializes a sequence into a `RawVec`, failing gracefully when the storage runs out of room.
+#[cfg(feature = "serde")]
+struct RawVecVisitor<T, S>(PhantomData<fn() -> (T, S)>);
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>, S: Default + SingleRangeStorage> Visitor<'de> for RawVecVisitor<T, S> {
+    type Value = RawVec<T, S>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> { write!(f, "a sequence") }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut result = RawVec::new(S::default());
+
+        while let Some(e) = seq.next_element()? {
+            result.try_reserve(1).map_err(de::Error::custom)?;
+
+            //  Safety:
+            //  -   There is room for 1 more element, as per `try_reserve`.
+            unsafe { result.push_unchecked(e) };
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test_inline {
+
+use core::mem;
+
+use crate::collections::CollectIn;
+use crate::error::StorageErrorKind;
+use crate::inline::SingleRange;
+use crate::small;
+use crate::utils::NonAllocator;
+
+use super::*;
+
+#[test]
+fn size() {
+    type Storage = SingleRange<u8, u8, 31>;
+    type Vec = RawVec<u8, Storage>;
+
+    assert_eq!(32, mem::size_of::<Vec>());
+}
+
+#[test]
+fn growth_capped_by_capacity_type() {
+    use crate::alternative::{self, DefaultBuilder};
+
+    type Small = SingleRange<u8, u8, 16>;
+    type Large = SingleRange<u8, u8, 255>;
+    type Storage = alternative::SingleRange<Small, Large, DefaultBuilder, DefaultBuilder>;
+
+    let mut vec = RawVec::new(Storage::first(SingleRange::new(), DefaultBuilder));
+
+    for i in 0..255 {
+        vec.push(i as u8);
+    }
+
+    assert_eq!(255, vec.capacity());
+    assert_eq!(Err(255), vec.try_push(255));
+
+    let error = vec.try_reserve(usize::MAX).unwrap_err();
+    assert_eq!(StorageErrorKind::CapacityOverflow, error.kind());
+}
+
+#[test]
+fn from_array() {
+    type Vec = RawVec<u8, SingleRange<u8, u8, 4>>;
+
+    let vec = Vec::from([1, 2, 3]);
+    assert_eq!(&[1, 2, 3], &*vec);
+
+    assert!(Vec::try_from_array_in([1, 2, 3, 4, 5], SingleRange::new()).is_err());
+}
+
+#[test]
+fn new_spans_storage() {
+    let vec = RawVec::<u8, SingleRange<u8, u8, 31>>::default();
+    assert_eq!(31, vec.capacity());
+
+    let vec = RawVec::<u8, small::SingleRange<[u8; 31], NonAllocator>>::default();
+    assert_eq!(31, vec.capacity());
+}
+
+#[test]
+fn smoke_test() {
+    type Storage = SingleRange<u8, u8, 31>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+
+    for i in 0..31 {
+        vec.push(i);
+    }
+
+    assert_eq!(Some(&2), vec.get(2));
+
+    assert_eq!(
+        "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30]",
+        format!("{:?}", vec)
+    );
+}
+
+#[test]
+fn try_push_failure() {
+    type Storage = SingleRange<u8, u8, 1>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+    vec.push(0);
+
+    assert_eq!(Err(42), vec.try_push(42));
+}
+
+#[test]
+fn collect_in() {
+    type Storage = SingleRange<u8, u8, 31>;
+
+    let vec = (0u8..3).collect_in::<RawVec<_, _>>(Storage::default());
+
+    assert_eq!([0, 1, 2], &*vec);
+}
+
+#[test]
+fn collect_extend() {
+    let mut vec: RawVec<u8, SingleRange<u8, u8, 8>> = (0u8..3).collect();
+
+    vec.extend(3..6);
+
+    assert_eq!([0, 1, 2, 3, 4, 5], &*vec);
+}
+
+#[test]
+fn try_collect_in_failure() {
+    type Storage = SingleRange<u8, u8, 2>;
+
+    let error = (0u8..3).try_collect_in::<RawVec<_, _>>(Storage::default()).unwrap_err();
+
+    assert_eq!(StorageErrorKind::TooLarge, error.kind());
+}
+
+#[test]
+fn bytes() {
+    type Storage = SingleRange<u8, u8, 8>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+
+    vec.extend_from_slice_copied(b"abc");
+    vec.extend_from_slice_copied(b"de");
+    assert_eq!(b"abcde", &*vec);
+
+    vec.try_extend_from_slice_copied(b"fghi").unwrap_err();
+    assert_eq!(b"abcde", &*vec);
+
+    vec.resize_zeroed(7);
+    assert_eq!(b"abcde\0\0", &*vec);
+
+    vec.resize_zeroed(2);
+    assert_eq!(b"ab", &*vec);
+
+    vec.try_resize_zeroed(9).unwrap_err();
+    assert_eq!(b"ab", &*vec);
+
+    vec.fill_capacity(b'x');
+    assert_eq!(b"xxxxxxxx", &*vec);
+}
+
+#[test]
+fn split_at_spare() {
+    type Storage = SingleRange<u8, u8, 8>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+    vec.extend_from_slice(b"ab");
+
+    let (initialized, spare) = vec.split_at_spare_mut();
+
+    assert_eq!(b"ab", initialized);
+    assert_eq!(6, spare.len());
+
+    spare[0].write(b'c');
+    spare[1].write(b'd');
+
+    unsafe { vec.assume_init_grow(2) };
+
+    assert_eq!(b"abcd", &*vec);
+
+    let (initialized, spare) = vec.split_at_spare();
+
+    assert_eq!(b"abcd", initialized);
+    assert_eq!(4, spare.len());
+
+    unsafe { vec.set_len(1) };
+
+    assert_eq!(b"a", &*vec);
+}
+
+#[test]
+fn get_many_mut() {
+    type Storage = SingleRange<u8, u8, 4>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+    vec.extend_from_slice(&[1, 2, 3]);
+
+    let [a, b] = vec.get_many_mut([2, 0]).unwrap();
+    mem::swap(a, b);
+
+    assert_eq!([3, 2, 1], &*vec);
+
+    assert!(vec.get_many_mut([0, 3]).is_none());
+    assert!(vec.get_many_mut([1, 2, 1]).is_none());
+    assert!(vec.get_many_mut::<0>([]).is_some());
+}
+
+#[test]
+fn try_into_array() {
+    type Storage = SingleRange<u8, u8, 4>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+    vec.extend_from_slice(&[1, 2, 3]);
+
+    let vec = vec.try_into_array::<4>().unwrap_err();
+
+    assert_eq!([1, 2, 3], vec.try_into_array::<3>().unwrap());
+}
+
+#[test]
+fn try_reserve() {
+    type Storage = SingleRange<u8, u8, 8>;
+    type Vec = RawVec<u8, Storage>;
+
+    let mut vec = Vec::default();
+
+    vec.reserve(8);
+    vec.try_reserve(9).unwrap_err();
+
+    assert_eq!(8, vec.capacity());
+
+    let vec = Vec::with_capacity_in(8, Storage::default());
+    assert_eq!(8, vec.capacity());
+
+    assert!(Vec::try_with_capacity_in(9, Storage::default()).is_err());
+}
+
+#[test]
+fn clone() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 8>>::default();
+    vec.extend_from_slice(&[1, 2, 3]);
+
+    let mut clone = vec.clone();
+    clone.push(4);
+
+    assert_eq!(&[1, 2, 3], &*vec);
+    assert_eq!(&[1, 2, 3, 4], &*clone);
+}
+
+#[test]
+fn insert_remove() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+
+    vec.insert(0, 2);
+    vec.insert(0, 0);
+    vec.insert(1, 1);
+    vec.insert(3, 3);
+
+    assert_eq!(&[0, 1, 2, 3], &*vec);
+    assert_eq!(Err(4), vec.try_insert(2, 4));
+
+    assert_eq!(1, vec.remove(1));
+    assert_eq!(3, vec.remove(2));
+
+    assert_eq!(&[0, 2], &*vec);
+}
+
+#[test]
+fn swap_remove() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+    vec.extend_from_slice(&[0, 1, 2, 3]);
+
+    assert_eq!(1, vec.swap_remove(1));
+    assert_eq!(&[0, 3, 2], &*vec);
+
+    assert_eq!(2, vec.swap_remove(2));
+    assert_eq!(&[0, 3], &*vec);
+}
+
+#[test]
+fn retain() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 8>>::default();
+    vec.extend_from_slice(&[0, 1, 2, 3, 4, 5, 6]);
+
+    vec.retain(|e| e % 3 != 0);
+
+    assert_eq!(&[1, 2, 4, 5], &*vec);
+
+    vec.retain_mut(|e| { *e *= 2; *e != 4 });
+
+    assert_eq!(&[2, 8, 10], &*vec);
+}
+
+#[test]
+fn dedup() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 8>>::default();
+    vec.extend_from_slice(&[1, 1, 2, 3, 3, 3, 1, 4]);
+
+    vec.dedup();
+    assert_eq!(&[1, 2, 3, 1, 4], &*vec);
+
+    vec.dedup_by_key(|e| *e / 2);
+    assert_eq!(&[1, 2, 1, 4], &*vec);
+
+    vec.dedup_by(|a, b| a > b);
+    assert_eq!(&[1, 1], &*vec);
+}
+
+#[test]
+fn compare() {
+    let mut inline = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+    inline.extend_from_slice(&[1, 2, 3]);
+
+    let mut other = RawVec::<u8, SingleRange<u8, u8, 8>>::default();
+    other.extend_from_slice(&[1, 2, 3]);
+
+    assert_eq!(inline, other);
+    assert_eq!(inline.partial_cmp(&other), Some(Ordering::Equal));
+
+    other.push(0);
+
+    assert_ne!(inline, other);
+    assert!(inline < other);
+
+    inline[2] = 4;
+
+    assert!(inline > other);
+
+    let mut smaller = inline.clone();
+    smaller.pop();
+
+    assert_eq!(Ordering::Greater, inline.cmp(&smaller));
+}
+
+#[test]
+fn hash() {
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of<T: ?Sized + Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let mut inline = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+    inline.extend_from_slice(&[1, 2, 3]);
+
+    let mut other = RawVec::<u8, SingleRange<u8, u8, 8>>::default();
+    other.extend_from_slice(&[1, 2, 3]);
+
+    assert_eq!(hash_of(&[1u8, 2, 3][..]), hash_of(&inline));
+    assert_eq!(hash_of(&inline), hash_of(&other));
+}
+
+#[test]
+fn spare_capacity_mut() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+    vec.push(1);
+
+    let spare = vec.spare_capacity_mut();
+    assert_eq!(3, spare.len());
+
+    spare[0].write(2);
+    spare[1].write(3);
+
+    //  Safety:
+    //  -   The first 2 elements of the spare capacity were initialized above.
+    unsafe { vec.set_len(3) };
+
+    assert_eq!(&[1, 2, 3], &*vec);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    type Vec = RawVec<u8, SingleRange<u8, u8, 2>>;
+
+    let mut vec = Vec::default();
+    vec.extend_from_slice(&[1, 2]);
+
+    assert_tokens(&vec, &[Token::Seq { len: Some(2) }, Token::U8(1), Token::U8(2), Token::SeqEnd]);
+
+    let tokens = [Token::Seq { len: Some(3) }, Token::U8(1), Token::U8(2), Token::U8(3), Token::SeqEnd];
+    assert_de_tokens_error::<Vec>(&tokens, "layout too large (size: 3, align: 1)");
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn remove_out_of_bounds() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+    vec.push(0);
+
+    vec.remove(1);
+}
+
+#[test]
+fn resize_truncate() {
+    let mut vec = RawVec::<u8, SingleRange<u8, u8, 4>>::default();
+
+    vec.resize(3, 7);
+    assert_eq!(&[7, 7, 7], &*vec);
+
+    vec.truncate(1);
+    assert_eq!(&[7], &*vec);
+
+    let mut next = 0;
+    vec.resize_with(4, || { next += 1; next });
+    assert_eq!(&[7, 1, 2, 3], &*vec);
+
+    assert!(vec.try_resize(5, 0).is_err());
+    assert_eq!(&[7, 1, 2, 3], &*vec);
+
+    vec.resize(0, 0);
+    assert!(vec.is_empty());
+    assert_eq!(4, vec.capacity());
+}
+
+} // mod test_inline
+
+#[cfg(test)]
+mod test_allocator {
+
+use core::mem;
+
+use crate::{alternative, small};
+use crate::allocator::SingleRange;
+use crate::collections::CollectIn;
+use crate::fallback::Fallback;
+use crate::inline;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+#[test]
+fn size() {
+    type Storage = SingleRange<NonAllocator>;
+    type Vec = RawVec<u8, Storage>;
+
+    assert_eq!(mem::size_of::<usize>() * 3, mem::size_of::<Vec>());
+}
+
+#[test]
+fn smoke_test() {
+    type Storage = SingleRange<SpyAllocator>;
+    type Vec = RawVec<u8, Storage>;
+
+    let allocator = SpyAllocator::default();
+
+    let storage = SingleRange::new(allocator.clone());
+    let mut vec = Vec::new(storage);
+
+    assert_eq!(0, allocator.allocated());
+    assert_eq!(0, allocator.deallocated());
+
+    for i in 0..31 {
+        vec.push(i);
+
+        assert_eq!(allocator.allocated() - 1, allocator.deallocated());
+    }
+
+    assert_eq!(Some(&2), vec.get(2));
+
+    assert_eq!(
+        "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30]",
         format!("{:?}", vec)
     );
 
@@ -300,6 +1564,348 @@ fn smoke_test() {
     assert_eq!(6, allocator.deallocated());
 }
 
+#[test]
+fn insert_remove_releases() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+
+    for i in 0..4 {
+        vec.insert(0, i.to_string());
+    }
+
+    assert_eq!("2", vec.remove(1));
+    assert_eq!("3", vec.swap_remove(0));
+    assert_eq!(&["0".to_string(), "1".to_string()], &*vec);
+
+    drop(vec);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn with_capacity_in() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::with_capacity_in(4, SingleRange::new(allocator.clone()));
+
+    assert_eq!(4, vec.capacity());
+    assert_eq!(1, allocator.allocated());
+
+    vec.extend_from_slice(&[1u32, 2, 3, 4]);
+    vec.reserve_exact(3);
+
+    assert_eq!(7, vec.capacity());
+    assert_eq!(2, allocator.allocated());
+
+    vec.reserve_exact(3);
+
+    assert_eq!(2, allocator.allocated());
+}
+
+#[test]
+fn push_within_capacity() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::with_capacity_in(2, SingleRange::new(allocator.clone()));
+
+    vec.push_within_capacity(1u32).unwrap();
+    vec.push_within_capacity(2).unwrap();
+
+    assert_eq!(Err(3), vec.push_within_capacity(3));
+    assert_eq!(&[1, 2], &*vec);
+    assert_eq!(1, allocator.allocated());
+}
+
+#[test]
+fn clear_drops() {
+    use std::rc::Rc;
+
+    let counted = Rc::new(());
+
+    let mut vec = RawVec::new(SingleRange::new(SpyAllocator::default()));
+    vec.resize(4, counted.clone());
+
+    assert_eq!(5, Rc::strong_count(&counted));
+
+    vec.truncate(1);
+
+    assert_eq!(2, Rc::strong_count(&counted));
+
+    vec.clear();
+
+    assert_eq!(1, Rc::strong_count(&counted));
+    assert_eq!(4, vec.capacity());
+}
+
+#[test]
+fn spilled() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(small::SingleRange::<[u8; 4], _>::new(allocator.clone()));
+    vec.extend_from_slice(&[1u8, 2, 3, 4]);
+
+    assert!(!vec.spilled());
+    assert_eq!(0, allocator.allocated());
+
+    vec.push(5);
+
+    assert!(vec.spilled());
+    assert_eq!(1, allocator.allocated());
+}
+
+#[test]
+fn amortized_growth_capped_by_storage() {
+    use core::ptr::NonNull;
+
+    use crate::traits::RangeStorage;
+
+    //  Caps the capacity at 6 elements, well below `usize::MAX`.
+    struct Capped(SingleRange<SpyAllocator>);
+
+    impl RangeStorage for Capped {
+        type Handle<T> = <SingleRange<SpyAllocator> as RangeStorage>::Handle<T>;
+
+        type Capacity = usize;
+
+        fn maximum_capacity<T>(&self) -> usize { 6 }
+
+        unsafe fn deallocate<T>(&mut self, handle: Self::Handle<T>) { self.0.deallocate(handle) }
+
+        unsafe fn resolve<T>(&self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> { self.0.resolve(handle) }
+
+        unsafe fn resolve_mut<T>(&mut self, handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
+            self.0.resolve_mut(handle)
+        }
+
+        unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: usize)
+            -> Result<Self::Handle<T>, StorageError>
+        {
+            if new_capacity > 6 {
+                return Err(StorageError::capacity_overflow());
+            }
+
+            self.0.try_grow(handle, new_capacity)
+        }
+    }
+
+    impl SingleRangeStorage for Capped {
+        fn allocate<T>(&mut self, capacity: usize) -> Result<Self::Handle<T>, StorageError> {
+            self.0.allocate(capacity)
+        }
+    }
+
+    let allocator = SpyAllocator::default();
+    let mut vec = RawVec::new(Capped(SingleRange::new(allocator.clone())));
+
+    for i in 0..5u8 {
+        vec.push(i);
+    }
+
+    //  Doubling 4 would exceed the maximum capacity of the storage, which is reserved at once instead.
+    assert_eq!(6, vec.capacity());
+}
+
+#[test]
+fn spilled_alternative() {
+    use crate::allocator::AllocatorBuilder;
+
+    let allocator = SpyAllocator::default();
+    let storage = alternative::SingleRange::<_, SingleRange<_>, alternative::DefaultBuilder, _>::first(
+        inline::SingleRange::<usize, u8, 4>::new(),
+        AllocatorBuilder(allocator.clone()),
+    );
+
+    let mut vec: RawVec<u8, _> = RawVec::new(storage);
+    vec.extend_from_slice(&[1u8, 2, 3, 4]);
+
+    assert!(!vec.spilled());
+
+    vec.push(5);
+
+    assert!(vec.spilled());
+    assert_eq!(1, allocator.allocated());
+}
+
+#[test]
+fn spilled_fallback() {
+    let allocator = SpyAllocator::default();
+    let storage = Fallback::new(inline::SingleRange::<usize, u8, 4>::new(), SingleRange::new(allocator.clone()));
+
+    let mut vec: RawVec<u8, _> = RawVec::new(storage);
+    vec.extend_from_slice(&[1u8, 2, 3, 4]);
+
+    assert!(!vec.spilled());
+
+    vec.push(5);
+
+    assert!(vec.spilled());
+    assert_eq!(1, allocator.allocated());
+}
+
+#[test]
+fn try_in() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::<String, inline::SingleRange<u8, [usize; 6], 1>>::default();
+    vec.push("1".to_string());
+    vec.push("2".to_string());
+
+    let vec = vec.try_in(inline::SingleRange::<u8, String, 1>::new()).unwrap_err();
+
+    let mut vec = vec.try_in(SingleRange::new(allocator.clone())).unwrap();
+    vec.push("3".to_string());
+
+    assert_eq!(&["1".to_string(), "2".to_string(), "3".to_string()], &*vec);
+
+    drop(vec);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_into_vec() {
+    let vec = vec![1u32, 2, 3];
+    let pointer = vec.as_ptr();
+
+    let mut raw: RawVec<u32, SingleRange<Global>> = vec.into();
+    assert_eq!(pointer, raw.as_ptr());
+
+    raw.truncate(2);
+
+    let vec = raw.into_vec();
+    assert_eq!(pointer, vec.as_ptr());
+    assert_eq!(vec![1, 2], vec);
+
+    let raw = RawVec::from(Vec::<u32>::new());
+    assert_eq!(0, raw.capacity());
+
+    let vec: Vec<()> = RawVec::from(vec![(); 4]).into();
+    assert_eq!(4, vec.len());
+}
+
+#[test]
+fn drop_dangling() {
+    let mut vec = RawVec::new(SingleRange::new(SpyAllocator::default()));
+
+    let value = String::from("dangling");
+    vec.push(&value);
+
+    assert_eq!(1, vec.len());
+}
+
+#[test]
+fn from_array_in() {
+    let allocator = SpyAllocator::default();
+
+    let vec = RawVec::from_array_in(["1".to_string(), "2".to_string()], SingleRange::new(allocator.clone()));
+
+    assert_eq!(&["1".to_string(), "2".to_string()], &*vec);
+    assert_eq!(1, allocator.allocated());
+
+    drop(vec);
+
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn leak() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+    vec.extend_from_slice(&[1u32, 2, 3]);
+
+    let slice: &'static mut [u32] = vec.leak();
+    slice[0] = 4;
+
+    assert_eq!(&[4, 2, 3], slice);
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(0, allocator.deallocated());
+}
+
+#[test]
+fn zero_sized() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+
+    for _ in 0..100 {
+        vec.push(());
+    }
+
+    vec.truncate(10);
+
+    assert_eq!(10, vec.len());
+    assert_eq!(usize::MAX, vec.capacity());
+    assert_eq!(Some(()), vec.pop());
+
+    drop(vec);
+
+    assert_eq!(0, allocator.allocated());
+    assert_eq!(0, allocator.deallocated());
+}
+
+#[test]
+fn resize_releases() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+
+    vec.resize(5, "a".to_string());
+    vec.truncate(2);
+
+    assert_eq!(&["a".to_string(), "a".to_string()], &*vec);
+    assert_eq!(1, allocator.allocated());
+
+    drop(vec);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn dedup_releases() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+
+    for e in ["a", "a", "b", "c", "c"] {
+        vec.push(e.to_string());
+    }
+
+    vec.dedup();
+
+    assert_eq!(&["a".to_string(), "b".to_string(), "c".to_string()], &*vec);
+
+    drop(vec);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
+#[test]
+fn retain_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+
+    for i in 0..6 {
+        vec.push(i.to_string());
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        vec.retain(|e| if e == "3" { panic!("Boom") } else { e != "1" })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(&["0", "2", "3", "4", "5"], &*vec);
+
+    drop(vec);
+
+    assert_eq!(allocator.allocated(), allocator.deallocated());
+}
+
 #[test]
 fn try_push_failure() {
     type Storage = SingleRange<NonAllocator>;
@@ -310,4 +1916,110 @@ fn try_push_failure() {
     assert_eq!(Err(42), vec.try_push(42));
 }
 
+#[test]
+fn collect_in_reserves() {
+    let allocator = SpyAllocator::default();
+
+    let vec = (0..31).collect_in::<RawVec<u8, _>>(SingleRange::new(allocator.clone()));
+
+    assert_eq!(31, vec.len());
+    assert_eq!(1, allocator.allocated());
+}
+
+#[test]
+fn extend_reserves() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::<u8, _>::new(SingleRange::new(allocator.clone()));
+    vec.push(0);
+
+    vec.extend(1..32);
+
+    assert_eq!(32, vec.len());
+    assert_eq!(2, allocator.allocated());
+}
+
+#[test]
+fn try_collect_in_failure() {
+    (0..3).try_collect_in::<RawVec<u8, _>>(SingleRange::new(NonAllocator)).unwrap_err();
+}
+
+#[test]
+fn extend_from_slice_clones() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::new(SingleRange::new(allocator.clone()));
+    vec.push("a".to_string());
+
+    vec.extend_from_slice(&["b".to_string(), "c".to_string()]);
+
+    assert_eq!(&["a", "b", "c"], &*vec);
+    assert_eq!(2, allocator.allocated());
+}
+
+#[test]
+fn extend_from_slice_grows_once() {
+    let allocator = SpyAllocator::default();
+
+    let mut vec = RawVec::<u8, _>::new(SingleRange::new(allocator.clone()));
+
+    vec.extend_from_slice(&[1; 100]);
+
+    assert_eq!(100, vec.len());
+    assert_eq!(1, allocator.allocated());
+
+    vec.resize_zeroed(150);
+
+    assert_eq!(&[1; 100][..], &vec[..100]);
+    assert_eq!(&[0; 50][..], &vec[100..]);
+    assert_eq!(200, vec.capacity());
+}
+
+#[test]
+fn try_into_array_releases() {
+    let allocator = SpyAllocator::default();
+
+    let vec = (0..3).map(|i| i.to_string()).collect_in::<RawVec<_, _>>(SingleRange::new(allocator.clone()));
+
+    let array: [String; 3] = vec.try_into_array().unwrap();
+
+    assert_eq!(["0", "1", "2"], array);
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+}
+
+#[test]
+fn read_into_spare() {
+    let mut vec = RawVec::<u8, _>::new(SingleRange::new(SpyAllocator::default()));
+
+    vec.extend_from_slice(b"head:");
+    vec.reserve(16);
+
+    let (_, spare) = vec.split_at_spare_mut();
+
+    //  Simulates a read from an I/O source.
+    for (slot, byte) in spare.iter_mut().zip(b"payload") {
+        slot.write(*byte);
+    }
+
+    unsafe { vec.assume_init_grow(7) };
+
+    assert_eq!(b"head:payload", &*vec);
+}
+
+#[test]
+fn clone() {
+    let allocator = SpyAllocator::default();
+
+    let vec = (0..3).map(|i| i.to_string()).collect_in::<RawVec<_, _>>(SingleRange::new(allocator.clone()));
+    let clone = vec.clone();
+
+    assert_eq!(&*vec, &*clone);
+    assert_eq!(2, allocator.allocated());
+
+    drop((vec, clone));
+
+    assert_eq!(2, allocator.deallocated());
+}
+
 } // mod test_allocator
@@ -0,0 +1,112 @@
+//! The error reported by the storages, on failure.
+//!
+//! Unlike the bare `AllocError`, a `StorageError` records why the storage failed, and the offending layout, if any.
+
+use core::{
+    alloc::{AllocError, Layout},
+    fmt::{self, Display},
+};
+
+/// The reason for which a storage failed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StorageErrorKind {
+    /// The layout is too large for the storage, such as an inline storage.
+    TooLarge,
+    /// The alignment of the layout is not supported by the storage.
+    UnsupportedAlignment,
+    /// The requested capacity overflows the capacity type, or the size of its layout overflows.
+    CapacityOverflow,
+    /// The storage has no room left, though it would support the layout if it had.
+    Exhausted,
+    /// The underlying allocator failed.
+    AllocatorFailure,
+    /// The request is not supported by the storage, such as an element whose handle it cannot hold.
+    Unsupported,
+}
+
+/// The error reported by a storage, on failure.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct StorageError {
+    kind: StorageErrorKind,
+    layout: Option<Layout>,
+}
+
+impl StorageError {
+    /// Creates an instance of `kind`, for `layout`.
+    pub fn new(kind: StorageErrorKind, layout: Option<Layout>) -> Self { Self { kind, layout } }
+
+    /// Creates an instance for a `layout` too large for the storage.
+    pub fn too_large(layout: Layout) -> Self { Self::new(StorageErrorKind::TooLarge, Some(layout)) }
+
+    /// Creates an instance for a `layout` whose alignment is not supported by the storage.
+    pub fn unsupported_alignment(layout: Layout) -> Self {
+        Self::new(StorageErrorKind::UnsupportedAlignment, Some(layout))
+    }
+
+    /// Creates an instance for a capacity overflow, for which no layout can be computed.
+    pub fn capacity_overflow() -> Self { Self::new(StorageErrorKind::CapacityOverflow, None) }
+
+    /// Creates an instance for an exhausted storage, which had no room left for `layout`.
+    pub fn exhausted(layout: Layout) -> Self { Self::new(StorageErrorKind::Exhausted, Some(layout)) }
+
+    /// Creates an instance for the failure of the underlying allocator to allocate `layout`.
+    pub fn allocator_failure(layout: Layout) -> Self { Self::new(StorageErrorKind::AllocatorFailure, Some(layout)) }
+
+    /// Creates an instance for a request not supported by the storage.
+    pub fn unsupported() -> Self { Self::new(StorageErrorKind::Unsupported, None) }
+
+    /// Returns the reason of the failure.
+    pub fn kind(&self) -> StorageErrorKind { self.kind }
+
+    /// Returns the offending layout, if any.
+    pub fn layout(&self) -> Option<Layout> { self.layout }
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let reason = match self.kind {
+            StorageErrorKind::TooLarge => "layout too large",
+            StorageErrorKind::UnsupportedAlignment => "alignment unsupported",
+            StorageErrorKind::CapacityOverflow => "capacity overflow",
+            StorageErrorKind::Exhausted => "storage exhausted",
+            StorageErrorKind::AllocatorFailure => "allocator failure",
+            StorageErrorKind::Unsupported => "unsupported",
+        };
+
+        match self.layout {
+            Some(layout) => write!(f, "{} (size: {}, align: {})", reason, layout.size(), layout.align()),
+            None => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl From<AllocError> for StorageError {
+    fn from(_: AllocError) -> Self { Self::new(StorageErrorKind::AllocatorFailure, None) }
+}
+
+impl From<StorageError> for AllocError {
+    fn from(_: StorageError) -> Self { AllocError }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+
+#[test]
+fn display() {
+    let layout = Layout::new::<u64>();
+
+    assert_eq!("layout too large (size: 8, align: 8)", StorageError::too_large(layout).to_string());
+    assert_eq!("capacity overflow", StorageError::capacity_overflow().to_string());
+}
+
+#[test]
+fn from_alloc_error() {
+    let error = StorageError::from(AllocError);
+
+    assert_eq!(StorageErrorKind::AllocatorFailure, error.kind());
+    assert_eq!(None, error.layout());
+}
+
+} // mod tests
@@ -6,27 +6,95 @@
 //! It is simpler than alternative, however is heavier weight.
 
 use core::{
-    alloc::AllocError,
     cmp,
     fmt::{self, Debug},
-    marker::Unsize,
+    marker::{PhantomData, Unsize},
     mem::MaybeUninit,
     ptr::{self, NonNull, Pointee},
 };
 
-use crate::traits::{
-    Capacity, ElementStorage, MultiElementStorage, RangeStorage, SingleElementStorage,
-    SingleRangeStorage,
+use crate::{
+    error::StorageError,
+    traits::{
+        Capacity, CloneStorage, ElementStorage, MultiElementStorage, MultiRangeStorage, PinningStorage, RangeStorage,
+        SingleElementStorage, SingleRangeStorage, SpillingElementStorage, SpillingRangeStorage,
+    },
 };
 
 /// An allocator that implements ElementStorage, SingleElementStorage, MultiElementStorage,
-/// RangeStorage, and SingleRangeStorage, depending on what the supplied allocators implement.
-#[derive(Default)]
-pub struct Fallback<P, S> {
+/// RangeStorage, and SingleRangeStorage or MultiRangeStorage, depending on what the supplied
+/// allocators implement.
+///
+/// Since a storage may implement both SingleRangeStorage and MultiRangeStorage, `K` selects
+/// which of the two is used to allocate ranges: `Single` (the default) or `Multi`.
+pub struct Fallback<P, S, K = Single> {
     /// The primary allocator.
     pub primary: P,
     /// The secondary allocator.
     pub secondary: S,
+    _kind: PhantomData<K>,
+}
+
+impl<P, S, K> Fallback<P, S, K> {
+    /// Creates an instance, allocating from `primary` first, and from `secondary` on failure.
+    pub fn new(primary: P, secondary: S) -> Self {
+        Self { primary, secondary, _kind: PhantomData }
+    }
+}
+
+/// Selects SingleRangeStorage to allocate the ranges of a [`Fallback`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Single;
+
+/// Selects MultiRangeStorage to allocate the ranges of a [`Fallback`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Multi;
+
+/// Allocates the ranges of a [`Fallback`] from its primary and secondary allocators.
+pub trait RangeKind<P: RangeStorage, S: RangeStorage> {
+    /// Allocates a range of `capacity` elements from `primary`.
+    fn allocate_primary<T>(
+        primary: &mut P,
+        capacity: P::Capacity,
+    ) -> Result<P::Handle<T>, StorageError>;
+
+    /// Allocates a range of `capacity` elements from `secondary`.
+    fn allocate_secondary<T>(
+        secondary: &mut S,
+        capacity: S::Capacity,
+    ) -> Result<S::Handle<T>, StorageError>;
+}
+
+impl<P: SingleRangeStorage, S: SingleRangeStorage> RangeKind<P, S> for Single {
+    fn allocate_primary<T>(
+        primary: &mut P,
+        capacity: P::Capacity,
+    ) -> Result<P::Handle<T>, StorageError> {
+        primary.allocate(capacity)
+    }
+
+    fn allocate_secondary<T>(
+        secondary: &mut S,
+        capacity: S::Capacity,
+    ) -> Result<S::Handle<T>, StorageError> {
+        secondary.allocate(capacity)
+    }
+}
+
+impl<P: MultiRangeStorage, S: MultiRangeStorage> RangeKind<P, S> for Multi {
+    fn allocate_primary<T>(
+        primary: &mut P,
+        capacity: P::Capacity,
+    ) -> Result<P::Handle<T>, StorageError> {
+        primary.allocate(capacity)
+    }
+
+    fn allocate_secondary<T>(
+        secondary: &mut S,
+        capacity: S::Capacity,
+    ) -> Result<S::Handle<T>, StorageError> {
+        secondary.allocate(capacity)
+    }
 }
 
 /// The handle used by the [`Fallback`] allocator.
@@ -40,7 +108,12 @@ pub enum FallbackHandle<P, S> {
 
 use FallbackHandle::*;
 
-impl<F, S> ElementStorage for Fallback<F, S>
+impl<P, S> FallbackHandle<P, S> {
+    /// Returns whether the handle was issued by the secondary storage, rather than the primary one.
+    pub fn is_secondary(&self) -> bool { matches!(self, Secondary(_)) }
+}
+
+impl<F, S, K> ElementStorage for Fallback<F, S, K>
 where
     F: ElementStorage,
     S: ElementStorage,
@@ -77,9 +150,30 @@ where
             Secondary(second) => Secondary(self.secondary.coerce(second)),
         }
     }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(
+        &self,
+        handle: Self::Handle<T>,
+    ) -> Self::Handle<U> {
+        match handle {
+            Primary(first) => Primary(self.primary.cast(first)),
+            Secondary(second) => Secondary(self.secondary.cast(second)),
+        }
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(
+        &mut self,
+        handle: Self::Handle<T>,
+        meta: T::Metadata,
+    ) -> Result<Self::Handle<T>, StorageError> {
+        match handle {
+            Primary(first) => self.primary.update_metadata(first, meta).map(Primary),
+            Secondary(second) => self.secondary.update_metadata(second, meta).map(Secondary),
+        }
+    }
 }
 
-impl<F, S> SingleElementStorage for Fallback<F, S>
+impl<F, S, K> SingleElementStorage for Fallback<F, S, K>
 where
     F: SingleElementStorage,
     S: SingleElementStorage,
@@ -94,7 +188,7 @@ where
     fn allocate<T: ?Sized + Pointee>(
         &mut self,
         meta: T::Metadata,
-    ) -> Result<Self::Handle<T>, AllocError> {
+    ) -> Result<Self::Handle<T>, StorageError> {
         self.primary
             .allocate::<T>(meta)
             .map(|handle| Primary(handle))
@@ -106,7 +200,7 @@ where
     }
 }
 
-impl<F, S> MultiElementStorage for Fallback<F, S>
+impl<F, S, K> MultiElementStorage for Fallback<F, S, K>
 where
     F: MultiElementStorage,
     S: MultiElementStorage,
@@ -121,7 +215,7 @@ where
     fn allocate<T: ?Sized + Pointee>(
         &mut self,
         meta: T::Metadata,
-    ) -> Result<Self::Handle<T>, AllocError> {
+    ) -> Result<Self::Handle<T>, StorageError> {
         self.primary
             .allocate::<T>(meta)
             .map(|handle| Primary(handle))
@@ -133,10 +227,11 @@ where
     }
 }
 
-impl<F, S> RangeStorage for Fallback<F, S>
+impl<F, S, K> RangeStorage for Fallback<F, S, K>
 where
-    F: SingleRangeStorage,
-    S: SingleRangeStorage,
+    F: RangeStorage,
+    S: RangeStorage,
+    K: RangeKind<F, S>,
 {
     type Handle<T> = FallbackHandle<F::Handle<T>, S::Handle<T>>;
 
@@ -180,7 +275,7 @@ where
         &mut self,
         handle: Self::Handle<T>,
         new_capacity: Self::Capacity,
-    ) -> Result<Self::Handle<T>, AllocError> {
+    ) -> Result<Self::Handle<T>, StorageError> {
         match handle {
             Primary(first) => {
                 let first_capacity = into_first::<F, S>(new_capacity);
@@ -190,7 +285,7 @@ where
                 {
                     Ok(handle) => Ok(Primary(handle)),
                     Err(_) => {
-                        let second = self.secondary.allocate(new_capacity)?;
+                        let second = K::allocate_secondary(&mut self.secondary, new_capacity)?;
                         transfer(self.primary.resolve_mut(first), self.secondary.resolve_mut(second));
                         self.primary.deallocate(first);
                         Ok(Secondary(second))
@@ -204,11 +299,39 @@ where
         }
     }
 
+    unsafe fn try_grow_front<T>(
+        &mut self,
+        handle: Self::Handle<T>,
+        new_capacity: Self::Capacity,
+    ) -> Result<Self::Handle<T>, StorageError> {
+        match handle {
+            Primary(first) => {
+                let first_capacity = into_first::<F, S>(new_capacity);
+
+                match first_capacity
+                    .and_then(|new_capacity| self.primary.try_grow_front(first, new_capacity))
+                {
+                    Ok(handle) => Ok(Primary(handle)),
+                    Err(_) => {
+                        let second = K::allocate_secondary(&mut self.secondary, new_capacity)?;
+                        transfer_back(self.primary.resolve_mut(first), self.secondary.resolve_mut(second));
+                        self.primary.deallocate(first);
+                        Ok(Secondary(second))
+                    }
+                }
+            }
+            Secondary(second) => self
+                .secondary
+                .try_grow_front(second, new_capacity)
+                .map(Secondary),
+        }
+    }
+
     unsafe fn try_shrink<T>(
         &mut self,
         handle: Self::Handle<T>,
         new_capacity: Self::Capacity,
-    ) -> Result<Self::Handle<T>, AllocError> {
+    ) -> Result<Self::Handle<T>, StorageError> {
         let first_capacity = into_first::<F, S>(new_capacity);
 
         match handle {
@@ -217,7 +340,9 @@ where
                 .try_shrink(first, first_capacity?)
                 .map(|handle| Primary(handle)),
             Secondary(second) => {
-                if let Ok(first) = first_capacity.and_then(|cap| self.primary.allocate(cap)) {
+                let first = first_capacity.and_then(|cap| K::allocate_primary(&mut self.primary, cap));
+
+                if let Ok(first) = first {
                     transfer(self.secondary.resolve_mut(second), self.primary.resolve_mut(first));
                     self.secondary.deallocate(second);
                     Ok(Primary(first))
@@ -231,12 +356,12 @@ where
     }
 }
 
-impl<F, S> SingleRangeStorage for Fallback<F, S>
+impl<F, S> SingleRangeStorage for Fallback<F, S, Single>
 where
     F: SingleRangeStorage,
     S: SingleRangeStorage,
 {
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         let first_capacity = into_first::<F, S>(capacity);
 
         if let Ok(first) = first_capacity.and_then(|cap| self.primary.allocate(cap)) {
@@ -249,7 +374,52 @@ where
     }
 }
 
-impl<F, S> Debug for Fallback<F, S> {
+impl<F, S> MultiRangeStorage for Fallback<F, S, Multi>
+where
+    F: MultiRangeStorage,
+    S: MultiRangeStorage,
+{
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let first_capacity = into_first::<F, S>(capacity);
+
+        if let Ok(first) = first_capacity.and_then(|cap| self.primary.allocate(cap)) {
+            Ok(Primary(first))
+        } else {
+            self.secondary.allocate(capacity).map(Secondary)
+        }
+    }
+}
+
+impl<F, S, K> SpillingElementStorage for Fallback<F, S, K>
+where
+    F: ElementStorage,
+    S: ElementStorage,
+{
+    unsafe fn is_spilled<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> bool { handle.is_secondary() }
+}
+
+impl<F, S, K> SpillingRangeStorage for Fallback<F, S, K>
+where
+    F: RangeStorage,
+    S: RangeStorage,
+    K: RangeKind<F, S>,
+{
+    unsafe fn is_spilled<T>(&self, handle: Self::Handle<T>) -> bool { handle.is_secondary() }
+}
+
+//  Safety:
+//  -   Either storage resolves the handles it issued, and neither moves its elements or ranges when moved.
+unsafe impl<F: PinningStorage, S: PinningStorage, K> PinningStorage for Fallback<F, S, K> {}
+
+impl<F: Default, S: Default, K> Default for Fallback<F, S, K> {
+    fn default() -> Self { Self::new(F::default(), S::default()) }
+}
+
+impl<F: CloneStorage, S: CloneStorage, K> CloneStorage for Fallback<F, S, K> {
+    fn clone_storage(&self) -> Self { Self::new(self.primary.clone_storage(), self.secondary.clone_storage()) }
+}
+
+impl<F, S, K> Debug for Fallback<F, S, K> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "MultiElement")
     }
@@ -257,8 +427,8 @@ impl<F, S> Debug for Fallback<F, S> {
 
 fn into_first<F: RangeStorage, S: RangeStorage>(
     capacity: S::Capacity,
-) -> Result<F::Capacity, AllocError> {
-    F::Capacity::from_usize(capacity.into_usize()).ok_or(AllocError)
+) -> Result<F::Capacity, StorageError> {
+    F::Capacity::from_usize(capacity.into_usize()).ok_or_else(StorageError::capacity_overflow)
 }
 
 unsafe fn transfer<T>(from: NonNull<[MaybeUninit<T>]>, mut to: NonNull<[MaybeUninit<T>]>) {
@@ -271,3 +441,84 @@ unsafe fn transfer<T>(from: NonNull<[MaybeUninit<T>]>, mut to: NonNull<[MaybeUni
         cmp::min(from.len(), to.len()),
     );
 }
+
+// Transfers the elements of `from` to the back of `to`, which is assumed to be at least as large.
+unsafe fn transfer_back<T>(from: NonNull<[MaybeUninit<T>]>, mut to: NonNull<[MaybeUninit<T>]>) {
+    let from = from.as_ref();
+    let to = to.as_mut();
+
+    debug_assert!(from.len() <= to.len());
+
+    ptr::copy_nonoverlapping(
+        from.as_ptr(),
+        to.as_mut_ptr().add(to.len() - from.len()),
+        from.len(),
+    );
+}
+
+#[cfg(test)]
+mod test_multi_range {
+
+use crate::adapter::Typed;
+use crate::allocator::MultiElement;
+use crate::utils::{NonAllocator, SpyAllocator};
+
+use super::*;
+
+type Spy = Typed<MultiElement<SpyAllocator>>;
+
+#[test]
+fn allocate_primary() {
+    let (primary, secondary) = (SpyAllocator::default(), SpyAllocator::default());
+
+    let mut storage: Fallback<Spy, Spy, Multi> =
+        Fallback::new(Typed::new(MultiElement::new(primary.clone())), Typed::new(MultiElement::new(secondary.clone())));
+
+    let first = MultiRangeStorage::allocate::<u32>(&mut storage, 2).unwrap();
+    let second = MultiRangeStorage::allocate::<u32>(&mut storage, 4).unwrap();
+
+    assert!(matches!(first, Primary(_)));
+    assert!(matches!(second, Primary(_)));
+    assert_eq!(2, primary.allocated());
+    assert_eq!(0, secondary.allocated());
+
+    unsafe {
+        RangeStorage::deallocate(&mut storage, first);
+        RangeStorage::deallocate(&mut storage, second);
+    }
+
+    assert_eq!(2, primary.deallocated());
+}
+
+#[test]
+fn allocate_secondary() {
+    let secondary = SpyAllocator::default();
+
+    let mut storage: Fallback<Typed<MultiElement<NonAllocator>>, Spy, Multi> =
+        Fallback::new(Typed::new(MultiElement::new(NonAllocator)), Typed::new(MultiElement::new(secondary.clone())));
+
+    let first = MultiRangeStorage::allocate::<u32>(&mut storage, 2).unwrap();
+    let second = MultiRangeStorage::allocate::<u32>(&mut storage, 4).unwrap();
+
+    assert!(matches!(first, Secondary(_)));
+    assert!(matches!(second, Secondary(_)));
+
+    unsafe {
+        let slice = &mut *RangeStorage::resolve_mut(&mut storage, first).as_ptr();
+        slice[0].write(1);
+        slice[1].write(2);
+
+        let first = storage.try_grow(first, 8).unwrap();
+        let slice = &*RangeStorage::resolve(&storage, first).as_ptr();
+
+        assert_eq!(8, slice.len());
+        assert_eq!([1, 2], [slice[0].assume_init(), slice[1].assume_init()]);
+
+        RangeStorage::deallocate(&mut storage, first);
+        RangeStorage::deallocate(&mut storage, second);
+    }
+
+    assert_eq!(secondary.allocated(), secondary.deallocated());
+}
+
+} // mod test_multi_range
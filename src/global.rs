@@ -0,0 +1,142 @@
+//! A process-wide default storage, akin to `#[global_allocator]`.
+//!
+//! Applications may `register` a provider once, typically at start-up, after which all storages of this module forward
+//! their allocations to it. The storages of this module are the default storages of the collections, so that an
+//! application may redirect all collections for which no storage was specified to a pool, or an instrumented
+//! allocator, without threading types everywhere.
+//!
+//! Until a provider is registered, all allocations fail.
+
+use core::{alloc::{Allocator, AllocError, Layout}, cell::UnsafeCell, hint, ptr::NonNull, sync::atomic::{AtomicU8, Ordering}};
+
+use crate::{adapter, allocator};
+
+/// The provider of the process-wide default storage.
+pub type Provider = &'static (dyn Allocator + Sync);
+
+/// Default SingleElementStorage, forwarding to the registered provider.
+pub type SingleElement = allocator::SingleElement<GlobalStorage>;
+
+/// Default SingleRangeStorage, forwarding to the registered provider.
+pub type SingleRange = allocator::SingleRange<GlobalStorage>;
+
+/// Default MultiElementStorage, forwarding to the registered provider.
+pub type MultiElement = allocator::MultiElement<GlobalStorage>;
+
+/// Default MultiRangeStorage, forwarding to the registered provider.
+pub type MultiRange = adapter::Typed<MultiElement>;
+
+/// Registers `provider` as the process-wide default storage provider.
+///
+/// Registration may only occur once, further attempts return the `provider` passed.
+pub fn register(provider: Provider) -> Result<(), Provider> {
+    if REGISTRY.state.compare_exchange(UNREGISTERED, REGISTERING, Ordering::Acquire, Ordering::Relaxed).is_err() {
+        return Err(provider);
+    }
+
+    //  Safety:
+    //  -   Exclusive access, as the state was successfully switched to REGISTERING.
+    unsafe { *REGISTRY.provider.get() = Some(provider) };
+
+    REGISTRY.state.store(REGISTERED, Ordering::Release);
+
+    Ok(())
+}
+
+/// Returns the registered provider, if any.
+pub fn provider() -> Option<Provider> {
+    if REGISTRY.state.load(Ordering::Acquire) != REGISTERED {
+        return None;
+    }
+
+    //  Safety:
+    //  -   The provider is never modified once REGISTERED.
+    unsafe { *REGISTRY.provider.get() }
+}
+
+/// A zero-sized Allocator, forwarding to the registered provider.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlobalStorage;
+
+unsafe impl Allocator for GlobalStorage {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        provider().ok_or(AllocError)?.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        //  Safety:
+        //  -   `ptr` was allocated by the provider, hence it is registered.
+        let provider = provider().unwrap_or_else(|| hint::unreachable_unchecked());
+
+        provider.deallocate(ptr, layout)
+    }
+
+    unsafe fn grow(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        provider().ok_or(AllocError)?.grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        provider().ok_or(AllocError)?.shrink(ptr, old_layout, new_layout)
+    }
+}
+
+//
+//  Implementation
+//
+
+const UNREGISTERED: u8 = 0;
+const REGISTERING: u8 = 1;
+const REGISTERED: u8 = 2;
+
+struct Registry {
+    state: AtomicU8,
+    provider: UnsafeCell<Option<Provider>>,
+}
+
+//  Safety:
+//  -   `provider` is only written once, prior to publishing the REGISTERED state.
+unsafe impl Sync for Registry {}
+
+static REGISTRY: Registry = Registry { state: AtomicU8::new(UNREGISTERED), provider: UnsafeCell::new(None) };
+
+#[cfg(test)]
+mod tests {
+
+use core::sync::atomic::AtomicUsize;
+
+use std::alloc::Global;
+
+use crate::collections::RawVec;
+
+use super::*;
+
+struct CountingAllocator(AtomicUsize);
+
+unsafe impl Allocator for CountingAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) { Global.deallocate(ptr, layout) }
+}
+
+static COUNTING: CountingAllocator = CountingAllocator(AtomicUsize::new(0));
+
+//  Registration being process-wide, this is the one and only test registering a provider.
+#[test]
+fn register_once() {
+    let mut vec: RawVec<u8> = RawVec::default();
+    assert_eq!(Err(1), vec.try_push(1));
+
+    assert!(register(&COUNTING).is_ok());
+    assert!(register(&COUNTING).is_err());
+
+    vec.push(1);
+    vec.push(2);
+
+    assert_eq!([1, 2], &*vec);
+    assert!(COUNTING.0.load(Ordering::Relaxed) > 0);
+}
+
+} // mod tests
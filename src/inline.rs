@@ -1,9 +1,13 @@
 //! Simple implementations of the various inline storages.
 
+mod frame_arena;
 mod multi_element;
+mod obstack;
 mod single_element;
 mod single_range;
 
+pub use frame_arena::{FrameArena, FrameArenaHandle};
 pub use multi_element::{MultiElement, MultiElementHandle};
+pub use obstack::{Obstack, ObstackHandle};
 pub use single_element::SingleElement;
 pub use single_range::SingleRange;
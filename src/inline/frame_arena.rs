@@ -0,0 +1,302 @@
+//! Inline implementation of a double-buffered, frame-scoped, MultiElementStorage.
+
+use core::{
+    fmt::{self, Debug},
+    marker::Unsize,
+    mem::{self, MaybeUninit},
+    num::NonZeroUsize,
+    ops::CoerceUnsized,
+    ptr::{NonNull, Pointee},
+};
+
+use crate::{
+    error::StorageError,
+    traits::{CloneStorage, ElementStorage, MultiElementStorage},
+    utils::{self, OffsetMeta},
+};
+
+/// Generic inline double-buffered arena MultiElementStorage.
+///
+/// Elements are bump-allocated from the arena of the current frame, and deallocating them is a no-op. Calling
+/// `swap_and_clear` ends the current frame: its arena is kept as is, so that the elements allocated during the frame
+/// remain accessible during the next one, while the arena of the previous frame is wholesale reset, and used for the
+/// new frame.
+///
+/// The elements of the arena being reset are not dropped; it is up to the user to destroy them beforehand, if needed.
+///
+/// `S` is the underlying storage of each arena, used to specify the size and alignment.
+pub struct FrameArena<S> {
+    frame: usize,
+    tops: [usize; 2],
+    data: [MaybeUninit<S>; 2],
+}
+
+impl<S> FrameArena<S> {
+    /// Creates an instance of FrameArena.
+    pub fn new() -> Self { Self { frame: 0, tops: [0; 2], data: [MaybeUninit::uninit(), MaybeUninit::uninit()], } }
+
+    /// Returns the number of the current frame, starting from 0.
+    pub fn frame(&self) -> usize { self.frame }
+
+    /// Returns the number of bytes currently in use in the arena of the current frame, including padding.
+    pub fn used(&self) -> usize { self.tops[self.current()] }
+
+    /// Ends the current frame, and starts a new one.
+    ///
+    /// The handles allocated during the current frame remain valid during the new frame, whereas those allocated
+    /// during the previous frame are invalidated.
+    pub fn swap_and_clear(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+
+        let current = self.current();
+        self.tops[current] = 0;
+    }
+}
+
+impl<S> ElementStorage for FrameArena<S> {
+    type Handle<T: ?Sized + Pointee> = FrameArenaHandle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        //  The memory is reclaimed wholesale when the arena is reset.
+        self.check(handle);
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.check(handle);
+
+        let pointer: NonNull<u8> = NonNull::from(&self.data[handle.0 % 2]).cast();
+
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its offset is within its arena.
+        let pointer: NonNull<()> = NonNull::new_unchecked(pointer.as_ptr().add(handle.offset())).cast();
+
+        NonNull::from_raw_parts(pointer, handle.meta())
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        self.check(handle);
+
+        let pointer: NonNull<u8> = NonNull::from(&mut self.data[handle.0 % 2]).cast();
+
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its offset is within its arena.
+        let pointer: NonNull<()> = NonNull::new_unchecked(pointer.as_ptr().add(handle.offset())).cast();
+
+        NonNull::from_raw_parts(pointer, handle.meta())
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid.
+        let element = self.resolve(handle);
+
+        let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
+
+        FrameArenaHandle::new(handle.0, handle.offset(), meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        FrameArenaHandle::new(handle.0, handle.offset(), handle.meta())
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        self.check(handle);
+
+        Ok(FrameArenaHandle::new(handle.0, handle.offset(), meta))
+    }
+}
+
+impl<S> MultiElementStorage for FrameArena<S> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+
+        utils::validate_layout_for::<S>(layout)?;
+
+        let exhausted = || StorageError::exhausted(layout);
+        let current = self.current();
+
+        let offset = round_up(self.tops[current], layout.align()).ok_or_else(exhausted)?;
+        let end = offset.checked_add(layout.size()).ok_or_else(exhausted)?;
+
+        if end > mem::size_of::<S>() {
+            return Err(exhausted());
+        }
+
+        self.tops[current] = end;
+
+        Ok(FrameArenaHandle::new(self.frame, offset, meta))
+    }
+}
+
+impl<S> CloneStorage for FrameArena<S> {
+    fn clone_storage(&self) -> Self { Self::new() }
+}
+
+impl<S> Debug for FrameArena<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "FrameArena{{ frame: {}, used: {} }}", self.frame, self.used())
+    }
+}
+
+impl<S> Default for FrameArena<S> {
+    fn default() -> Self { Self::new() }
+}
+
+/// The Handle for FrameArena.
+///
+/// Records the frame during which the element was allocated, and its offset within the arena of this frame.
+///
+/// The handle coerces implicitly, as per `CoerceUnsized`, and so do the boxes using it.
+pub struct FrameArenaHandle<T: ?Sized + Pointee>(usize, OffsetMeta<T>);
+
+impl<T: ?Sized + Pointee> Clone for FrameArenaHandle<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized + Pointee> Copy for FrameArenaHandle<T> {}
+
+impl<T: ?Sized + Pointee> Debug for FrameArenaHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "FrameArenaHandle{{ frame: {}, offset: {} }}", self.0, self.offset())
+    }
+}
+
+impl<T, U> CoerceUnsized<FrameArenaHandle<U>> for FrameArenaHandle<T>
+    where
+        T: ?Sized + Pointee + Unsize<U>,
+        U: ?Sized + Pointee,
+{
+}
+
+//
+//  Implementation
+//
+
+impl<T: ?Sized + Pointee> FrameArenaHandle<T> {
+    //  The offset is stored off-by-one; it is within the arena, hence cannot saturate.
+    fn new(frame: usize, offset: usize, meta: T::Metadata) -> Self {
+        Self(frame, OffsetMeta::new(NonZeroUsize::MIN.saturating_add(offset), meta))
+    }
+
+    fn offset(&self) -> usize { self.1.offset().get() - 1 }
+
+    fn meta(&self) -> T::Metadata { self.1.meta() }
+}
+
+impl<S> FrameArena<S> {
+    fn current(&self) -> usize { self.frame % 2 }
+
+    fn check<T: ?Sized + Pointee>(&self, handle: FrameArenaHandle<T>) {
+        debug_assert!(handle.0 == self.frame || handle.0.wrapping_add(1) == self.frame,
+            "FrameArena: handle from a cleared frame");
+    }
+}
+
+fn round_up(n: usize, align: usize) -> Option<usize> {
+    debug_assert!(align.is_power_of_two());
+
+    n.checked_add(align - 1).map(|n| n & !(align - 1))
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+
+#[test]
+fn new_unconditional_success() {
+    FrameArena::<u8>::new();
+}
+
+#[test]
+fn create_success() {
+    let mut storage = FrameArena::<[u32; 4]>::new();
+
+    let h1 = storage.create(1u8).unwrap();
+    let h2 = storage.create(2u32).unwrap();
+
+    assert_eq!(8, storage.used());
+    assert_eq!(1, unsafe { *storage.resolve(h1).as_ref() });
+    assert_eq!(2, unsafe { *storage.resolve(h2).as_ref() });
+}
+
+#[test]
+fn create_insufficient_alignment() {
+    let mut storage = FrameArena::<[u8; 16]>::new();
+    storage.create(1u32).unwrap_err();
+}
+
+#[test]
+fn create_insufficient_size() {
+    let mut storage = FrameArena::<[u32; 2]>::new();
+
+    let handle = storage.create(1u8).unwrap();
+    storage.create([1u32, 2]).unwrap_err();
+
+    //  Deallocating does not reclaim memory, only swapping does.
+    unsafe { storage.destroy(handle) };
+    storage.create([1u32, 2]).unwrap_err();
+
+    storage.swap_and_clear();
+    storage.create([1u32, 2]).unwrap();
+}
+
+#[test]
+fn swap_and_clear() {
+    let mut storage = FrameArena::<[u32; 2]>::new();
+
+    let first = storage.create(1u32).unwrap();
+    let _ = storage.create(2u32).unwrap();
+
+    storage.swap_and_clear();
+
+    assert_eq!(1, storage.frame());
+    assert_eq!(0, storage.used());
+
+    //  The elements of the previous frame are still accessible.
+    let second = storage.create(3u32).unwrap();
+
+    assert_eq!(1, unsafe { *storage.resolve(first).as_ref() });
+    assert_eq!(3, unsafe { *storage.resolve(second).as_ref() });
+
+    //  The arena of the first frame is reset and reused.
+    storage.swap_and_clear();
+
+    let third = storage.create(4u32).unwrap();
+    let _ = storage.create(5u32).unwrap();
+
+    assert_eq!(8, storage.used());
+    assert_eq!(3, unsafe { *storage.resolve(second).as_ref() });
+    assert_eq!(4, unsafe { *storage.resolve(third).as_ref() });
+}
+
+#[test]
+fn coerce_unsize() {
+    let mut storage = FrameArena::<[u8; 4]>::new();
+
+    let _ = storage.create(0u8).unwrap();
+    let handle = storage.create([1u8, 2]).unwrap();
+
+    let handle = unsafe { storage.coerce::<[u8], _>(handle) };
+    let element = unsafe { storage.resolve(handle) };
+
+    assert_eq!(&[1, 2], unsafe { element.as_ref() });
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "cleared frame")]
+fn resolve_cleared_frame() {
+    let mut storage = FrameArena::<[u8; 4]>::new();
+
+    let handle = storage.create(1u8).unwrap();
+
+    storage.swap_and_clear();
+    storage.swap_and_clear();
+
+    let _ = unsafe { storage.resolve(handle) };
+}
+
+} // mod tests
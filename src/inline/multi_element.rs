@@ -1,8 +1,20 @@
 //! Inline implementation of MultiElementStorage.
 
-use core::{alloc::AllocError, fmt::{self, Debug}, marker::Unsize, mem::{ManuallyDrop, MaybeUninit}, ptr::{NonNull, Pointee}};
-
-use crate::{traits::{ElementStorage, MultiElementStorage}, utils};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug},
+    marker::Unsize,
+    mem::{ManuallyDrop, MaybeUninit},
+    num::NonZeroUsize,
+    ops::CoerceUnsized,
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::{
+    error::StorageError,
+    traits::{ClearableStorage, CloneStorage, ElementStorage, MultiElementStorage},
+    utils::{self, OffsetMeta},
+};
 
 /// Generic inline MultiElementStorage.
 ///
@@ -17,6 +29,55 @@ impl<S, const N: usize> MultiElement<S, N> {
     pub fn new() -> Self {
         unsafe { Self::default() }
     }
+
+    /// Compacts the storage, moving all live elements to the lowest slots.
+    ///
+    /// For each element moved, `remap` is invoked with the index of its slot before and after the move; its handles
+    /// should be relocated accordingly, see `MultiElementHandle::relocate`. All handles of elements not moved remain
+    /// valid.
+    ///
+    /// The elements are moved bitwise, hence any pointer to them is invalidated.
+    pub fn compact<F: FnMut(usize, usize)>(&mut self, mut remap: F) {
+        let mut free = self.free_slots();
+
+        let mut low = 0;
+        let mut high = N;
+
+        loop {
+            while low < high && !free[low] {
+                low += 1;
+            }
+
+            while low < high && free[high - 1] {
+                high -= 1;
+            }
+
+            if low + 1 >= high {
+                break;
+            }
+
+            high -= 1;
+
+            //  Safety:
+            //  -   `low` and `high` are within range, and distinct.
+            unsafe { ptr::copy_nonoverlapping(&self.data[high], &mut self.data[low], 1) };
+
+            free[low] = false;
+            free[high] = true;
+
+            remap(high, low);
+        }
+
+        //  Rebuild the free list, in ascending order.
+        self.next = INVALID_NEXT;
+
+        for index in (0..N).rev() {
+            if free[index] {
+                self.data[index].next = self.next;
+                self.next = index;
+            }
+        }
+    }
 }
 
 impl<S, const N: usize> ElementStorage for MultiElement<S, N> {
@@ -25,35 +86,35 @@ impl<S, const N: usize> ElementStorage for MultiElement<S, N> {
     unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
         //  Safety:
         //  -   `handle` is assumed to be within range, as part of being valid.
-        let slot = self.data.get_unchecked_mut(handle.0);
+        let slot = self.data.get_unchecked_mut(handle.index());
 
         //  Place slot back in linked-list.
         slot.next = self.next;
-        self.next = handle.0;
+        self.next = handle.index();
     }
 
     unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
         //  Safety:
         //  -   `handle` is assumed to be within range.
-        let slot = self.data.get_unchecked(handle.0);
+        let slot = self.data.get_unchecked(handle.index());
 
         let pointer: NonNull<()> = NonNull::from(&slot.data).cast();
 
         //  Safety:
         //  -   `handle` is assumed to point to a valid element.
-        NonNull::from_raw_parts(pointer, handle.1)
+        NonNull::from_raw_parts(pointer, handle.meta())
     }
 
     unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
         //  Safety:
         //  -   `handle` is assumed to be within range.
-        let slot = self.data.get_unchecked_mut(handle.0);
+        let slot = self.data.get_unchecked_mut(handle.index());
 
         let pointer: NonNull<()> = NonNull::from(&mut slot.data).cast();
 
         //  Safety:
         //  -   `handle` is assumed to point to a valid element.
-        NonNull::from_raw_parts(pointer, handle.1)
+        NonNull::from_raw_parts(pointer, handle.meta())
     }
 
     unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
@@ -63,24 +124,34 @@ impl<S, const N: usize> ElementStorage for MultiElement<S, N> {
 
         let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
 
-        MultiElementHandle(handle.0, meta)
+        MultiElementHandle::new(handle.index(), meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        MultiElementHandle::new(handle.index(), handle.meta())
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        Ok(MultiElementHandle::new(handle.index(), meta))
     }
 }
 
 impl<S, const N: usize> MultiElementStorage for MultiElement<S, N> {
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
         let _ = utils::validate_layout::<T, S>(meta)?;
 
         if self.next == INVALID_NEXT {
-            return Err(AllocError);
+            return Err(StorageError::exhausted(utils::layout_of::<T>(meta)));
         }
 
         //  Pop slot from linked list.
-        let handle = MultiElementHandle(self.next, meta);
+        let handle = MultiElementHandle::new(self.next, meta);
 
         //  Safety:
-        //  -   `handle.0` is within bounds by invariant.
-        let slot = unsafe { self.data.get_unchecked_mut(handle.0) };
+        //  -   `handle.index()` is within bounds by invariant.
+        let slot = unsafe { self.data.get_unchecked_mut(handle.index()) };
 
         //  Safety:
         //  -   By invariant, if pointed it contains the "next" field.
@@ -88,6 +159,61 @@ impl<S, const N: usize> MultiElementStorage for MultiElement<S, N> {
 
         Ok(handle)
     }
+
+    fn allocate_n<'a, T>(&mut self, handles: &'a mut [MaybeUninit<Self::Handle<T>>])
+        -> Result<&'a mut [Self::Handle<T>], StorageError>
+    {
+        utils::validate_layout::<T, S>(())?;
+
+        //  Check that there are sufficient free slots, prior to popping any.
+        let mut next = self.next;
+
+        for _ in 0..handles.len() {
+            if next == INVALID_NEXT {
+                return Err(StorageError::exhausted(Layout::new::<T>()));
+            }
+
+            //  Safety:
+            //  -   `next` is within range, by invariant.
+            //  -   `slot` contains `next`, as it is in the free list.
+            next = unsafe { self.data.get_unchecked(next).next };
+        }
+
+        for handle in handles.iter_mut() {
+            handle.write(MultiElementHandle::new(self.next, ()));
+
+            //  Safety:
+            //  -   `self.next` is within range, and contains `next`, as checked above.
+            self.next = unsafe { self.data.get_unchecked(self.next).next };
+        }
+
+        //  Safety:
+        //  -   All `handles` were initialized.
+        Ok(unsafe { MaybeUninit::slice_assume_init_mut(handles) })
+    }
+}
+
+impl<S, const N: usize> ClearableStorage for MultiElement<S, N> {
+    unsafe fn for_each_handle<T, F: FnMut(&mut Self, Self::Handle<T>)>(&mut self, mut fun: F) {
+        let free = self.free_slots();
+
+        for (index, _) in free.iter().enumerate().filter(|(_, free)| !**free) {
+            fun(self, MultiElementHandle::new(index, ()));
+        }
+    }
+
+    fn reset(&mut self) {
+        self.next = INVALID_NEXT;
+
+        for index in (0..N).rev() {
+            self.data[index].next = self.next;
+            self.next = index;
+        }
+    }
+}
+
+impl<S, const N: usize> CloneStorage for MultiElement<S, N> {
+    fn clone_storage(&self) -> Self { Self::new() }
 }
 
 impl<S, const N: usize> Debug for MultiElement<S, N> {
@@ -119,7 +245,21 @@ impl<S, const N: usize> Default for MultiElement<S, N> {
 }
 
 /// The Handle for MultiElements.
-pub struct MultiElementHandle<T: ?Sized + Pointee>(usize, T::Metadata);
+///
+/// The index is stored off-by-one, so that an `Option` of the handle is no larger than the handle itself.
+///
+/// The handle coerces implicitly, as per `CoerceUnsized`, and so do the boxes using it.
+pub struct MultiElementHandle<T: ?Sized + Pointee>(OffsetMeta<T>);
+
+impl<T: ?Sized + Pointee> MultiElementHandle<T> {
+    /// Returns the index of the slot of the element.
+    pub fn index(&self) -> usize { self.0.offset().get() - 1 }
+
+    /// Returns a handle to the same element, after it was moved to the slot at `index`.
+    ///
+    /// See `MultiElement::compact`.
+    pub fn relocate(self, index: usize) -> Self { Self::new(index, self.meta()) }
+}
 
 impl<T: ?Sized + Pointee> Clone for MultiElementHandle<T> {
     fn clone(&self) -> Self { *self }
@@ -129,16 +269,32 @@ impl<T: ?Sized + Pointee> Copy for MultiElementHandle<T> {}
 
 impl<T: ?Sized + Pointee> Debug for MultiElementHandle<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "MultiElementHandle({})", self.0)
+        write!(f, "MultiElementHandle({})", self.index())
     }
 }
 
+impl<T, U> CoerceUnsized<MultiElementHandle<U>> for MultiElementHandle<T>
+    where
+        T: ?Sized + Pointee + Unsize<U>,
+        U: ?Sized + Pointee,
+{
+}
+
 //
 //  Implementation
 //
 
 const INVALID_NEXT: usize = usize::MAX;
 
+impl<T: ?Sized + Pointee> MultiElementHandle<T> {
+    //  `index` is less than `N`, hence cannot saturate.
+    fn new(index: usize, meta: T::Metadata) -> Self {
+        Self(OffsetMeta::new(NonZeroUsize::MIN.saturating_add(index), meta))
+    }
+
+    fn meta(&self) -> T::Metadata { self.0.meta() }
+}
+
 impl<S, const N: usize> MultiElement<S, N> {
     //  Creates a default instance.
     //
@@ -166,6 +322,25 @@ impl<S, const N: usize> MultiElement<S, N> {
     }
 }
 
+impl<S, const N: usize> MultiElement<S, N> {
+    //  Returns, for each slot, whether it is free.
+    fn free_slots(&self) -> [bool; N] {
+        let mut free = [false; N];
+
+        let mut next = self.next;
+        while next != INVALID_NEXT {
+            free[next] = true;
+
+            //  Safety:
+            //  -   `next` is within range, by invariant.
+            //  -   `slot` contains `next`, as it is in the free list.
+            next = unsafe { self.data.get_unchecked(next).next };
+        }
+
+        free
+    }
+}
+
 union Overlay<S> {
     next: usize,
     data: ManuallyDrop<MaybeUninit<S>>,
@@ -193,6 +368,14 @@ fn new_unconditional_success() {
     MultiElement::<u8, 5>::new();
 }
 
+#[test]
+fn handle_niche() {
+    use core::mem::size_of;
+
+    assert_eq!(size_of::<MultiElementHandle<u32>>(), size_of::<Option<MultiElementHandle<u32>>>());
+    assert_eq!(size_of::<MultiElementHandle<[u32]>>(), size_of::<Option<MultiElementHandle<[u32]>>>());
+}
+
 #[test]
 fn create_success() {
     let mut storage = MultiElement::<u8, 5>::new();
@@ -202,6 +385,21 @@ fn create_success() {
     assert_eq!(4, unsafe { *element.as_ref() });
 }
 
+#[test]
+fn duplicate() {
+    let mut storage = MultiElement::<[u8; 4], 2>::new();
+
+    let original = storage.create([1u8, 2, 3]).unwrap();
+    let original = unsafe { storage.coerce::<[u8], _>(original) };
+
+    let duplicate = unsafe { storage.duplicate(original) }.unwrap();
+
+    assert_eq!(3, unsafe { storage.resolve(duplicate).len() });
+    assert_eq!(&[1, 2, 3], unsafe { storage.resolve(original).as_ref() });
+
+    unsafe { storage.duplicate(original) }.unwrap_err();
+}
+
 #[test]
 fn create_insufficient_alignment() {
     let mut storage = MultiElement::<[u8; 4], 5>::new();
@@ -214,6 +412,25 @@ fn create_insufficient_size() {
     storage.create([1u8, 2, 3]).unwrap_err();
 }
 
+#[test]
+fn allocate_failure_kind() {
+    use crate::error::StorageErrorKind;
+
+    let mut storage = MultiElement::<[u8; 4], 1>::new();
+
+    let error = storage.allocate::<u32>(()).unwrap_err();
+    assert_eq!(StorageErrorKind::UnsupportedAlignment, error.kind());
+    assert_eq!(Some(Layout::new::<u32>()), error.layout());
+
+    let error = storage.allocate::<[u8; 5]>(()).unwrap_err();
+    assert_eq!(StorageErrorKind::TooLarge, error.kind());
+
+    storage.allocate::<[u8; 4]>(()).unwrap();
+
+    let error = storage.allocate::<[u8; 4]>(()).unwrap_err();
+    assert_eq!(StorageErrorKind::Exhausted, error.kind());
+}
+
 #[test]
 fn create_insufficient_capacity() {
     let victim = "Hello, World".to_string();
@@ -255,4 +472,81 @@ fn coerce_unsize() {
     assert_eq!(&[1, 2], unsafe { element.as_ref() });
 }
 
+#[test]
+fn compact() {
+    let mut storage = MultiElement::<u32, 5>::new();
+
+    let handles: Vec<_> = (0..5u32).map(|i| storage.create(i).unwrap()).collect();
+
+    unsafe { storage.destroy(handles[0]) };
+    unsafe { storage.destroy(handles[2]) };
+
+    let mut handles: Vec<_> = vec![handles[1], handles[3], handles[4]];
+
+    storage.compact(|old, new| {
+        for handle in &mut handles {
+            if handle.index() == old {
+                *handle = handle.relocate(new);
+            }
+        }
+    });
+
+    let indexes: Vec<_> = handles.iter().map(|h| h.index()).collect();
+    let values: Vec<_> = handles.iter().map(|h| unsafe { *storage.resolve(*h).as_ref() }).collect();
+
+    assert_eq!(vec![1, 2, 0], indexes);
+    assert_eq!(vec![1, 3, 4], values);
+    assert_eq!("MultiElement{ next: 3 -> 4 -> null }", format!("{:?}", storage));
+}
+
+#[test]
+fn compact_full() {
+    let mut storage = MultiElement::<u32, 2>::new();
+
+    storage.create(1u32).unwrap();
+    storage.create(2u32).unwrap();
+
+    storage.compact(|_, _| panic!("Nothing to move"));
+
+    storage.create(3u32).unwrap_err();
+}
+
+#[test]
+fn allocate_n() {
+    let mut storage = MultiElement::<u32, 3>::new();
+    let mut handles = [MaybeUninit::uninit(); 4];
+
+    storage.allocate_n::<u32>(&mut handles).unwrap_err();
+
+    let handles = storage.allocate_n::<u32>(&mut handles[..3]).unwrap();
+
+    let indexes: Vec<_> = handles.iter().map(|h| h.index()).collect();
+
+    assert_eq!(vec![0, 1, 2], indexes);
+    assert_eq!("MultiElement{ next: null }", format!("{:?}", storage));
+}
+
+#[test]
+fn clear_with() {
+    let mut storage = MultiElement::<String, 4>::new();
+
+    let handles: Vec<_> = (0..4).map(|i| storage.create(i.to_string()).unwrap()).collect();
+
+    unsafe { storage.destroy(handles[1]) };
+
+    let mut count = 0;
+    unsafe { storage.for_each_handle::<String, _>(|_, _| count += 1) };
+
+    assert_eq!(3, count);
+
+    let mut values = Vec::new();
+    unsafe { storage.clear_with(|pointer: NonNull<String>| values.push(ptr::read(pointer.as_ptr()))) };
+
+    assert_eq!(vec!["0", "2", "3"], values);
+
+    for i in 0..4 {
+        storage.create(i).unwrap();
+    }
+}
+
 }
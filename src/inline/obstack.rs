@@ -0,0 +1,298 @@
+//! Inline implementation of a LIFO MultiElementStorage, also known as an obstack.
+
+use core::{
+    fmt::{self, Debug},
+    marker::Unsize,
+    mem::{self, MaybeUninit},
+    num::NonZeroUsize,
+    ops::CoerceUnsized,
+    ptr::{NonNull, Pointee},
+};
+
+use crate::{
+    error::StorageError,
+    traits::{CloneStorage, ElementStorage, MultiElementStorage},
+    utils::{self, OffsetMeta},
+};
+
+/// Generic inline obstack MultiElementStorage.
+///
+/// Elements are bump-allocated from a single inline buffer, and must be deallocated in the reverse order of their
+/// allocation. This discipline is checked in Debug builds, and guarantees that the storage never fragments.
+///
+/// `S` is the underlying storage, used to specify the size and alignment.
+pub struct Obstack<S> {
+    top: usize,
+    data: MaybeUninit<S>,
+}
+
+impl<S> Obstack<S> {
+    /// Creates an instance of Obstack.
+    pub fn new() -> Self { Self { top: 0, data: MaybeUninit::uninit(), } }
+
+    /// Returns the number of bytes currently in use, including padding.
+    pub fn used(&self) -> usize { self.top }
+}
+
+impl<S> ElementStorage for Obstack<S> {
+    type Handle<T: ?Sized + Pointee> = ObstackHandle<T>;
+
+    unsafe fn deallocate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) {
+        debug_assert!(handle.offset() + utils::layout_of::<T>(handle.meta()).size() == self.top,
+            "Obstack: deallocation out of LIFO order");
+
+        self.top = handle.top();
+    }
+
+    unsafe fn resolve<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> NonNull<T> {
+        let pointer: NonNull<u8> = NonNull::from(&self.data).cast();
+
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its offset is within `self.data`.
+        let pointer: NonNull<()> = NonNull::new_unchecked(pointer.as_ptr().add(handle.offset())).cast();
+
+        NonNull::from_raw_parts(pointer, handle.meta())
+    }
+
+    unsafe fn resolve_mut<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>) -> NonNull<T> {
+        let pointer: NonNull<u8> = NonNull::from(&mut self.data).cast();
+
+        //  Safety:
+        //  -   `handle` is assumed to be valid, hence its offset is within `self.data`.
+        let pointer: NonNull<()> = NonNull::new_unchecked(pointer.as_ptr().add(handle.offset())).cast();
+
+        NonNull::from_raw_parts(pointer, handle.meta())
+    }
+
+    unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        //  Safety:
+        //  -   `handle` is assumed to be valid.
+        let element = self.resolve(handle);
+
+        let meta = (element.as_ptr() as *mut U).to_raw_parts().1;
+
+        ObstackHandle::new(handle.top(), meta)
+    }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        ObstackHandle::new(handle.top(), handle.meta())
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        //  Only the top-most element may be shrunk, as the others are followed by more recent elements.
+        if handle.offset() + utils::layout_of::<T>(handle.meta()).size() != self.top {
+            return Err(StorageError::unsupported());
+        }
+
+        let handle = ObstackHandle::new(handle.top(), meta);
+        self.top = handle.offset() + utils::layout_of::<T>(meta).size();
+
+        Ok(handle)
+    }
+}
+
+impl<S> MultiElementStorage for Obstack<S> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let layout = utils::layout_of::<T>(meta);
+
+        utils::validate_layout_for::<S>(layout)?;
+
+        let exhausted = || StorageError::exhausted(layout);
+
+        let offset = round_up(self.top, layout.align()).ok_or_else(exhausted)?;
+        let end = offset.checked_add(layout.size()).ok_or_else(exhausted)?;
+
+        if end > mem::size_of::<S>() {
+            return Err(exhausted());
+        }
+
+        let handle = ObstackHandle::new(self.top, meta);
+        self.top = end;
+
+        Ok(handle)
+    }
+}
+
+impl<S> CloneStorage for Obstack<S> {
+    fn clone_storage(&self) -> Self { Self::new() }
+}
+
+impl<S> Debug for Obstack<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Obstack{{ top: {} }}", self.top)
+    }
+}
+
+impl<S> Default for Obstack<S> {
+    fn default() -> Self { Self::new() }
+}
+
+/// The Handle for Obstack.
+///
+/// Records the top of the obstack prior to the allocation, so that deallocating restores it, padding included.
+///
+/// The handle coerces implicitly, as per `CoerceUnsized`, and so do the boxes using it.
+pub struct ObstackHandle<T: ?Sized + Pointee>(OffsetMeta<T>);
+
+impl<T: ?Sized + Pointee> Clone for ObstackHandle<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized + Pointee> Copy for ObstackHandle<T> {}
+
+impl<T: ?Sized + Pointee> Debug for ObstackHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "ObstackHandle({})", self.top())
+    }
+}
+
+impl<T, U> CoerceUnsized<ObstackHandle<U>> for ObstackHandle<T>
+    where
+        T: ?Sized + Pointee + Unsize<U>,
+        U: ?Sized + Pointee,
+{
+}
+
+//
+//  Implementation
+//
+
+impl<T: ?Sized + Pointee> ObstackHandle<T> {
+    //  The top is stored off-by-one; it is within the obstack, hence cannot saturate.
+    fn new(top: usize, meta: T::Metadata) -> Self {
+        Self(OffsetMeta::new(NonZeroUsize::MIN.saturating_add(top), meta))
+    }
+
+    //  Returns the top of the obstack prior to the allocation.
+    fn top(&self) -> usize { self.0.offset().get() - 1 }
+
+    fn meta(&self) -> T::Metadata { self.0.meta() }
+
+    //  Returns the offset of the element, past any padding.
+    fn offset(&self) -> usize {
+        let align = utils::layout_of::<T>(self.meta()).align();
+
+        //  Overflow was checked on allocation.
+        (self.top() + align - 1) & !(align - 1)
+    }
+}
+
+fn round_up(n: usize, align: usize) -> Option<usize> {
+    debug_assert!(align.is_power_of_two());
+
+    n.checked_add(align - 1).map(|n| n & !(align - 1))
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+
+#[test]
+fn new_unconditional_success() {
+    Obstack::<u8>::new();
+}
+
+#[test]
+fn create_success() {
+    let mut storage = Obstack::<[u32; 4]>::new();
+
+    let h1 = storage.create(1u8).unwrap();
+    let h2 = storage.create(2u32).unwrap();
+
+    assert_eq!(8, storage.used());
+    assert_eq!(1, unsafe { *storage.resolve(h1).as_ref() });
+    assert_eq!(2, unsafe { *storage.resolve(h2).as_ref() });
+
+    unsafe { storage.destroy(h2) };
+    unsafe { storage.destroy(h1) };
+
+    assert_eq!(0, storage.used());
+}
+
+#[test]
+fn create_insufficient_alignment() {
+    let mut storage = Obstack::<[u8; 16]>::new();
+    storage.create(1u32).unwrap_err();
+}
+
+#[test]
+fn create_insufficient_size() {
+    let mut storage = Obstack::<[u32; 2]>::new();
+
+    let handle = storage.create(1u8).unwrap();
+    storage.create([1u32, 2]).unwrap_err();
+
+    unsafe { storage.destroy(handle) };
+
+    storage.create([1u32, 2]).unwrap();
+}
+
+#[test]
+fn update_metadata_top_only() {
+    let mut storage = Obstack::<[u8; 8]>::new();
+
+    let h1 = storage.create([1u8, 2, 3]).unwrap();
+    let h1 = unsafe { storage.coerce::<[u8], _>(h1) };
+
+    let h2 = storage.create([4u8, 5, 6]).unwrap();
+    let h2 = unsafe { storage.coerce::<[u8], _>(h2) };
+
+    unsafe { storage.update_metadata(h1, 1) }.unwrap_err();
+
+    let h2 = unsafe { storage.update_metadata(h2, 1) }.unwrap();
+
+    assert_eq!(4, storage.used());
+    assert_eq!(&[4], unsafe { storage.resolve(h2).as_ref() });
+
+    unsafe { storage.destroy(h2) };
+
+    let h1 = unsafe { storage.update_metadata(h1, 1) }.unwrap();
+
+    assert_eq!(1, storage.used());
+    assert_eq!(&[1], unsafe { storage.resolve(h1).as_ref() });
+}
+
+#[test]
+fn resolve_accross_moves() {
+    let mut storage = Obstack::<[u16; 4]>::new();
+
+    let h1 = storage.create(1u8).unwrap();
+    let h2 = storage.create(2u16).unwrap();
+    let h3 = storage.create(3u8).unwrap();
+
+    let storage = storage;
+
+    assert_eq!(1, unsafe { *storage.resolve(h1).as_ref() });
+    assert_eq!(2, unsafe { *storage.resolve(h2).as_ref() });
+    assert_eq!(3, unsafe { *storage.resolve(h3).as_ref() });
+}
+
+#[test]
+fn coerce_unsize() {
+    let mut storage = Obstack::<[u8; 4]>::new();
+
+    let _ = storage.create(0u8).unwrap();
+    let handle = storage.create([1u8, 2]).unwrap();
+
+    let handle = unsafe { storage.coerce::<[u8], _>(handle) };
+    let element = unsafe { storage.resolve(handle) };
+
+    assert_eq!(&[1, 2], unsafe { element.as_ref() });
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "LIFO order")]
+fn deallocate_out_of_order() {
+    let mut storage = Obstack::<[u8; 4]>::new();
+
+    let h1 = storage.create(1u8).unwrap();
+    let _h2 = storage.create(2u8).unwrap();
+
+    unsafe { storage.destroy(h1) };
+}
+
+} // mod tests
@@ -1,8 +1,8 @@
 //! Simple implementation of `SingleElementStorage<T>`.
 
-use core::{alloc::AllocError, fmt::{self, Debug}, marker::Unsize, mem::MaybeUninit, ptr::{NonNull, Pointee}};
+use core::{fmt::{self, Debug}, marker::Unsize, mem::MaybeUninit, ptr::{NonNull, Pointee}};
 
-use crate::{traits::{ElementStorage, SingleElementStorage}, utils};
+use crate::{error::StorageError, traits::{CloneStorage, ElementStorage, SingleElementStorage}, utils};
 
 /// Generic inline SingleElementStorage.
 ///
@@ -42,16 +42,30 @@ impl<S> ElementStorage for SingleElement<S> {
 
         SingleElementHandle(meta)
     }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        SingleElementHandle(handle.0)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, _: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        Ok(SingleElementHandle(meta))
+    }
 }
 
 impl<S> SingleElementStorage for SingleElement<S> {
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
         let _ = utils::validate_layout::<T, S>(meta)?;
 
         Ok(SingleElementHandle(meta))
     }
 }
 
+impl<S> CloneStorage for SingleElement<S> {
+    fn clone_storage(&self) -> Self { Self::new() }
+}
+
 impl<S> Debug for SingleElement<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleElement")
@@ -106,6 +120,37 @@ fn create_insufficient_alignment() {
     storage.create([1u32]).unwrap_err();
 }
 
+#[test]
+fn create_with_success() {
+    let mut storage = SingleElement::<[u8; 32]>::new();
+
+    let handle = storage.create_with(|| [7u8; 32]).ok().unwrap();
+
+    assert_eq!(&[7u8; 32], unsafe { storage.resolve(handle).as_ref() });
+}
+
+#[test]
+fn create_with_insufficient_size() {
+    let mut storage = SingleElement::<u8>::new();
+
+    let fun = storage.create_with(|| [1u8, 2, 3]).unwrap_err();
+
+    //  `fun` is handed back, uninvoked, for use with another storage.
+    let mut other = SingleElement::<[u8; 4]>::new();
+    let handle = other.create_with(fun).ok().unwrap();
+
+    assert_eq!(&[1, 2, 3], unsafe { other.resolve(handle).as_ref() });
+}
+
+#[test]
+fn create_with_uninit_success() {
+    let mut storage = SingleElement::<[u32; 4]>::new();
+
+    let handle = unsafe { storage.create_with_uninit(|slot| { slot.write([1u32, 2, 3, 4]); }) }.ok().unwrap();
+
+    assert_eq!(&[1, 2, 3, 4], unsafe { storage.resolve(handle).as_ref() });
+}
+
 #[test]
 fn coerce() {
     let mut storage = SingleElement::<[u8; 32]>::new();
@@ -121,4 +166,16 @@ fn coerce() {
     unsafe { storage.destroy(handle) };
 }
 
+#[test]
+fn update_metadata() {
+    let mut storage = SingleElement::<[u8; 32]>::new();
+
+    let handle = storage.create([1u8, 2, 3, 4]).unwrap();
+    let handle = unsafe { storage.coerce::<[u8], _>(handle) };
+
+    let handle = unsafe { storage.update_metadata(handle, 2) }.unwrap();
+
+    assert_eq!(&[1, 2], unsafe { storage.resolve(handle).as_ref() });
+}
+
 } // mod tests
@@ -1,8 +1,8 @@
 //! Simple implementation of `SingleRangeStorage`.
 
-use core::{alloc::AllocError, cmp, fmt::{self, Debug}, marker::PhantomData, mem::{self, MaybeUninit}, ptr::NonNull};
+use core::{cmp, fmt::{self, Debug}, marker::PhantomData, mem::{self, MaybeUninit}, ptr::NonNull};
 
-use crate::{traits::{Capacity, RangeStorage, SingleRangeStorage}, utils};
+use crate::{error::StorageError, traits::{Capacity, CloneStorage, RangeStorage, SingleRangeStorage}, utils};
 
 /// Generic inline SingleRangeStorage.
 ///
@@ -23,14 +23,7 @@ impl<C: Capacity, S, const N: usize> RangeStorage for SingleRange<C, S, N> {
     type Capacity = C;
 
     fn maximum_capacity<T>(&self) -> Self::Capacity {
-        assert!(mem::size_of::<S>().checked_mul(N).is_some());
-
-        //  The maximum capacity cannot exceed what can fit in an `isize`.
-        let capacity = cmp::min(C::max().into_usize(), N);
-
-        C::from_usize(mem::size_of::<S>() * capacity / mem::size_of::<T>())
-            .or_else(|| C::from_usize(capacity))
-            .expect("Cannot fail, since capacity <= C::max()")
+        C::from_usize(Self::capacity::<T>()).expect("Cannot fail, since capacity <= C::max()")
     }
 
     unsafe fn deallocate<T>(&mut self, _handle: Self::Handle<T>) {}
@@ -38,24 +31,43 @@ impl<C: Capacity, S, const N: usize> RangeStorage for SingleRange<C, S, N> {
     unsafe fn resolve<T>(&self, _handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
         let pointer: NonNull<MaybeUninit<T>> = NonNull::from(&self.data).cast();
 
-        NonNull::slice_from_raw_parts(pointer, N)
+        NonNull::slice_from_raw_parts(pointer, Self::capacity::<T>())
     }
 
     unsafe fn resolve_mut<T>(&mut self, _handle: Self::Handle<T>) -> NonNull<[MaybeUninit<T>]> {
         let pointer: NonNull<MaybeUninit<T>> = NonNull::from(&mut self.data).cast();
 
-        NonNull::slice_from_raw_parts(pointer, N)
+        NonNull::slice_from_raw_parts(pointer, Self::capacity::<T>())
+    }
+
+    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        //  The range always spans the whole storage.
+        if new_capacity.into_usize() <= Self::capacity::<T>() {
+            Ok(handle)
+        } else {
+            let layout = utils::array_layout::<T>(new_capacity.into_usize())?;
+
+            Err(StorageError::too_large(layout))
+        }
+    }
+
+    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, _new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        //  The range always spans the whole storage.
+        Ok(handle)
     }
 }
 
 impl<C: Capacity, S, const N: usize> SingleRangeStorage for SingleRange<C, S, N> {
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         utils::validate_array_layout::<T, [MaybeUninit<S>; N]>(capacity.into_usize())
             .map(|_| SingleRangeHandle::new())
-            .map_err(|_| AllocError)
     }
 }
 
+impl<C, S, const N: usize> CloneStorage for SingleRange<C, S, N> {
+    fn clone_storage(&self) -> Self { Self::new() }
+}
+
 impl<C, S, const N: usize> Debug for SingleRange<C, S, N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleRange")
@@ -67,6 +79,19 @@ impl<C, S, const N: usize> Default for SingleRange<C, S, N> {
 }
 
 
+//
+//  Implementation
+//
+
+impl<C: Capacity, S, const N: usize> SingleRange<C, S, N> {
+    //  Returns the number of elements of type `T` fitting within the storage, bounded by the maximum of `C`.
+    fn capacity<T>() -> usize {
+        let capacity = mem::size_of::<[MaybeUninit<S>; N]>().checked_div(mem::size_of::<T>()).unwrap_or(usize::MAX);
+
+        cmp::min(C::max().into_usize(), capacity)
+    }
+}
+
 /// Handle of SingleRange.
 pub struct SingleRangeHandle<T>(PhantomData<fn(T)->T>);
 
@@ -89,6 +114,8 @@ impl<T> Debug for SingleRangeHandle<T> {
 #[cfg(test)]
 mod tests {
 
+use crate::traits::Placement;
+
 use super::*;
 
 #[test]
@@ -114,4 +141,49 @@ fn allocate_insufficient_alignment() {
     storage.allocate::<u32>(1).unwrap_err();
 }
 
+#[test]
+fn allocate_zeroed_success() {
+    let mut storage = SingleRange::<u8, u8, 4>::new();
+
+    let handle = storage.allocate::<u8>(4).unwrap();
+    unsafe { storage.resolve_mut(handle).as_mut().iter_mut().for_each(|e| { e.write(0xff); }) };
+
+    let handle = storage.allocate_zeroed::<u8>(4).unwrap();
+    let range = unsafe { storage.resolve(handle).as_ref() };
+
+    assert!(range.iter().all(|e| unsafe { e.assume_init() } == 0));
+}
+
+#[test]
+fn capacity_depends_on_element_size() {
+    let mut storage = SingleRange::<u8, [u16; 3], 2>::new();
+
+    let bytes = storage.allocate::<u8>(1).unwrap();
+    assert_eq!(12, unsafe { storage.resolve(bytes).len() });
+
+    storage.allocate::<u32>(1).unwrap_err();
+
+    let shorts = storage.allocate::<u16>(1).unwrap();
+    assert_eq!(6, unsafe { storage.resolve(shorts).len() });
+    assert_eq!(6, storage.maximum_capacity::<u16>());
+}
+
+#[test]
+fn try_grow_tracked_in_place() {
+    let mut storage = SingleRange::<u8, u8, 4>::new();
+
+    let handle = storage.allocate::<u8>(2).unwrap();
+    let (_, placement) = unsafe { storage.try_grow_tracked(handle, 3) }.unwrap();
+
+    assert_eq!(Placement::InPlace, placement);
+}
+
+#[test]
+fn capacity_bounded_by_capacity_type() {
+    let storage = SingleRange::<u8, [u8; 300], 1>::new();
+
+    assert_eq!(255, storage.maximum_capacity::<u8>());
+    assert_eq!(1, storage.maximum_capacity::<[u8; 200]>());
+}
+
 } // mod tests
@@ -2,27 +2,38 @@
 
 //  Language Features
 #![feature(coerce_unsized)]
+#![feature(dropck_eyepatch)]
 #![feature(ptr_metadata)]
+#![feature(unboxed_closures)]
 #![feature(unsize)]
 
 //  Library Features
 #![feature(allocator_api)]
+#![feature(fn_traits)]
 #![feature(layout_for_ptr)]
 #![feature(maybe_uninit_slice)]
 #![feature(maybe_uninit_uninit_array)]
 #![feature(nonnull_slice_from_raw_parts)]
 #![feature(slice_ptr_get)]
 #![feature(slice_ptr_len)]
+#![feature(tuple_trait)]
 
 //  Lints
 #![deny(missing_docs)]
 
 //! TODO
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod adapter;
 pub mod allocator;
 pub mod alternative;
+pub mod budget;
 pub mod collections;
+pub mod error;
 pub mod fallback;
+pub mod global;
 pub mod inline;
 pub mod small;
 pub mod traits;
@@ -1,12 +1,13 @@
 //! Small implementation of `SingleElementStorage`.
 
-use core::{alloc::{Allocator, AllocError}, fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
+use core::{alloc::Allocator, fmt::{self, Debug}, marker::Unsize, ptr::{NonNull, Pointee}};
 
 use crate::{
     allocator::{self, AllocatorBuilder},
     alternative::{self, DefaultBuilder},
+    error::StorageError,
     inline,
-    traits::{ElementStorage, SingleElementStorage},
+    traits::{CloneStorage, ElementStorage, SingleElementStorage, SpillingElementStorage},
 };
 
 /// Generic inline SingleElementStorage.
@@ -21,6 +22,11 @@ impl<S: Default, A> SingleElement<S, A> {
     pub fn new(allocator: A) -> Self { Self { inner: Inner::first(Default::default(), AllocatorBuilder(allocator)) } }
 }
 
+impl<S, A> SingleElement<S, A> {
+    /// Returns whether the element lives in the allocator, rather than inline.
+    pub fn spilled(&self) -> bool { !self.inner.is_first() }
+}
+
 impl<S, A: Allocator> ElementStorage for SingleElement<S, A> {
     type Handle<T: ?Sized + Pointee> = <Inner<S, A> as ElementStorage>::Handle<T>;
 
@@ -39,6 +45,16 @@ impl<S, A: Allocator> ElementStorage for SingleElement<S, A> {
     unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
         self.inner.coerce(handle)
     }
+
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U> {
+        self.inner.cast(handle)
+    }
+
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        self.inner.update_metadata(handle, meta)
+    }
 }
 
 impl<S, A: Allocator> SingleElementStorage for SingleElement<S, A> {
@@ -46,11 +62,19 @@ impl<S, A: Allocator> SingleElementStorage for SingleElement<S, A> {
         self.inner.create(value)
     }
 
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
         self.inner.allocate(meta)
     }
 }
 
+impl<S, A: Allocator> SpillingElementStorage for SingleElement<S, A> {
+    unsafe fn is_spilled<T: ?Sized + Pointee>(&self, _: Self::Handle<T>) -> bool { self.spilled() }
+}
+
+impl<S, A: Clone> CloneStorage for SingleElement<S, A> {
+    fn clone_storage(&self) -> Self { Self { inner: self.inner.clone_storage() } }
+}
+
 impl<S, A> Debug for SingleElement<S, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleElement")
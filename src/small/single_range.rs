@@ -1,12 +1,13 @@
 //! Small implementation of `SingleRangeStorage`.
 
-use core::{alloc::{Allocator, AllocError}, fmt::{self, Debug}, mem::MaybeUninit, ptr::NonNull};
+use core::{alloc::Allocator, fmt::{self, Debug}, mem::MaybeUninit, ptr::NonNull};
 
 use crate::{
     allocator::{self, AllocatorBuilder},
     alternative::{self, DefaultBuilder},
+    error::StorageError,
     inline,
-    traits::{RangeStorage, SingleRangeStorage},
+    traits::{CloneStorage, RangeStorage, SingleRangeStorage, SpillingRangeStorage},
 };
 
 /// Generic inline SingleRangeStorage.
@@ -21,6 +22,11 @@ impl<S: Default, A> SingleRange<S, A> {
     pub fn new(allocator: A) -> Self { Self { inner: Inner::first(Default::default(), AllocatorBuilder(allocator)) } }
 }
 
+impl<S, A> SingleRange<S, A> {
+    /// Returns whether the elements live in the allocator, rather than inline.
+    pub fn spilled(&self) -> bool { !self.inner.is_first() }
+}
+
 impl<S, A: Allocator> RangeStorage for SingleRange<S, A> {
     type Handle<T> = <Inner<S, A> as RangeStorage>::Handle<T>;
 
@@ -40,21 +46,33 @@ impl<S, A: Allocator> RangeStorage for SingleRange<S, A> {
         self.inner.resolve_mut(handle)
     }
 
-    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    unsafe fn try_grow<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         self.inner.try_grow(handle, new_capacity)
     }
 
-    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    unsafe fn try_grow_front<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        self.inner.try_grow_front(handle, new_capacity)
+    }
+
+    unsafe fn try_shrink<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         self.inner.try_shrink(handle, new_capacity)
     }
 }
 
 impl<S, A: Allocator> SingleRangeStorage for SingleRange<S, A> {
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
         self.inner.allocate(capacity)
     }
 }
 
+impl<S, A: Allocator> SpillingRangeStorage for SingleRange<S, A> {
+    unsafe fn is_spilled<T>(&self, _: Self::Handle<T>) -> bool { self.spilled() }
+}
+
+impl<S, A: Clone> CloneStorage for SingleRange<S, A> {
+    fn clone_storage(&self) -> Self { Self { inner: self.inner.clone_storage() } }
+}
+
 impl<S, A> Debug for SingleRange<S, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "SingleRange")
@@ -121,4 +139,25 @@ fn allocate_failure() {
     storage.allocate::<String>(1).unwrap_err();
 }
 
+#[test]
+fn try_grow_front_spill() {
+    let allocator = SpyAllocator::default();
+
+    let mut storage = SingleRange::<u8, _>::new(allocator.clone());
+    let handle = storage.allocate::<u8>(1).unwrap();
+
+    unsafe {
+        (*storage.resolve_mut(handle).as_ptr())[0].write(42);
+
+        let handle = storage.try_grow_front(handle, 4).unwrap();
+        let slice = &*storage.resolve(handle).as_ptr();
+
+        assert_eq!(1, allocator.allocated());
+        assert_eq!(4, slice.len());
+        assert_eq!(42, slice[3].assume_init());
+
+        storage.deallocate(handle);
+    }
+}
+
 } // mod tests
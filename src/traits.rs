@@ -1,6 +1,101 @@
 //! The various storages available.
 
-use core::{alloc::AllocError, convert::TryInto, marker::Unsize, mem::MaybeUninit, ptr::{self, NonNull, Pointee}};
+use core::{
+    alloc::Layout,
+    cmp,
+    convert::TryInto,
+    marker::Unsize,
+    mem::{self, MaybeUninit},
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::{error::StorageError, utils};
+
+//
+//  Untyped Storage
+//
+
+/// An untyped storage, storing blocks of bytes.
+///
+/// This is the foundation upon which the typed storages, `ElementStorage` and `RangeStorage`, may be layered, see
+/// `adapter::Typed`, so that adaptors and decorators may be written once against the untyped layer.
+///
+/// The storage may store multiple blocks at any one time.
+pub trait Storage {
+    /// The Handle used to obtain the blocks.
+    type Handle : Clone + Copy;
+
+    /// Allocates a block suitable for `layout`.
+    ///
+    /// This may fail if memory cannot be allocated for it.
+    ///
+    /// #   Safety
+    ///
+    /// -   The Handle obtained is only valid until `self.deallocate` is invoked on it, or one of its copies.
+    /// -   This may relocate all existing blocks, pointers should be re-acquired through their handles.
+    fn allocate(&mut self, layout: Layout) -> Result<Self::Handle, StorageError>;
+
+    /// Deallocates the block associated to `handle`.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `layout` is the layout it was
+    ///     allocated with.
+    /// -   This invalidates `handle`, and all of its copies.
+    unsafe fn deallocate(&mut self, handle: Self::Handle, layout: Layout);
+
+    /// Gets a pointer to the block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   The pointer is only valid as long as the storage is not moved and the `handle` remains valid.
+    unsafe fn resolve(&self, handle: Self::Handle) -> NonNull<u8>;
+
+    /// Gets a pointer to the block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   The pointer is only valid as long as the storage is not moved and the `handle` remains valid.
+    unsafe fn resolve_mut(&mut self, handle: Self::Handle) -> NonNull<u8>;
+
+    /// Attempts to grow the block associated to `handle` to `new_layout`, preserving its bytes.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
+    ///
+    /// By default, allocates a new block, copies the bytes over, and deallocates the old block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `old_layout` is the layout it was
+    ///     allocated with.
+    unsafe fn try_grow(&mut self, handle: Self::Handle, old_layout: Layout, new_layout: Layout)
+        -> Result<Self::Handle, StorageError>
+    {
+        debug_assert!(old_layout.size() <= new_layout.size());
+
+        reallocate(self, handle, old_layout, new_layout)
+    }
+
+    /// Attempts to shrink the block associated to `handle` to `new_layout`, preserving its leading bytes.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
+    ///
+    /// By default, allocates a new block, copies the bytes over, and deallocates the old block.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, was issued by this instance, and that `old_layout` is the layout it was
+    ///     allocated with.
+    unsafe fn try_shrink(&mut self, handle: Self::Handle, old_layout: Layout, new_layout: Layout)
+        -> Result<Self::Handle, StorageError>
+    {
+        debug_assert!(old_layout.size() >= new_layout.size());
+
+        reallocate(self, handle, old_layout, new_layout)
+    }
+}
 
 //
 //  Element Storage
@@ -67,6 +162,31 @@ pub trait ElementStorage {
     /// -   Assumes that `handle` is valid, and was issued by this instance.
     unsafe fn coerce<U: ?Sized + Pointee, T: ?Sized + Pointee + Unsize<U>>(&self, handle: Self::Handle<T>) -> Self::Handle<U>;
 
+    /// Casts the type of the handle, to a type sharing the same meta-data.
+    ///
+    /// This is typically used to turn a `Handle<MaybeUninit<T>>` into a `Handle<T>`, once the element is initialized.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   Assumes that `U` has the same layout as `T`, for the meta-data of `handle`.
+    unsafe fn cast<U: ?Sized + Pointee<Metadata = T::Metadata>, T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> Self::Handle<U>;
+
+    /// Attempts to replace the meta-data of the element with `meta`, shrinking it.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated; only the leading bytes of the
+    /// element, as per its new layout, are preserved. If the attempt fails, `handle` remains valid.
+    ///
+    /// This is typically used to shorten a slice, such as turning a `Handle<[T]>` of 8 elements into one of 3.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   Assumes that the layout of `meta` is as aligned as, and no larger than, the layout of the element.
+    /// -   The trailing bytes are discarded, hence any value they contain should be dropped beforehand.
+    /// -   The element may be moved, hence any pointer to it should be re-acquired through the new handle.
+    unsafe fn update_metadata<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>, meta: T::Metadata)
+        -> Result<Self::Handle<T>, StorageError>;
 }
 
 /// A single element storage.
@@ -94,12 +214,119 @@ pub trait SingleElementStorage : ElementStorage {
         }
     }
 
+    /// Stores a `value` within the storage, and coerces its handle to `U`.
+    ///
+    /// If a value is already stored, it is overwritten and `drop` is not executed.
+    fn create_unsize<U: ?Sized + Pointee, T: Pointee + Unsize<U>>(&mut self, value: T) -> Result<Self::Handle<U>, T> {
+        let handle = self.create(value)?;
+
+        //  Safety:
+        //  -   `handle` is valid, and was issued by `self`.
+        Ok(unsafe { self.coerce::<U, _>(handle) })
+    }
+
+    /// Stores the value produced by `fun` within the storage.
+    ///
+    /// Unlike `create`, the value is only produced once memory was allocated for it, giving the optimizer a chance to
+    /// construct it in place rather than on the stack. On failure, `fun` is returned, uninvoked.
+    ///
+    /// If a value is already stored, it is overwritten and `drop` is not executed.
+    fn create_with<T, F: FnOnce() -> T>(&mut self, fun: F) -> Result<Self::Handle<T>, F> {
+        if let Ok(handle) = self.allocate::<T>(()) {
+            //  Should `fun` panic, `handle` is deallocated.
+            let guard = DeallocateGuard { storage: self, handle };
+
+            //  Safety:
+            //  -   `handle` is valid.
+            let pointer = unsafe { guard.storage.resolve_mut(handle) };
+
+            //  Safety:
+            //  -   `pointer` points to a suitable memory area for `T`.
+            unsafe { ptr::write(pointer.as_ptr(), fun()) };
+
+            mem::forget(guard);
+
+            Ok(handle)
+        } else {
+            Err(fun)
+        }
+    }
+
+    /// Stores the value initialized by `fun` within the storage.
+    ///
+    /// `fun` is handed the memory area of the value, guaranteeing that it is constructed in place. On failure, `fun`
+    /// is returned, uninvoked.
+    ///
+    /// If a value is already stored, it is overwritten and `drop` is not executed.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `fun` fully initializes the value, unless it panics.
+    unsafe fn create_with_uninit<T, F: FnOnce(&mut MaybeUninit<T>)>(&mut self, fun: F) -> Result<Self::Handle<T>, F> {
+        if let Ok(handle) = self.allocate::<T>(()) {
+            //  Should `fun` panic, `handle` is deallocated.
+            let guard = DeallocateGuard { storage: self, handle };
+
+            //  Safety:
+            //  -   `handle` is valid.
+            let pointer = guard.storage.resolve_mut(handle);
+
+            //  Safety:
+            //  -   `pointer` points to a suitable memory area for `T`, which `MaybeUninit<T>` shares the layout of.
+            fun(&mut *(pointer.as_ptr() as *mut MaybeUninit<T>));
+
+            mem::forget(guard);
+
+            Ok(handle)
+        } else {
+            Err(fun)
+        }
+    }
+
     /// Attempts to allocate memory, and returns a handle to it.
     ///
     /// This may fail if memory cannot be allocated for it.
     ///
     /// If a value is already stored, the memory area may overlap.
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError>;
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError>;
+
+    /// Attempts to allocate zeroed memory, and returns a handle to it.
+    ///
+    /// This may fail if memory cannot be allocated for it.
+    fn allocate_zeroed<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let handle = self.allocate::<T>(meta)?;
+
+        //  Safety:
+        //  -   `handle` is valid.
+        let pointer = unsafe { self.resolve_mut(handle) };
+
+        //  Safety:
+        //  -   `pointer` points to a memory area suitable for `T`.
+        unsafe { ptr::write_bytes(pointer.as_ptr() as *mut u8, 0, utils::layout_of::<T>(meta).size()) };
+
+        Ok(handle)
+    }
+
+    /// Attempts to move the element of `handle` from `source` into this storage, and returns its new handle.
+    ///
+    /// On success, the element is moved bitwise and `handle` is deallocated. On failure, `source` and `handle` are left
+    /// untouched, and the element remains within `source`.
+    ///
+    /// If a value is already stored, it is overwritten and `drop` is not executed.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by `source`.
+    unsafe fn transfer_from<T: ?Sized + Pointee, S: ElementStorage>(&mut self, source: &mut S, handle: S::Handle<T>)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let meta = source.resolve(handle).to_raw_parts().1;
+        let new_handle = self.allocate::<T>(meta)?;
+
+        relocate(source, handle, self, new_handle);
+
+        Ok(new_handle)
+    }
 }
 
 /// A multi elements storage.
@@ -136,7 +363,93 @@ pub trait MultiElementStorage : ElementStorage{
     /// Allocates memory, and returns a handle to it.
     ///
     /// This may fail if memory cannot be allocated for it.
-    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, AllocError>;
+    fn allocate<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError>;
+
+    /// Attempts to allocate zeroed memory, and returns a handle to it.
+    ///
+    /// This may fail if memory cannot be allocated for it.
+    fn allocate_zeroed<T: ?Sized + Pointee>(&mut self, meta: T::Metadata) -> Result<Self::Handle<T>, StorageError> {
+        let handle = self.allocate::<T>(meta)?;
+
+        //  Safety:
+        //  -   `handle` is valid.
+        let pointer = unsafe { self.resolve_mut(handle) };
+
+        //  Safety:
+        //  -   `pointer` points to a memory area suitable for `T`.
+        unsafe { ptr::write_bytes(pointer.as_ptr() as *mut u8, 0, utils::layout_of::<T>(meta).size()) };
+
+        Ok(handle)
+    }
+
+    /// Attempts to allocate memory for as many elements as `handles` has room for, storing their handles in it.
+    ///
+    /// On success, the initialized `handles` are returned. On failure, no memory is allocated.
+    ///
+    /// Storages are encouraged to override the default implementation, which allocates one element at a time, so as to
+    /// reserve all elements in a single pass.
+    ///
+    /// #   Safety
+    ///
+    /// -   This may relocate all existing elements, pointers should be re-acquired through their handles.
+    fn allocate_n<'a, T>(&mut self, handles: &'a mut [MaybeUninit<Self::Handle<T>>])
+        -> Result<&'a mut [Self::Handle<T>], StorageError>
+    {
+        for index in 0..handles.len() {
+            match self.allocate::<T>(()) {
+                Ok(handle) => { handles[index].write(handle); },
+                Err(error) => {
+                    for handle in &handles[..index] {
+                        //  Safety:
+                        //  -   `handle` was initialized, and is valid, having been allocated above.
+                        unsafe { self.deallocate(handle.assume_init_read()) };
+                    }
+
+                    return Err(error);
+                },
+            }
+        }
+
+        //  Safety:
+        //  -   All `handles` were initialized.
+        Ok(unsafe { MaybeUninit::slice_assume_init_mut(handles) })
+    }
+
+    /// Allocates memory for a new element, with the same meta-data as the element of `handle`.
+    ///
+    /// This is typically used to clone the element of `handle`, which is left untouched.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    /// -   This may relocate all existing elements, pointers should be re-acquired through their handles.
+    unsafe fn duplicate<T: ?Sized + Pointee>(&mut self, handle: Self::Handle<T>)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let meta = self.resolve(handle).to_raw_parts().1;
+
+        self.allocate::<T>(meta)
+    }
+
+    /// Attempts to move the element of `handle` from `source` into this storage, and returns its new handle.
+    ///
+    /// On success, the element is moved bitwise and `handle` is deallocated. On failure, `source` and `handle` are left
+    /// untouched, and the element remains within `source`.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by `source`.
+    /// -   This may relocate all existing elements, pointers should be re-acquired through their handles.
+    unsafe fn transfer_from<T: ?Sized + Pointee, S: ElementStorage>(&mut self, source: &mut S, handle: S::Handle<T>)
+        -> Result<Self::Handle<T>, StorageError>
+    {
+        let meta = source.resolve(handle).to_raw_parts().1;
+        let new_handle = self.allocate::<T>(meta)?;
+
+        relocate(source, handle, self, new_handle);
+
+        Ok(new_handle)
+    }
 }
 
 //
@@ -153,6 +466,30 @@ pub trait Capacity : Sized + Clone + Copy {
 
     /// Convert back to usize.
     fn into_usize(self) -> usize;
+
+    /// Returns `self + other`, or None if not representable.
+    fn checked_add(self, other: Self) -> Option<Self> {
+        self.into_usize().checked_add(other.into_usize()).and_then(Self::from_usize)
+    }
+
+    /// Returns `self * other`, or None if not representable.
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        self.into_usize().checked_mul(other.into_usize()).and_then(Self::from_usize)
+    }
+
+    /// Returns `self * 2`, saturating at `Self::max()`.
+    fn saturating_double(self) -> Self {
+        self.into_usize().checked_mul(2).and_then(Self::from_usize).unwrap_or_else(Self::max)
+    }
+}
+
+/// Where a range is located, after being resized.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Placement {
+    /// The range was resized in place, its elements were not moved.
+    InPlace,
+    /// The range was relocated, its elements were moved bitwise.
+    Relocated,
 }
 
 /// A storage for (contigous) ranges of elements.
@@ -161,6 +498,10 @@ pub trait Capacity : Sized + Clone + Copy {
 ///
 /// -   `SingleRangeStorage`, which stores up to one single range at any one time.
 /// -   `MultiRangeStorage`, which may store multiple ranges at any one time.
+///
+/// A storage may grant a greater capacity than requested when allocating, growing, or shrinking a range, for example
+/// because an allocator rounded up the size of the memory block: the capacity actually granted is the length of the
+/// range obtained by `resolve`, which collections should consult rather than assume the requested capacity.
 pub trait RangeStorage {
     /// The Handle used to obtain the range.
     type Handle<T> : Clone + Copy;
@@ -205,15 +546,66 @@ pub trait RangeStorage {
     /// Attempts to grow the internal storage to accomodate at least `new_capacity` elements in total.
     ///
     /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
-    unsafe fn try_grow<T>(&mut self, _handle: Self::Handle<T>, _new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
-        Err(AllocError)
+    unsafe fn try_grow<T>(&mut self, _handle: Self::Handle<T>, _new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        Err(StorageError::unsupported())
+    }
+
+    /// Attempts to grow the internal storage to accomodate at least `new_capacity` elements in total, adding the extra
+    /// capacity at the front of the range.
+    ///
+    /// If the attempt succeeds, a new handle is returned and `handle` is invalidated. The elements of the range are
+    /// moved to its back: the element previously at index `i` is now at index `i + new_len - old_len`, where `new_len`
+    /// and `old_len` are the lengths of the new and old ranges respectively.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    unsafe fn try_grow_front<T>(&mut self, _handle: Self::Handle<T>, _new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        Err(StorageError::unsupported())
     }
 
     /// Attempts to shrink the internal storage to accomodate at least `new_capacity` elements in total.
     ///
     /// If the attempt succeeds, a new handle is returned and `handle` is invalidated.
-    unsafe fn try_shrink<T>(&mut self, _handle: Self::Handle<T>, _new_capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError> {
-        Err(AllocError)
+    unsafe fn try_shrink<T>(&mut self, _handle: Self::Handle<T>, _new_capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        Err(StorageError::unsupported())
+    }
+
+    /// Attempts to grow the internal storage to accomodate at least `new_capacity` elements in total, see `try_grow`.
+    ///
+    /// If the attempt succeeds, a new handle is returned alongside whether the range was relocated, and `handle` is
+    /// invalidated.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    unsafe fn try_grow_tracked<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity)
+        -> Result<(Self::Handle<T>, Placement), StorageError>
+    {
+        let before = self.resolve(handle).cast::<u8>();
+
+        let handle = self.try_grow(handle, new_capacity)?;
+
+        Ok((handle, placement(before, self.resolve(handle).cast())))
+    }
+
+    /// Attempts to shrink the internal storage to accomodate at least `new_capacity` elements in total, see
+    /// `try_shrink`.
+    ///
+    /// If the attempt succeeds, a new handle is returned alongside whether the range was relocated, and `handle` is
+    /// invalidated.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    unsafe fn try_shrink_tracked<T>(&mut self, handle: Self::Handle<T>, new_capacity: Self::Capacity)
+        -> Result<(Self::Handle<T>, Placement), StorageError>
+    {
+        let before = self.resolve(handle).cast::<u8>();
+
+        let handle = self.try_shrink(handle, new_capacity)?;
+
+        Ok((handle, placement(before, self.resolve(handle).cast())))
     }
 }
 
@@ -224,7 +616,24 @@ pub trait SingleRangeStorage : RangeStorage {
     /// Allocates memory for a new `Handle`, large enough to at least accomodate the required `capacity`.
     ///
     /// Does not `deallocate` the current handles, nor drop their content. It merely invalidates them.
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError>;
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError>;
+
+    /// Allocates zeroed memory for a new `Handle`, large enough to at least accomodate the required `capacity`.
+    ///
+    /// Does not `deallocate` the current handles, nor drop their content. It merely invalidates them.
+    fn allocate_zeroed<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError> {
+        let handle = self.allocate::<T>(capacity)?;
+
+        //  Safety:
+        //  -   `handle` is valid.
+        let range = unsafe { self.resolve_mut(handle) };
+
+        //  Safety:
+        //  -   `range` points to a memory area suitable for `range.len()` elements.
+        unsafe { ptr::write_bytes(range.as_mut_ptr(), 0, range.len()) };
+
+        Ok(handle)
+    }
 }
 
 /// A multi elements storage.
@@ -240,10 +649,108 @@ pub trait MultiRangeStorage : RangeStorage{
     /// -   The Handle obtained is only valid until `self.destroy` or `self.deallocate` is invoked on it, or one of its
     ///     copies.
     /// -   This may relocate all existing ranges, which should be re-acquired through their handles.
-    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, AllocError>;
+    fn allocate<T>(&mut self, capacity: Self::Capacity) -> Result<Self::Handle<T>, StorageError>;
 }
 
 
+//
+//  Clearing
+//
+
+/// A multi elements storage which keeps track of its allocated elements, and may release all of them at once.
+///
+/// Arena-style users may thus drop all the elements at once, rather than tracking every handle.
+pub trait ClearableStorage : MultiElementStorage {
+    /// Invokes `fun` with the handle of each currently allocated element, in unspecified order.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that all currently allocated elements are of type `T`.
+    /// -   Assumes that `fun` neither allocates nor deallocates.
+    unsafe fn for_each_handle<T, F: FnMut(&mut Self, Self::Handle<T>)>(&mut self, fun: F);
+
+    /// Deallocates all elements at once, without dropping them.
+    ///
+    /// All handles are invalidated.
+    fn reset(&mut self);
+
+    /// Invokes `fun` with a pointer to each currently allocated element, in unspecified order, then deallocates all
+    /// of them at once.
+    ///
+    /// All handles are invalidated.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that all currently allocated elements are of type `T`.
+    unsafe fn clear_with<T, F: FnMut(NonNull<T>)>(&mut self, mut fun: F) {
+        self.for_each_handle::<T, _>(|this, handle| fun(this.resolve_mut(handle)));
+        self.reset();
+    }
+}
+
+
+//
+//  Cloning
+//
+
+/// A storage which may be cloned, without its elements or ranges.
+///
+/// Inline storages are cloned trivially, whereas allocator-backed storages clone their allocator. The elements and
+/// ranges are not cloned along, it is up to the collections to allocate them anew within the clone, and clone their
+/// values into them.
+pub trait CloneStorage : Sized {
+    /// Returns a new storage, with no element or range, and otherwise configured as `self` is.
+    fn clone_storage(&self) -> Self;
+}
+
+
+//
+//  Spilling
+//
+
+/// An element storage which allocates from a first storage, typically inline, and spills into a second one otherwise.
+///
+/// Examples include `small::SingleElement`, `alternative::SingleElement` and `fallback::Fallback`.
+pub trait SpillingElementStorage : ElementStorage {
+    /// Returns whether the element of `handle` lives in the second storage, rather than the first.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    unsafe fn is_spilled<T: ?Sized + Pointee>(&self, handle: Self::Handle<T>) -> bool;
+}
+
+/// A range storage which allocates from a first storage, typically inline, and spills into a second one otherwise.
+///
+/// Examples include `small::SingleRange`, `alternative::SingleRange` and `fallback::Fallback`.
+pub trait SpillingRangeStorage : RangeStorage {
+    /// Returns whether the range of `handle` lives in the second storage, rather than the first.
+    ///
+    /// #   Safety
+    ///
+    /// -   Assumes that `handle` is valid, and was issued by this instance.
+    unsafe fn is_spilled<T>(&self, handle: Self::Handle<T>) -> bool;
+}
+
+
+//
+//  Markers
+//
+
+/// A storage whose elements and ranges do not move when the storage itself is moved.
+///
+/// This allows collections to offer `Pin`-based APIs, or to cache the pointers obtained by resolving their handles,
+/// when the storage opts in.
+///
+/// Allocator-backed storages are typically pinning, whereas inline storages are not.
+///
+/// #   Safety
+///
+/// -   The pointers obtained by `resolve` and `resolve_mut` must remain valid when the storage is moved, for as long
+///     as the handle they were obtained from remains valid, and the storage is not otherwise mutated.
+pub unsafe trait PinningStorage {}
+
+
 //
 //  Implementations of Capacity.
 //
@@ -280,3 +787,173 @@ impl Capacity for u32 {
 
     fn into_usize(self)-> usize { self as usize }
 }
+
+#[cfg(target_pointer_width = "64")]
+impl Capacity for u64 {
+    fn max() -> Self { u64::MAX }
+
+    fn from_usize(capacity: usize) -> Option<Self> { capacity.try_into().ok() }
+
+    fn into_usize(self) -> usize { self as usize }
+}
+
+
+//
+//  Implementation
+//
+
+//  Returns the placement of a range which was at `before`, and is now at `after`.
+fn placement(before: NonNull<u8>, after: NonNull<u8>) -> Placement {
+    if before == after { Placement::InPlace } else { Placement::Relocated }
+}
+
+//  Reallocates the block of `handle` into a new block of `new_layout`, copying over as many bytes as fit.
+unsafe fn reallocate<S: Storage + ?Sized>(storage: &mut S, handle: S::Handle, old_layout: Layout, new_layout: Layout)
+    -> Result<S::Handle, StorageError>
+{
+    let new_handle = storage.allocate(new_layout)?;
+
+    //  Safety:
+    //  -   `handle` and `new_handle` are both valid, and designate distinct blocks.
+    //  -   Both blocks are valid for at least the smaller of their sizes.
+    let (from, to) = (storage.resolve(handle), storage.resolve_mut(new_handle));
+    ptr::copy_nonoverlapping(from.as_ptr(), to.as_ptr(), cmp::min(old_layout.size(), new_layout.size()));
+
+    storage.deallocate(handle, old_layout);
+
+    Ok(new_handle)
+}
+
+//  Moves the element of `handle` from `source` into the block of `new_handle` in `destination`, then deallocates `handle`.
+//
+//  #   Safety
+//
+//  -   Assumes that `handle` is valid, and was issued by `source`.
+//  -   Assumes that `new_handle` is valid, was issued by `destination`, and has the same meta-data as `handle`.
+unsafe fn relocate<T, S, D>(source: &mut S, handle: S::Handle<T>, destination: &mut D, new_handle: D::Handle<T>)
+where
+    T: ?Sized + Pointee,
+    S: ElementStorage,
+    D: ElementStorage + ?Sized,
+{
+    let (from, meta) = source.resolve(handle).to_raw_parts();
+    let to = destination.resolve_mut(new_handle);
+
+    //  Safety:
+    //  -   Both blocks are valid for the layout of the element, as they share its meta-data.
+    //  -   The blocks are distinct, as they belong to distinct storages.
+    ptr::copy_nonoverlapping(from.as_ptr() as *const u8, to.as_ptr() as *mut u8, utils::layout_of::<T>(meta).size());
+
+    source.deallocate(handle);
+}
+
+//  Deallocates `handle` on drop, unless forgotten; guards the allocation against a panicking initializer.
+struct DeallocateGuard<'a, T: ?Sized + Pointee, S: ElementStorage + ?Sized> {
+    storage: &'a mut S,
+    handle: S::Handle<T>,
+}
+
+impl<'a, T: ?Sized + Pointee, S: ElementStorage + ?Sized> Drop for DeallocateGuard<'a, T, S> {
+    fn drop(&mut self) {
+        //  Safety:
+        //  -   `self.handle` is valid, and its element was never initialized.
+        unsafe { self.storage.deallocate(self.handle) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use crate::{allocator, inline};
+use crate::utils::SpyAllocator;
+
+use super::*;
+
+#[test]
+fn transfer_from() {
+    let allocator = SpyAllocator::default();
+
+    let mut source = allocator::MultiElement::new(allocator.clone());
+    let mut destination = inline::SingleElement::<[String; 2]>::new();
+
+    let handle = MultiElementStorage::create(&mut source, ["1".to_string(), "2".to_string()]).unwrap();
+    let handle = unsafe { source.coerce::<[String], _>(handle) };
+
+    let handle = unsafe { SingleElementStorage::transfer_from(&mut destination, &mut source, handle).unwrap() };
+
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+
+    let handle = unsafe { MultiElementStorage::transfer_from(&mut source, &mut destination, handle).unwrap() };
+
+    assert_eq!(2, allocator.allocated());
+    assert_eq!(["1".to_string(), "2".to_string()], unsafe { &*ElementStorage::resolve(&source, handle).as_ptr() });
+
+    unsafe { source.destroy(handle) };
+
+    assert_eq!(2, allocator.deallocated());
+}
+
+#[test]
+fn transfer_from_failure() {
+    let mut source = inline::MultiElement::<u32, 2>::new();
+    let mut destination = inline::SingleElement::<u8>::new();
+
+    let handle = MultiElementStorage::create(&mut source, 42u32).unwrap();
+
+    unsafe {
+        SingleElementStorage::transfer_from(&mut destination, &mut source, handle).unwrap_err();
+
+        assert_eq!(42, *source.resolve(handle).as_ptr());
+    }
+}
+
+#[test]
+fn create_with_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let allocator = SpyAllocator::default();
+    let mut storage = allocator::SingleElement::new(allocator.clone());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| storage.create_with::<String, _>(|| panic!("Oops"))));
+
+    assert!(result.is_err());
+    assert_eq!(1, allocator.allocated());
+    assert_eq!(1, allocator.deallocated());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+        storage.create_with_uninit::<String, _>(|_| panic!("Oops"))
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(2, allocator.allocated());
+    assert_eq!(2, allocator.deallocated());
+}
+
+#[test]
+fn capacity_checked_add() {
+    assert_eq!(Some(255u8), Capacity::checked_add(200u8, 55));
+    assert_eq!(None, Capacity::checked_add(200u8, 56));
+}
+
+#[test]
+fn capacity_checked_mul() {
+    assert_eq!(Some(65_535u16), Capacity::checked_mul(13_107u16, 5));
+    assert_eq!(None, Capacity::checked_mul(13_108u16, 5));
+}
+
+#[test]
+fn capacity_saturating_double() {
+    assert_eq!(254, Capacity::saturating_double(127u8));
+    assert_eq!(255, Capacity::saturating_double(128u8));
+    assert_eq!(usize::MAX, Capacity::saturating_double(usize::MAX / 2 + 1));
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn capacity_u64() {
+    assert_eq!(Some(42u64), u64::from_usize(42));
+    assert_eq!(usize::MAX, u64::MAX.into_usize());
+}
+
+} // mod tests
@@ -1,6 +1,16 @@
 //! Various utilities.
 
-use core::{alloc::{AllocError, Layout}, fmt::{self, Debug}, marker::PhantomData, mem, ptr::{self, Pointee}};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug},
+    marker::{PhantomData, Unsize},
+    mem,
+    num::NonZeroUsize,
+    ops::CoerceUnsized,
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::error::StorageError;
 
 #[cfg(test)]
 pub(crate) use test::*;
@@ -18,6 +28,49 @@ impl<T: ?Sized> Default for PhantomInvariant<T> {
     fn default() -> Self { Self(PhantomData) }
 }
 
+/// A non-zero offset, or index, alongside the metadata of `T`.
+///
+/// Both are packed in a pointer to `T`, which is never dereferenced, so that the handles embedding it coerce
+/// implicitly, as per `CoerceUnsized`, while being no larger than an offset and the metadata.
+pub struct OffsetMeta<T: ?Sized>(NonNull<T>);
+
+impl<T: ?Sized + Pointee> OffsetMeta<T> {
+    /// Creates an instance from `offset` and `meta`.
+    pub fn new(offset: NonZeroUsize, meta: T::Metadata) -> Self {
+        let address: NonNull<()> = NonNull::without_provenance(offset);
+
+        Self(NonNull::from_raw_parts(address, meta))
+    }
+
+    /// Returns the offset.
+    pub fn offset(&self) -> NonZeroUsize { self.0.addr() }
+
+    /// Returns the metadata.
+    pub fn meta(&self) -> T::Metadata { self.0.to_raw_parts().1 }
+}
+
+impl<T: ?Sized> Clone for OffsetMeta<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T: ?Sized> Copy for OffsetMeta<T> {}
+
+impl<T: ?Sized + Pointee> Debug for OffsetMeta<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "OffsetMeta({})", self.offset())
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<OffsetMeta<U>> for OffsetMeta<T> {}
+
+//  Safety:
+//  -   The pointer is never dereferenced, only an offset and a metadata are stored.
+unsafe impl<T: ?Sized> Send for OffsetMeta<T> {}
+
+//  Safety:
+//  -   The pointer is never dereferenced, only an offset and a metadata are stored.
+unsafe impl<T: ?Sized> Sync for OffsetMeta<T> {}
+
 /// Computes the layout for a value with metadata `meta`.
 pub fn layout_of<T: ?Sized + Pointee>(meta: T::Metadata) -> Layout {
     let pointer: *const T = ptr::from_raw_parts(ptr::null_mut(), meta);
@@ -30,31 +83,37 @@ pub fn layout_of<T: ?Sized + Pointee>(meta: T::Metadata) -> Layout {
 /// Validates that the layout of `storage` is sufficient to accomodate an instance of `T`.
 ///
 /// Return `Ok` on success, and `Err` on failure.
-pub fn validate_layout<T: ?Sized + Pointee, Storage>(meta: T::Metadata) -> Result<(), AllocError> {
+pub fn validate_layout<T: ?Sized + Pointee, Storage>(meta: T::Metadata) -> Result<(), StorageError> {
     validate_layout_for::<Storage>(layout_of::<T>(meta))
 }
 
 /// Validates that the layout of `storage` is sufficient to accomodate an instance of `T`.
 ///
 /// Return `Ok` on success, and `Err` on failure.
-pub fn validate_array_layout<T, Storage>(capacity: usize) -> Result<(), AllocError> {
-    validate_layout_for::<Storage>(Layout::array::<T>(capacity).map_err(|_| AllocError)?)
+pub fn validate_array_layout<T, Storage>(capacity: usize) -> Result<(), StorageError> {
+    validate_layout_for::<Storage>(array_layout::<T>(capacity)?)
 }
 
 /// Validates that the layout of `storage` is sufficient for `layout`.
 ///
 /// Return `Ok` on success, and `Err` on failure.
-pub fn validate_layout_for<Storage>(layout: Layout) -> Result<(), AllocError> {
-    let validated_size = layout.size() <= mem::size_of::<Storage>();
-    let validated_alignment = layout.align() <= mem::align_of::<Storage>();
-
-    if validated_size && validated_alignment {
-        Ok(())
+pub fn validate_layout_for<Storage>(layout: Layout) -> Result<(), StorageError> {
+    if layout.align() > mem::align_of::<Storage>() {
+        Err(StorageError::unsupported_alignment(layout))
+    } else if layout.size() > mem::size_of::<Storage>() {
+        Err(StorageError::too_large(layout))
     } else {
-        Err(AllocError)
+        Ok(())
     }
 }
 
+/// Computes the layout of an array of `capacity` elements of `T`.
+///
+/// Return `Err` if the size of the array overflows.
+pub fn array_layout<T>(capacity: usize) -> Result<Layout, StorageError> {
+    Layout::array::<T>(capacity).map_err(|_| StorageError::capacity_overflow())
+}
+
 #[cfg(test)]
 mod test {
 